@@ -0,0 +1,42 @@
+; Closures capture their environment, dynamic forms see their callers.
+
+(defndynamic show (&rest values) (println (join (to-strings values) " ")))
+
+(defndynamic to-strings (values)
+  (if (= (length values) 0)
+      (list)
+      (cons (to-string (first values)) (to-strings (rest values)))))
+
+(defndynamic make-counter ()
+  (let (count 0) (fn () (set! count (+ count 1)) count)))
+
+(def counter (make-counter))
+(counter)
+(counter)
+(show (counter))
+
+(def add (fn (x) (fn (y) (+ x y))))
+(show ((add 3) 4))
+(show (funcall (add 10) 5))
+(show (apply (fn (a b c) (+ a b c)) (list 1 2 3)))
+
+; `scale` isn't an argument of `multiply`, it comes from its caller.
+(defndynamic multiply (x) (* x scale))
+(defndynamic scale-all (scale values)
+  (let (result (list))
+    (while (not (= (length values) 0))
+      (set! result (append result (multiply (first values))))
+      (set! values (rest values)))
+    result))
+
+(show (scale-all 3 (list 1 2 3)))
+
+; A callee mutating a variable of its caller.
+(defndynamic bump () (set! total (+ total 1)))
+(defndynamic bump-twice (total) (bump) (bump) total)
+
+(show (bump-twice 40))
+
+(def compose (fn (f g) (fn (x) (f (g x)))))
+(def inc-then-double (compose (fn (x) (* x 2)) (fn (x) (+ x 1))))
+(show (inc-then-double 5))
@@ -0,0 +1,52 @@
+; Conditions, loops and pattern matching.
+
+(defndynamic show (&rest values) (println (join (to-strings values) " ")))
+
+(defndynamic to-strings (values)
+  (if (= (length values) 0)
+      (list)
+      (cons (to-string (first values)) (to-strings (rest values)))))
+
+(defndynamic classify (n)
+  (cond ((< n 0) :negative) ((= n 0) :zero) ((< n 10) :small) (else :large)))
+
+(show (list (classify -5) (classify 0) (classify 7) (classify 100)))
+
+(defndynamic day-kind (day)
+  (case day ((:saturday :sunday) :weekend) (else :weekday)))
+
+(show (day-kind :sunday) (day-kind :monday))
+
+(defndynamic first-multiple (n limit)
+  (let (i 1 found nil)
+    (while (<= i limit)
+      (when (= (mod i n) 0) (set! found i) (break))
+      (set! i (+ i 1)))
+    found))
+
+(show (first-multiple 7 100))
+(show (first-multiple 200 100))
+
+(def grid (list))
+(let (y 0)
+  (while (< y 3)
+    (let (x 0 row (list))
+      (while true
+        (when (>= x 3) (break))
+        (set! row (append row (* x y)))
+        (set! x (+ x 1)))
+      (set! grid (append grid row)))
+    (set! y (+ y 1))))
+
+(show grid)
+
+(show (and true (or false true) (not false)))
+(show (unless false :ran) (when false :skipped) (if false 1))
+
+(defndynamic describe (value)
+  (match value
+    ((x y) (list :pair x y))
+    (n :when (is-integer? n) (list :integer n))
+    (_ :other)))
+
+(show (describe (list 1 2)) (describe 5) (describe "text"))
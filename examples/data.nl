@@ -0,0 +1,37 @@
+; Collections and strings.
+
+(defndynamic show (&rest values) (println (join (to-strings values) " ")))
+
+(defndynamic to-strings (values)
+  (if (= (length values) 0)
+      (list)
+      (cons (to-string (first values)) (to-strings (rest values)))))
+
+(def inventory {:apples 3 :pears 5})
+(def inventory (assoc inventory :plums (+ 1 1)))
+(show (get inventory :plums) (keys inventory) (vals inventory))
+
+(let (key :cherries)
+  (show {key (* 2 3)
+         :total (+ (get inventory :apples) (get inventory :pears))}))
+
+(def seen #{1 2 3})
+(show (contains? seen 2) (union seen #{(+ 2 2)}))
+
+(defndynamic squares (n)
+  (let (v (vector) i 0)
+    (while (< i n) (set! v (vector-push v (* i i))) (set! i (+ i 1)))
+    (vector->list v)))
+
+(show (squares 6))
+(show (sort (list 5 3 9 1)) (reverse (list 1 2 3)) (nth (list :a :b :c) 1))
+
+(defndynamic shout (words) (join (map-words words) " "))
+
+(defndynamic map-words (words)
+  (if (= (length words) 0)
+      (list)
+      (cons (upcase (first words)) (map-words (rest words)))))
+
+(show (shout (split "hello bytecode world" " ")))
+(show (string-append "a" (to-string 1) "b") (string-length "neolisp"))
@@ -0,0 +1,30 @@
+; Errors raised then caught.
+
+(defndynamic show (&rest values) (println (join (to-strings values) " ")))
+
+(defndynamic to-strings (values)
+  (if (= (length values) 0)
+      (list)
+      (cons (to-string (first values)) (to-strings (rest values)))))
+
+(defndynamic safe-div (a b)
+  (try
+    (if (= b 0) (throw :division-by-zero a) (/ a b))
+    (catch :division-by-zero e (list :failed (error-value e)))))
+
+(show (safe-div 10 2) (safe-div 1 0))
+
+(defndynamic checked (x) (try (+ x 1) (catch e (error-code e))))
+
+(show (checked 1) (checked "one"))
+
+(def cleaned false)
+(try
+  (undefined-form 1)
+  (catch e (show (error-kind e) (error-message e)))
+  (finally (set! cleaned true)))
+(show cleaned)
+
+(defndynamic depth (n) (if (= n 0) (throw :bottom) (depth (- n 1))))
+
+(try (depth 5) (catch :bottom e (show (length (error-frames e)))))
@@ -0,0 +1,21 @@
+; Naive and iterative Fibonacci numbers.
+
+(defndynamic show (&rest values) (println (join (to-strings values) " ")))
+
+(defndynamic to-strings (values)
+  (if (= (length values) 0)
+      (list)
+      (cons (to-string (first values)) (to-strings (rest values)))))
+
+(defndynamic fib (n) (if (< n 2) n (+ (fib (- n 1)) (fib (- n 2)))))
+
+(defndynamic fib-iter (n)
+  (let (a 0 b 1 i 0)
+    (while (< i n)
+      (let (next (+ a b)) (set! a b) (set! b next))
+      (set! i (+ i 1)))
+    a))
+
+(show (fib 20))
+(show (fib-iter 90))
+(show (= (fib 15) (fib-iter 15)))
@@ -0,0 +1,22 @@
+; Macros are expanded by the interpreter in both modes.
+
+(defndynamic show (&rest values) (println (join (to-strings values) " ")))
+
+(defndynamic to-strings (values)
+  (if (= (length values) 0)
+      (list)
+      (cons (to-string (first values)) (to-strings (rest values)))))
+
+(defmacro unless-zero (x body) `(if (= ,x 0) nil ,body))
+
+(defmacro repeat (n body) `(let (i 0) (while (< i ,n) ,body (set! i (+ i 1)))))
+
+(def total 0)
+(repeat 4 (set! total (+ total i)))
+(show total)
+(show (unless-zero 0 :never) (unless-zero 1 :always))
+
+(defndynamic sum-to (n) (let (sum 0) (repeat (+ n 1) (set! sum (+ sum i))) sum))
+
+(show (sum-to 10))
+(show (macroexpand '(unless-zero a b)))
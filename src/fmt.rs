@@ -0,0 +1,117 @@
+use neolisp::nl::core::diagnostic::*;
+use neolisp::nl::formatter::*;
+
+use super::{report_error, ErrorFormat, EXIT_ERROR, EXIT_USAGE, USAGE};
+
+use std::fs;
+use std::io::{self, Read};
+
+/// Name of the standard input in the reported errors.
+const STDIN_NAME: &str = "<stdin>";
+
+/// `neolisp fmt [--check] [--width=<n>] [files...]`
+///
+/// Formats the files in place, or the standard input to the standard
+/// output. With `--check`, nothing is written : the files which aren't
+/// formatted are listed, and the exit code is 1 if there are any.
+///
+pub fn fmt(args: &[String]) -> i32 {
+    let mut formatter = Formatter::new();
+    let mut check = false;
+    let mut files = Vec::new();
+
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check = true,
+            v if v.starts_with("--width=") => match v["--width=".len()..].parse() {
+                Ok(width) if width > 0 => formatter = formatter.with_width(width),
+                _ => return usage(),
+            },
+            v if v.starts_with("--") => return usage(),
+            v => files.push(v),
+        }
+    }
+
+    if files.is_empty() {
+        return format_stdin(&formatter, check);
+    }
+
+    let mut code = 0;
+
+    for file in files {
+        let source = match fs::read_to_string(file) {
+            Ok(v) => v,
+            Err(err) => {
+                eprintln!("Can't read '{}' : {}", file, err);
+                code = EXIT_ERROR;
+                continue;
+            }
+        };
+
+        let formatted = match format(&formatter, file, &source) {
+            Some(v) => v,
+            None => {
+                code = EXIT_ERROR;
+                continue;
+            }
+        };
+
+        if formatted == source {
+            continue;
+        }
+
+        if check {
+            println!("'{}' isn't formatted.", file);
+            code = EXIT_ERROR;
+        } else if let Err(err) = fs::write(file, formatted) {
+            eprintln!("Can't write '{}' : {}", file, err);
+            code = EXIT_ERROR;
+        }
+    }
+
+    code
+}
+
+/// Formats the standard input to the standard output, or checks it.
+///
+fn format_stdin(formatter: &Formatter, check: bool) -> i32 {
+    let mut source = String::new();
+
+    if let Err(err) = io::stdin().read_to_string(&mut source) {
+        eprintln!("Can't read the standard input : {}", err);
+        return EXIT_ERROR;
+    }
+
+    match format(formatter, STDIN_NAME, &source) {
+        Some(v) if check && v != source => {
+            println!("'{}' isn't formatted.", STDIN_NAME);
+            EXIT_ERROR
+        }
+        Some(_) if check => 0,
+        Some(v) => {
+            print!("{}", v);
+            0
+        }
+        None => EXIT_ERROR,
+    }
+}
+
+/// Formats a source, reporting its syntax error if any.
+///
+fn format(formatter: &Formatter, file: &str, source: &str) -> Option<String> {
+    match formatter.format(file, source) {
+        Ok(v) => Some(v),
+        Err(err) => {
+            let mut sources = SourceMap::new();
+            sources.insert(file, source);
+
+            report_error(&sources, &err, ErrorFormat::Human);
+            None
+        }
+    }
+}
+
+fn usage() -> i32 {
+    eprintln!("{}", USAGE);
+    EXIT_USAGE
+}
@@ -0,0 +1,235 @@
+use neolisp::nl::core::object::*;
+use neolisp::nl::interpreter::*;
+
+use serde_json::{json, Value};
+
+/// Forms defining a global name, whose definitions are indexed.
+///
+const DEFINITION_FORMS: [&str; 4] = ["def", "defconst", "defndynamic", "defmacro"];
+
+/// An open source file.
+///
+pub struct Document {
+    pub text: String,
+    /// Text the objects were read from : the last text without a syntax
+    /// error. The spans of the objects and of the definitions refer to it.
+    pub read_text: String,
+    /// Objects read from the text, or from the previous text if it has a
+    /// syntax error.
+    pub objects: Vec<Object>,
+    /// Syntax error of the text, if any.
+    pub error: Option<Error>,
+    pub definitions: Vec<Definition>,
+}
+
+/// A global name defined in a document.
+///
+pub struct Definition {
+    pub name: String,
+    /// Name of the defining form (`def`, `defndynamic`...).
+    pub form: String,
+    /// Arguments of the custom forms, separated by spaces.
+    pub arguments: Option<String>,
+    /// Span of the name, in bytes.
+    pub name_span: (usize, usize),
+    /// Span of the whole definition, in bytes.
+    pub span: (usize, usize),
+}
+
+impl Document {
+    /// Reads a document, then indexes its definitions.
+    ///
+    /// If the text has a syntax error, the objects and the definitions of
+    /// the previous version of the document are kept.
+    ///
+    pub fn new(uri: &str, text: String, previous: Option<Document>) -> Document {
+        let objects = match read_source(uri, &text) {
+            Ok(v) => v,
+            Err(err) => {
+                let (read_text, objects, definitions) = match previous {
+                    Some(v) => (v.read_text, v.objects, v.definitions),
+                    None => (text.clone(), Vec::new(), Vec::new()),
+                };
+
+                return Document {
+                    text,
+                    read_text,
+                    objects,
+                    error: Some(err),
+                    definitions,
+                };
+            }
+        };
+
+        let mut definitions = Vec::new();
+
+        for object in &objects {
+            collect_definitions(object, &mut definitions);
+        }
+
+        Document {
+            read_text: text.clone(),
+            text,
+            objects,
+            error: None,
+            definitions,
+        }
+    }
+
+    /// Finds the definition of a name.
+    ///
+    pub fn definition(&self, name: &str) -> Option<&Definition> {
+        self.definitions.iter().find(|x| x.name == name)
+    }
+
+    /// Finds the symbol under a byte offset. A cursor placed right after a
+    /// symbol is on it.
+    ///
+    pub fn symbol_at(&self, offset: usize) -> Option<&str> {
+        self.objects.iter().find_map(|x| find_symbol(x, offset))
+    }
+
+    /// Converts a byte span of the objects into an LSP range.
+    ///
+    pub fn range(&self, span: (usize, usize)) -> Value {
+        range(&self.read_text, span)
+    }
+
+    /// Converts a byte span of the current text, such as the one of its
+    /// syntax error, into an LSP range.
+    ///
+    pub fn text_range(&self, span: (usize, usize)) -> Value {
+        range(&self.text, span)
+    }
+
+    /// Converts an LSP position into a byte offset in the text the objects
+    /// were read from.
+    ///
+    pub fn offset(&self, position: &Value) -> Option<usize> {
+        let line = position["line"].as_u64()? as usize;
+        let character = position["character"].as_u64()? as usize;
+
+        let mut line_start = 0;
+
+        for _ in 0..line {
+            line_start += self.read_text[line_start..].find('\n')? + 1;
+        }
+
+        let mut units = 0;
+
+        for (i, c) in self.read_text[line_start..].char_indices() {
+            if units >= character || c == '\n' {
+                return Some(line_start + i);
+            }
+
+            units += c.len_utf16();
+        }
+
+        Some(self.read_text.len())
+    }
+}
+
+impl Definition {
+    /// Gets the signature of the definition, e.g. `(name a b)`.
+    ///
+    pub fn signature(&self) -> String {
+        match &self.arguments {
+            Some(v) if v.is_empty() => format!("({})", self.name),
+            Some(v) => format!("({} {})", self.name, v),
+            None => self.name.clone(),
+        }
+    }
+}
+
+/// Converts a byte offset of a text into an LSP position, whose character
+/// is counted in UTF-16 code units.
+///
+fn position(text: &str, offset: usize) -> Value {
+    let offset = offset.min(text.len());
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |x| x + 1);
+
+    json!({
+        "line": before.matches('\n').count(),
+        "character": before[line_start..].encode_utf16().count(),
+    })
+}
+
+/// Converts a byte span of a text into an LSP range.
+///
+fn range(text: &str, span: (usize, usize)) -> Value {
+    json!({
+        "start": position(text, span.0),
+        "end": position(text, span.1),
+    })
+}
+
+/// Gets the span of an object read from a source, in bytes.
+///
+pub fn span(object: &Object) -> Option<(usize, usize)> {
+    object
+        .get_info()
+        .location
+        .as_direct()
+        .map(|x| (x.index, x.index + x.length))
+}
+
+/// Collects the definitions of an object and of the objects it contains.
+///
+fn collect_definitions(object: &Object, definitions: &mut Vec<Definition>) {
+    let list = match object {
+        Object::List(_, v) | Object::Vector(_, v) => v,
+        _ => return,
+    };
+
+    if let (true, [Object::Symbol(_, form), name @ Object::Symbol(_, n), rest @ ..]) =
+        (object.is_list(), &list[..])
+    {
+        if DEFINITION_FORMS.contains(&form.as_str()) {
+            let arguments = match (form.as_str(), rest.first()) {
+                ("defndynamic" | "defmacro", Some(Object::List(_, v))) => Some(
+                    v.iter()
+                        .map(|x| format!("{}", x))
+                        .collect::<Vec<String>>()
+                        .join(" "),
+                ),
+                ("defndynamic" | "defmacro", Some(Object::Nil(_))) => Some(String::new()),
+                _ => None,
+            };
+
+            if let (Some(name_span), Some(span)) = (span(name), span(object)) {
+                definitions.push(Definition {
+                    name: n.clone(),
+                    form: form.clone(),
+                    arguments,
+                    name_span,
+                    span,
+                });
+            }
+        }
+    }
+
+    for x in list {
+        collect_definitions(x, definitions);
+    }
+}
+
+/// Finds the symbol under a byte offset in an object.
+///
+fn find_symbol(object: &Object, offset: usize) -> Option<&str> {
+    let (start, end) = span(object)?;
+
+    if offset < start || offset > end {
+        return None;
+    }
+
+    match object {
+        Object::Symbol(_, v) => Some(v),
+        Object::List(_, v) | Object::Vector(_, v) => v.iter().find_map(|x| find_symbol(x, offset)),
+        Object::Map(_, v) => v.iter().find_map(|(key, value)| {
+            find_symbol(key, offset).or_else(|| find_symbol(value, offset))
+        }),
+        Object::Set(_, v) => v.iter().find_map(|x| find_symbol(x, offset)),
+        _ => None,
+    }
+}
@@ -0,0 +1,427 @@
+mod document;
+mod transport;
+
+use document::*;
+use transport::*;
+
+use neolisp::nl::interpreter::builtin::register_all_builtin_forms;
+use neolisp::nl::interpreter::*;
+
+use serde_json::{json, Value};
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, BufReader};
+
+/// JSON-RPC error code of a message which isn't valid JSON.
+const PARSE_ERROR: i64 = -32700;
+/// JSON-RPC error code of an unknown method.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// JSON-RPC error code of invalid parameters.
+const INVALID_PARAMS: i64 = -32602;
+
+/// LSP `TextDocumentSyncKind.Full` : the client sends the whole text of a
+/// document on every change.
+const SYNC_FULL: i64 = 1;
+
+/// Error of a request : a JSON-RPC error code and a message.
+///
+type ResponseError = (i64, String);
+
+/// State of the language server.
+///
+struct Server {
+    /// Open documents, by URI.
+    documents: HashMap<String, Document>,
+    /// Kinds of the builtin bindings, by name.
+    builtins: BTreeMap<String, &'static str>,
+    /// Whether the `shutdown` request was received.
+    shutdown: bool,
+}
+
+/// Outcome of a message sent to the server.
+///
+enum Outcome {
+    /// Continues reading messages.
+    Continue,
+    /// Exits with a code.
+    Exit(i32),
+}
+
+/// `neolisp lsp`
+///
+/// Speaks the Language Server Protocol over the standard input and output,
+/// until the client sends `exit`.
+///
+pub fn lsp() -> i32 {
+    let mut input = BufReader::new(io::stdin().lock());
+    let mut output = io::stdout().lock();
+    let mut server = Server::new();
+
+    loop {
+        let content = match read_message(&mut input) {
+            Ok(Some(v)) => v,
+            Ok(None) => return 1,
+            Err(err) => {
+                eprintln!("neolisp lsp : {}", err);
+                return 1;
+            }
+        };
+
+        let mut replies = Vec::new();
+
+        // The id of a message which can't be parsed is unknown.
+        match serde_json::from_slice::<Value>(&content) {
+            Ok(message) => {
+                if let Outcome::Exit(code) = server.handle(&message, &mut replies) {
+                    return code;
+                }
+            }
+            Err(err) => replies.push(json!({
+                "jsonrpc": "2.0",
+                "id": Value::Null,
+                "error": { "code": PARSE_ERROR, "message": err.to_string() },
+            })),
+        }
+
+        for reply in replies {
+            if let Err(err) = write_message(&mut output, &reply) {
+                eprintln!("neolisp lsp : {}", err);
+                return 1;
+            }
+        }
+    }
+}
+
+impl Server {
+    fn new() -> Server {
+        let mut scope = Scope::new();
+        register_all_builtin_forms(&mut scope);
+
+        let builtins = scope
+            .visible_bindings()
+            .into_iter()
+            .map(|(name, binding)| (name, binding.kind_string()))
+            .collect();
+
+        Server {
+            documents: HashMap::new(),
+            builtins,
+            shutdown: false,
+        }
+    }
+
+    /// Handles a request or a notification, pushing the messages to send
+    /// back.
+    ///
+    fn handle(&mut self, message: &Value, replies: &mut Vec<Value>) -> Outcome {
+        let method = match message["method"].as_str() {
+            Some(v) => v,
+            // Responses of the client, the server doesn't send requests.
+            None => return Outcome::Continue,
+        };
+        let params = &message["params"];
+
+        let id = match message.get("id") {
+            Some(v) => v.clone(),
+            None => return self.notify(method, params, replies),
+        };
+
+        let result = match method {
+            "initialize" => Ok(initialize_result()),
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/definition" => self.definition(params),
+            "textDocument/hover" => self.hover(params),
+            "textDocument/documentSymbol" => self.document_symbol(params),
+            "textDocument/completion" => self.completion(params),
+            _ => Err((METHOD_NOT_FOUND, format!("unknown method '{}'.", method))),
+        };
+
+        replies.push(match result {
+            Ok(v) => json!({ "jsonrpc": "2.0", "id": id, "result": v }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            }),
+        });
+
+        Outcome::Continue
+    }
+
+    /// Handles a notification. The unknown ones are ignored.
+    ///
+    fn notify(&mut self, method: &str, params: &Value, replies: &mut Vec<Value>) -> Outcome {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or("");
+
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or("");
+                self.update(uri, text, replies);
+            }
+            "textDocument/didChange" => {
+                let changes = params["contentChanges"].as_array();
+
+                if let Some(text) = changes
+                    .and_then(|x| x.last())
+                    .and_then(|x| x["text"].as_str())
+                {
+                    self.update(uri, text, replies);
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+                replies.push(publish_diagnostics(uri, Vec::new()));
+            }
+            "exit" => return Outcome::Exit(if self.shutdown { 0 } else { 1 }),
+            _ => (),
+        }
+
+        Outcome::Continue
+    }
+
+    /// Reads the new text of a document, then publishes its diagnostics.
+    ///
+    fn update(&mut self, uri: &str, text: &str, replies: &mut Vec<Value>) {
+        let previous = self.documents.remove(uri);
+        let document = Document::new(uri, text.to_string(), previous);
+        let diagnostics = match &document.error {
+            Some(err) => vec![diagnostic(&document, err)],
+            None => Vec::new(),
+        };
+
+        self.documents.insert(uri.to_string(), document);
+        replies.push(publish_diagnostics(uri, diagnostics));
+    }
+
+    /// Gets the URI and the document of a request, with the symbol at the
+    /// position of the request.
+    ///
+    fn symbol_at(
+        &self,
+        params: &Value,
+    ) -> Result<Option<(&str, &Document, String)>, ResponseError> {
+        let (uri, document) = self.document(params)?;
+
+        Ok(document
+            .offset(&params["position"])
+            .and_then(|x| document.symbol_at(x))
+            .map(|x| (uri, document, x.to_string())))
+    }
+
+    /// Gets the URI and the document of a request.
+    ///
+    fn document(&self, params: &Value) -> Result<(&str, &Document), ResponseError> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or("");
+
+        match self.documents.get_key_value(uri) {
+            Some((uri, document)) => Ok((uri, document)),
+            None => Err((INVALID_PARAMS, format!("document '{}' isn't open.", uri))),
+        }
+    }
+
+    /// Finds the definition of a name, in a document first, then in the
+    /// other open documents.
+    ///
+    fn find_definition<'s>(
+        &'s self,
+        uri: &'s str,
+        document: &'s Document,
+        name: &str,
+    ) -> Option<(&'s str, &'s Document, &'s Definition)> {
+        if let Some(v) = document.definition(name) {
+            return Some((uri, document, v));
+        }
+
+        self.documents
+            .iter()
+            .find_map(|(uri, x)| x.definition(name).map(|v| (uri.as_str(), x, v)))
+    }
+
+    /// `textDocument/definition`
+    ///
+    fn definition(&self, params: &Value) -> Result<Value, ResponseError> {
+        let (uri, document, name) = match self.symbol_at(params)? {
+            Some(v) => v,
+            None => return Ok(Value::Null),
+        };
+
+        Ok(match self.find_definition(uri, document, &name) {
+            Some((uri, document, definition)) => json!({
+                "uri": uri,
+                "range": document.range(definition.name_span),
+            }),
+            None => Value::Null,
+        })
+    }
+
+    /// `textDocument/hover`
+    ///
+    fn hover(&self, params: &Value) -> Result<Value, ResponseError> {
+        let (uri, document, name) = match self.symbol_at(params)? {
+            Some(v) => v,
+            None => return Ok(Value::Null),
+        };
+
+        let text = match self.find_definition(uri, document, &name) {
+            Some((_, _, definition)) => format!(
+                "```neolisp\n{}\n```\nDefined with `{}`.",
+                definition.signature(),
+                definition.form
+            ),
+            None => match self.builtins.get(&name) {
+                Some(kind) => format!(
+                    "```neolisp\n{}\n```\nBuiltin {}.",
+                    name,
+                    describe_kind(kind)
+                ),
+                None => return Ok(Value::Null),
+            },
+        };
+
+        Ok(json!({
+            "contents": { "kind": "markdown", "value": text },
+        }))
+    }
+
+    /// `textDocument/documentSymbol`
+    ///
+    fn document_symbol(&self, params: &Value) -> Result<Value, ResponseError> {
+        let (_, document) = self.document(params)?;
+
+        let symbols: Vec<Value> = document
+            .definitions
+            .iter()
+            .map(|x| {
+                json!({
+                    "name": x.name,
+                    "detail": x.signature(),
+                    "kind": symbol_kind(&x.form),
+                    "range": document.range(x.span),
+                    "selectionRange": document.range(x.name_span),
+                })
+            })
+            .collect();
+
+        Ok(json!(symbols))
+    }
+
+    /// `textDocument/completion`
+    ///
+    /// Offers the builtin names, then the definitions of the document.
+    ///
+    fn completion(&self, params: &Value) -> Result<Value, ResponseError> {
+        let (_, document) = self.document(params)?;
+
+        let builtins = self.builtins.iter().map(|(name, kind)| {
+            json!({
+                "label": name,
+                "kind": builtin_completion_kind(kind),
+                "detail": format!("builtin {}", describe_kind(kind)),
+            })
+        });
+
+        let definitions = document.definitions.iter().map(|x| {
+            json!({
+                "label": x.name,
+                "kind": definition_completion_kind(&x.form),
+                "detail": x.signature(),
+            })
+        });
+
+        Ok(json!(builtins.chain(definitions).collect::<Vec<Value>>()))
+    }
+}
+
+/// Result of the `initialize` request, with the capabilities of the server.
+///
+fn initialize_result() -> Value {
+    json!({
+        "capabilities": {
+            "textDocumentSync": SYNC_FULL,
+            "definitionProvider": true,
+            "hoverProvider": true,
+            "documentSymbolProvider": true,
+            "completionProvider": {},
+        },
+        "serverInfo": {
+            "name": "neolisp",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+    })
+}
+
+/// `textDocument/publishDiagnostics` notification.
+///
+fn publish_diagnostics(uri: &str, diagnostics: Vec<Value>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/publishDiagnostics",
+        "params": { "uri": uri, "diagnostics": diagnostics },
+    })
+}
+
+/// Converts an error into a diagnostic, located at its innermost span in
+/// the document.
+///
+fn diagnostic(document: &Document, error: &Error) -> Value {
+    let span = error
+        .frames()
+        .iter()
+        .find_map(|x| x.location.as_direct())
+        .map_or((0, 0), |x| (x.index, x.index + x.length));
+
+    json!({
+        "range": document.text_range(span),
+        "severity": 1,
+        "code": error.code(),
+        "source": "neolisp",
+        "message": error.message(),
+    })
+}
+
+/// Describes the kind of a builtin binding.
+///
+fn describe_kind(kind: &str) -> &'static str {
+    match kind {
+        "SpecialForm" => "special form",
+        "EvalForm" => "eval form",
+        "DynamicForm" => "dynamic form",
+        "MacroForm" => "macro",
+        "ConstantVariable" => "constant",
+        _ => "variable",
+    }
+}
+
+/// LSP `SymbolKind` of a definition.
+///
+fn symbol_kind(form: &str) -> i64 {
+    match form {
+        "defndynamic" | "defmacro" => 12,
+        "defconst" => 14,
+        _ => 13,
+    }
+}
+
+/// LSP `CompletionItemKind` of a builtin binding.
+///
+fn builtin_completion_kind(kind: &str) -> i64 {
+    match kind {
+        "SpecialForm" | "MacroForm" => 14,
+        "EvalForm" | "DynamicForm" => 3,
+        "ConstantVariable" => 21,
+        _ => 6,
+    }
+}
+
+/// LSP `CompletionItemKind` of a definition.
+///
+fn definition_completion_kind(form: &str) -> i64 {
+    match form {
+        "defndynamic" | "defmacro" => 3,
+        "defconst" => 21,
+        _ => 6,
+    }
+}
@@ -0,0 +1,61 @@
+use serde_json::Value;
+
+use std::io::{self, BufRead, Write};
+
+/// Reads the content of a message framed by a `Content-Length` header,
+/// without parsing it.
+///
+/// Returns `None` at the end of the input.
+///
+pub fn read_message(input: &mut impl BufRead) -> io::Result<Option<Vec<u8>>> {
+    let mut length = None;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        let header = line.trim_end();
+
+        if header.is_empty() {
+            break;
+        }
+
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+
+    let length = match length {
+        Some(v) => v,
+        None => return Err(invalid_data("missing Content-Length header")),
+    };
+
+    let mut content = vec![0; length];
+    input.read_exact(&mut content)?;
+
+    Ok(Some(content))
+}
+
+/// Writes a message with its `Content-Length` header.
+///
+pub fn write_message(output: &mut impl Write, message: &Value) -> io::Result<()> {
+    let content = message.to_string();
+
+    write!(
+        output,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )?;
+    output.flush()
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
@@ -1,50 +1,263 @@
-#[macro_use]
-extern crate peg;
+mod fmt;
+mod lsp;
+mod repl;
 
-mod nl;
+use neolisp::nl::core::diagnostic::*;
+use neolisp::nl::core::object::*;
+use neolisp::nl::interpreter::builtin::register_all_builtin_forms;
+use neolisp::nl::interpreter::bytecode::evaluate_source_compiled;
+use neolisp::nl::interpreter::*;
 
-use crate::nl::interpreter::builtin::register_all_builtin_forms;
-use nl::core::object::*;
-use nl::interpreter::*;
-use nl::reader::*;
+use std::env;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::process;
 
-use rustyline::error::ReadlineError;
-use rustyline::Editor;
+/// Exit code of a script raising an error (syntax or evaluation).
+const EXIT_ERROR: i32 = 1;
+/// Exit code of an invalid command line.
+const EXIT_USAGE: i32 = 2;
+
+/// Environment variable holding additional module directories.
+const PATH_VARIABLE: &str = "NEOLISP_PATH";
+
+const USAGE: &str = "Usage :
+  neolisp                                  Starts the REPL.
+  neolisp repl [args...]                   Starts the REPL.
+  neolisp run [options] <file> [args...]   Evaluates a source file.
+  neolisp eval [options] <expr> [args...]  Evaluates expressions and prints the last value.
+  neolisp fmt [options] [files...]         Formats source files in place, or the
+                                           standard input to the standard output.
+  neolisp lsp                              Starts a language server over stdio.
+
+Options :
+  --error-format=<human|json>  Format of the reported errors. The JSON format
+                               prints one object per error, on a single line.
+  --vm                         Compiles the source to bytecode, executed by a
+                               virtual machine instead of the interpreter.
+
+Options of 'fmt' :
+  --check      Lists the files which aren't formatted, without writing them.
+               Exits with 1 if there are any.
+  --width=<n>  Maximum width of the lines, 80 by default.
+
+The extra arguments are bound to '*args*' as a list of strings.
+
+Modules are searched in the directory of the file given to 'run', then in
+the directories of NEOLISP_PATH, then in the current directory.";
 
 fn main() {
-    repl();
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let code = match args.first().map(|x| x.as_str()) {
+        None => repl::repl(&[]),
+        Some("repl") => repl::repl(&args[1..]),
+        Some(command @ "run") | Some(command @ "eval") => match parse_options(&args[1..]) {
+            Some((options, [input, rest @ ..])) if command == "run" => run(input, rest, options),
+            Some((options, [input, rest @ ..])) => eval(input, rest, options),
+            _ => {
+                eprintln!("{}", USAGE);
+                EXIT_USAGE
+            }
+        },
+        Some("fmt") => fmt::fmt(&args[1..]),
+        Some("lsp") if args.len() == 1 => lsp::lsp(),
+        Some("help") | Some("--help") | Some("-h") => {
+            println!("{}", USAGE);
+            0
+        }
+        Some(_) => {
+            eprintln!("{}", USAGE);
+            EXIT_USAGE
+        }
+    };
+
+    process::exit(code);
+}
+
+/// Format of the errors reported by `run` and `eval`.
+///
+#[derive(Clone, Copy)]
+enum ErrorFormat {
+    /// Diagnostics with the source lines, for humans.
+    Human,
+    /// JSON objects, for tools.
+    Json,
 }
 
-fn repl() {
-    let mut rl = Editor::<()>::new();
+/// Options of `run` and `eval`.
+///
+#[derive(Clone, Copy)]
+struct Options {
+    error_format: ErrorFormat,
+    /// Whether the source is executed by the bytecode virtual machine.
+    vm: bool,
+}
+
+/// Parses the options preceding the input of `run` and `eval`, and returns
+/// the remaining arguments.
+///
+/// Returns `None` if an option is invalid.
+///
+fn parse_options(args: &[String]) -> Option<(Options, &[String])> {
+    let mut options = Options {
+        error_format: ErrorFormat::Human,
+        vm: false,
+    };
+    let mut rest = args;
+
+    while let Some(option) = rest.first().and_then(|x| x.strip_prefix("--")) {
+        match option {
+            "error-format=human" => options.error_format = ErrorFormat::Human,
+            "error-format=json" => options.error_format = ErrorFormat::Json,
+            "vm" => options.vm = true,
+            _ => return None,
+        }
+
+        rest = &rest[1..];
+    }
+
+    Some((options, rest))
+}
+
+/// Evaluates a source file, with the interpreter or the virtual machine.
+///
+fn evaluate_with(
+    scope: &mut Scope,
+    file: &str,
+    source: &str,
+    options: Options,
+) -> Result<Object, Error> {
+    if options.vm {
+        evaluate_source_compiled(scope, file, source)
+    } else {
+        evaluate_source(scope, file, source)
+    }
+}
+
+/// Creates the global scope, with the builtin forms and the `*args*` and
+/// `*load-path*` variables.
+///
+fn create_scope(args: &[String]) -> Scope {
     let mut scope = Scope::new();
     register_all_builtin_forms(&mut scope);
 
-    loop {
-        match rl.readline("> ") {
-            Ok(line) => evaluate_line(&mut scope, line),
-            Err(ReadlineError::Interrupted) => break,
-            Err(ReadlineError::Eof) => break,
-            Err(err) => {
-                println!("Error: {:?}", err);
-                break;
-            }
+    let args = args.iter().map(|x| Object::string(x.clone())).collect();
+    scope.insert_global(
+        "*args*".to_string(),
+        Binding::DynamicVariable(Object::list(args)),
+    );
+
+    let mut load_path: Vec<Object> = match env::var_os(PATH_VARIABLE) {
+        Some(v) => env::split_paths(&v)
+            .map(|x| Object::string(x.to_string_lossy().into_owned()))
+            .collect(),
+        None => Vec::new(),
+    };
+    load_path.push(Object::string(".".to_string()));
+
+    scope.insert_global(
+        LOAD_PATH_VARIABLE.to_string(),
+        Binding::DynamicVariable(Object::list(load_path)),
+    );
+
+    scope
+}
+
+/// Adds a directory at the start of the load path.
+///
+fn prepend_load_path(scope: &mut Scope, directory: &Path) {
+    let mut load_path = vec![Object::string(directory.to_string_lossy().into_owned())];
+
+    if let Some(Binding::DynamicVariable(v)) = scope.get_global_binding(LOAD_PATH_VARIABLE) {
+        load_path.extend(v.to_list());
+    }
+
+    scope.insert_global(
+        LOAD_PATH_VARIABLE.to_string(),
+        Binding::DynamicVariable(Object::list(load_path)),
+    );
+}
+
+/// `neolisp run <file> [args...]`
+///
+fn run(file: &str, args: &[String], options: Options) -> i32 {
+    let source = match fs::read_to_string(file) {
+        Ok(v) => v,
+        Err(err) => {
+            let error = Error::new_kind(
+                ErrorKind::Io {
+                    path: file.to_string(),
+                },
+                &format!("can't read '{}' : {}", file, err),
+            );
+
+            report_error(&SourceMap::new(), &error, options.error_format);
+            return EXIT_ERROR;
+        }
+    };
+
+    let mut scope = create_scope(args);
+
+    if let Some(directory) = Path::new(file).parent() {
+        let directory = if directory.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            directory
+        };
+
+        prepend_load_path(&mut scope, directory);
+    }
+
+    match evaluate_with(&mut scope, file, &source, options) {
+        Ok(_) => 0,
+        Err(err) => {
+            let mut sources = SourceMap::new();
+            sources.insert(file, &source);
+
+            report_error(&sources, &err, options.error_format);
+            EXIT_ERROR
         }
     }
 }
 
-fn evaluate_line(scope: &mut Scope, line: String) {
-    let parse_result = nl_parser::expr(&line);
+/// `neolisp eval <expr> [args...]`
+///
+fn eval(source: &str, args: &[String], options: Options) -> i32 {
+    let mut scope = create_scope(args);
 
-    match parse_result {
-        Ok(mut object) => {
-            object.complete_location(&"REPL".to_string(), &line);
+    match evaluate_with(&mut scope, "<eval>", source, options) {
+        Ok(result) => {
+            println!("{}", result);
+            0
+        }
+        Err(err) => {
+            let mut sources = SourceMap::new();
+            sources.insert("<eval>", source);
 
-            match evaluate(scope, &object) {
-                Ok(result) => println!("=> {}", result),
-                Err(err) => println!("{}", err.to_string()),
-            }
+            report_error(&sources, &err, options.error_format);
+            EXIT_ERROR
         }
-        Err(err) => println!("Parse error: {:?}", err),
     }
 }
+
+/// Prints an error to the standard error output, either with the source
+/// lines it refers to or as JSON.
+///
+fn report_error(sources: &SourceMap, error: &Error, format: ErrorFormat) {
+    match format {
+        ErrorFormat::Human => {
+            let renderer = Renderer::new(sources).with_color(use_color(&io::stderr()));
+            eprint!("{}", renderer.render(error));
+        }
+        ErrorFormat::Json => eprintln!("{}", error_to_json(error)),
+    }
+}
+
+/// Checks if the diagnostics written to a stream are colored : the stream
+/// must be a terminal, and the `NO_COLOR` environment variable unset.
+///
+fn use_color(stream: &impl IsTerminal) -> bool {
+    stream.is_terminal() && env::var_os("NO_COLOR").is_none()
+}
@@ -0,0 +1,408 @@
+use super::error::*;
+
+use serde_json::{json, Map, Value};
+
+use std::collections::HashMap;
+use std::fs;
+
+/// Maximum number of source lines shown for a span. The lines in the middle
+/// of longer spans are elided.
+///
+const MAX_SPAN_LINES: usize = 4;
+
+/// Number of columns a tabulation is rendered with.
+///
+const TAB_WIDTH: usize = 4;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const CYAN: &str = "\x1b[1;36m";
+
+/// Sources of the files referenced by the locations of errors.
+///
+/// Files which weren't inserted are read from the disk when rendering.
+///
+#[derive(Default)]
+pub struct SourceMap {
+    files: HashMap<String, String>,
+}
+
+impl SourceMap {
+    pub fn new() -> SourceMap {
+        SourceMap::default()
+    }
+
+    /// Inserts the source of a file, replacing the previous one if any.
+    ///
+    pub fn insert(&mut self, file: &str, source: &str) -> &mut Self {
+        self.files.insert(file.to_string(), source.to_string());
+        self
+    }
+
+    /// Gets the source of a file.
+    ///
+    pub fn get(&self, file: &str) -> Option<String> {
+        match self.files.get(file) {
+            Some(v) => Some(v.clone()),
+            None => fs::read_to_string(file).ok(),
+        }
+    }
+}
+
+/// Renders errors as diagnostics : the message, then every stack frame
+/// with the source lines of its location and the span underlined.
+///
+pub struct Renderer<'a> {
+    sources: &'a SourceMap,
+    color: bool,
+}
+
+/// A source line of a snippet, with the span to underline as byte offsets
+/// in the line.
+///
+struct SnippetLine<'s> {
+    number: usize,
+    text: &'s str,
+    from: usize,
+    to: usize,
+}
+
+impl<'a> Renderer<'a> {
+    pub fn new(sources: &'a SourceMap) -> Renderer<'a> {
+        Renderer {
+            sources,
+            color: false,
+        }
+    }
+
+    /// Enables the ANSI colors.
+    ///
+    pub fn with_color(mut self, color: bool) -> Self {
+        self.color = color;
+        self
+    }
+
+    /// Renders an error.
+    ///
+    pub fn render(&self, error: &Error) -> String {
+        let mut out = String::new();
+        let mut cache = HashMap::new();
+        let mut sites: Vec<&DirectLocation> = Vec::new();
+
+        out.push_str(&self.paint(RED, "error"));
+        out.push_str(&self.paint(BOLD, &format!(" : {}", error.message())));
+        out.push('\n');
+
+        let frames = error.frames().iter().filter(|x| !x.name.starts_with('#'));
+        let mut primary = true;
+
+        for frame in frames {
+            match &frame.location {
+                Location::Direct(v) => {
+                    let label = format!("in '{}'", frame.name);
+                    self.render_span(&mut out, &mut cache, v, &label, primary);
+                    primary = false;
+                }
+                Location::Expansion(v) => {
+                    // The macros expanded at the same call site share a
+                    // snippet, shown once for all the frames.
+                    for group in v.chunk_by(|a, b| a.localisation.same_span(&b.localisation)) {
+                        let site = &group[0].localisation;
+
+                        if sites.iter().any(|x| x.same_span(site)) {
+                            continue;
+                        }
+
+                        let names: Vec<String> = group
+                            .iter()
+                            .map(|x| format!("'{}'", x.macro_name))
+                            .collect();
+                        let label = format!(
+                            "in '{}', from the expansion of {}",
+                            frame.name,
+                            names.join(" within ")
+                        );
+
+                        self.render_span(&mut out, &mut cache, site, &label, primary);
+                        sites.push(site);
+                        primary = false;
+                    }
+                }
+                Location::Intern(v) => {
+                    out.push_str(&format!(
+                        "  {} '{}' {}\n",
+                        self.paint(BLUE, "="),
+                        frame.name,
+                        v
+                    ));
+                }
+                Location::None => {
+                    out.push_str(&format!("  {} '{}'\n", self.paint(BLUE, "="), frame.name));
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Renders the header of a location, then its source lines with the
+    /// span underlined and labelled.
+    ///
+    fn render_span(
+        &self,
+        out: &mut String,
+        cache: &mut HashMap<String, Option<String>>,
+        location: &DirectLocation,
+        label: &str,
+        primary: bool,
+    ) {
+        out.push_str(&format!(
+            "  {} {}:{}:{}\n",
+            self.paint(BLUE, "-->"),
+            location.file,
+            location.line,
+            location.column
+        ));
+
+        let source = cache
+            .entry(location.file.clone())
+            .or_insert_with(|| self.sources.get(&location.file));
+
+        let lines = match source {
+            Some(v) => snippet_lines(v, location),
+            None => Vec::new(),
+        };
+
+        let style = if primary { RED } else { CYAN };
+
+        if lines.is_empty() {
+            out.push_str(&format!("  {} {}\n", self.paint(BLUE, "="), label));
+            return;
+        }
+
+        let width = lines.last().unwrap().number.to_string().len();
+        let gutter = self.paint(BLUE, &format!("{} |", " ".repeat(width)));
+
+        out.push_str(&format!(" {}\n", gutter));
+
+        let elided = lines.len() > MAX_SPAN_LINES;
+
+        for (i, line) in lines.iter().enumerate() {
+            if elided && i >= MAX_SPAN_LINES - 1 && i + 1 < lines.len() {
+                if i == MAX_SPAN_LINES - 1 {
+                    out.push_str(&format!(" {}\n", self.paint(BLUE, "...")));
+                }
+
+                continue;
+            }
+
+            let number = self.paint(BLUE, &format!("{:>w$} |", line.number, w = width));
+            out.push_str(&format!(" {} {}\n", number, expand_tabs(line.text)));
+
+            let offset = display_width(&line.text[..line.from]);
+            let length = display_width(&line.text[line.from..line.to]).max(1);
+            let mut underline = format!("{}{}", " ".repeat(offset), "^".repeat(length));
+
+            if i + 1 == lines.len() {
+                underline.push(' ');
+                underline.push_str(label);
+            }
+
+            out.push_str(&format!(" {} {}\n", gutter, self.paint(style, &underline)));
+        }
+    }
+
+    /// Wraps a text with an ANSI style if the colors are enabled.
+    ///
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Converts an error into JSON, for the tools reading the errors.
+///
+/// ```json
+/// {
+///   "code": "E0005",
+///   "kind": "arity",
+///   "message": "...",
+///   "data": { "form": "cons", "min": 2, "max": 2, "actual": 1 },
+///   "frames": [{ "name": "cons", "location": { "type": "direct", ... } }]
+/// }
+/// ```
+///
+/// The frames are ordered from the innermost to the outermost.
+///
+pub fn error_to_json(error: &Error) -> Value {
+    let frames: Vec<Value> = error
+        .frames()
+        .iter()
+        .filter(|x| !x.name.starts_with('#'))
+        .map(|x| {
+            json!({
+                "name": x.name,
+                "location": location_to_json(&x.location),
+            })
+        })
+        .collect();
+
+    json!({
+        "code": error.code(),
+        "kind": error.kind().name(),
+        "message": error.message(),
+        "data": kind_to_json(error.kind()),
+        "frames": frames,
+    })
+}
+
+/// Converts the values of an error kind into JSON.
+///
+fn kind_to_json(kind: &ErrorKind) -> Value {
+    match kind {
+        ErrorKind::Syntax { expected, found } => json!({
+            "expected": expected,
+            "found": found.map(|x| x.to_string()),
+        }),
+        ErrorKind::UnboundSymbol { name } | ErrorKind::Constant { name } => json!({
+            "name": name,
+        }),
+        ErrorKind::Arity {
+            form,
+            min,
+            max,
+            actual,
+        } => json!({
+            "form": form,
+            "min": min,
+            "max": max,
+            "actual": actual,
+        }),
+        ErrorKind::Type { expected, actual } => json!({
+            "expected": expected,
+            "actual": actual,
+        }),
+        ErrorKind::Range { index, start, end } => json!({
+            "index": index,
+            "start": start,
+            "end": end,
+        }),
+        ErrorKind::Io { path } => json!({ "path": path }),
+        ErrorKind::Module { name } => json!({ "module": name }),
+        ErrorKind::User { tag, value } => json!({
+            "tag": tag,
+            "value": format!("{}", value),
+        }),
+        ErrorKind::Evaluation | ErrorKind::Form | ErrorKind::Arithmetic => {
+            json!({})
+        }
+    }
+}
+
+/// Converts a location into JSON.
+///
+fn location_to_json(location: &Location) -> Value {
+    match location {
+        Location::None => Value::Null,
+        Location::Intern(v) => json!({
+            "type": "intern",
+            "file": v.file,
+            "line": v.line,
+        }),
+        Location::Direct(v) => direct_location_to_json(("type", json!("direct")), v),
+        Location::Expansion(v) => {
+            let expansions: Vec<Value> = v
+                .iter()
+                .map(|x| direct_location_to_json(("macro", json!(x.macro_name)), &x.localisation))
+                .collect();
+
+            json!({
+                "type": "expansion",
+                "expansions": expansions,
+            })
+        }
+    }
+}
+
+/// Converts a direct location into JSON, after a first field. The index and
+/// the length of the span are in bytes.
+///
+fn direct_location_to_json(first: (&str, Value), location: &DirectLocation) -> Value {
+    let mut result = Map::new();
+    result.insert(first.0.to_string(), first.1);
+    result.insert("file".to_string(), json!(location.file));
+    result.insert("line".to_string(), json!(location.line));
+    result.insert("column".to_string(), json!(location.column));
+    result.insert("index".to_string(), json!(location.index));
+    result.insert("length".to_string(), json!(location.length));
+
+    Value::Object(result)
+}
+
+/// Gets the source lines covered by a location.
+///
+/// Returns nothing if the location doesn't fit in the source, for instance
+/// if the file changed since it was read.
+///
+fn snippet_lines<'s>(source: &'s str, location: &DirectLocation) -> Vec<SnippetLine<'s>> {
+    let start = location.index;
+    let end = start + location.length;
+
+    if end > source.len() || !source.is_char_boundary(start) || !source.is_char_boundary(end) {
+        return Vec::new();
+    }
+
+    let last = if end > start { end - 1 } else { start };
+    let mut result = Vec::new();
+    let mut line_start = 0;
+
+    for (i, text) in source.split('\n').enumerate() {
+        let line_end = line_start + text.len();
+
+        if line_start > last {
+            break;
+        }
+
+        if line_end >= start {
+            let text = text.trim_end_matches('\r');
+            let to = end.min(line_end).max(start.max(line_start)) - line_start;
+            let from = if start >= line_start {
+                start - line_start
+            } else {
+                // The indentation of the following lines isn't underlined.
+                let indentation = text.len() - text.trim_start().len();
+                indentation.min(to)
+            };
+
+            result.push(SnippetLine {
+                number: i + 1,
+                text,
+                from: from.min(text.len()),
+                to: to.min(text.len()),
+            });
+        }
+
+        line_start = line_end + 1;
+    }
+
+    result
+}
+
+/// Replaces the tabulations of a line by spaces.
+///
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+/// Gets the number of columns a text is rendered with.
+///
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|x| if x == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
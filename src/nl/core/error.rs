@@ -1,30 +1,229 @@
 pub use super::location::*;
 
+use crate::nl::core::object::Object;
+
+use std::fmt;
+
 pub struct Error {
+    kind: Box<ErrorKind>,
     message: String,
     frames: Vec<Frame>,
 }
 
-struct Frame {
-    name: String,
-    location: Location,
+/// Kind of an error, with the values describing it.
+///
+/// Every kind has a stable code, to be relied upon by tools instead of the
+/// message.
+///
+#[derive(Clone)]
+pub enum ErrorKind {
+    /// An evaluation error without a more specific kind.
+    Evaluation,
+    /// The reader failed to parse a source.
+    Syntax {
+        /// Descriptions of the tokens which were expected.
+        expected: Vec<String>,
+        /// The character found instead, or `None` at the end of the input.
+        found: Option<char>,
+    },
+    /// A special form was given arguments it can't make sense of.
+    Form,
+    /// A symbol isn't bound in the scope.
+    UnboundSymbol { name: String },
+    /// A constant was assigned or redefined.
+    Constant { name: String },
+    /// A form received a wrong number of arguments.
+    Arity {
+        form: String,
+        /// The minimum number of arguments.
+        min: usize,
+        /// The maximum number of arguments, or `None` if unbounded.
+        max: Option<usize>,
+        actual: usize,
+    },
+    /// A value doesn't have the expected type.
+    Type { expected: String, actual: String },
+    /// An index is out of the bounds of a collection.
+    Range {
+        index: i64,
+        /// The first valid index.
+        start: i64,
+        /// The index following the last valid one.
+        end: i64,
+    },
+    /// An arithmetic operation failed, e.g. a division by zero.
+    Arithmetic,
+    /// A file couldn't be read.
+    Io { path: String },
+    /// A module couldn't be found or loaded.
+    Module { name: String },
+    /// A value raised by the `throw` form, with a keyword tag.
+    User { tag: String, value: Object },
+}
+
+/// A stack frame of an error.
+///
+pub struct Frame {
+    pub name: String,
+    pub location: Location,
+}
+
+impl ErrorKind {
+    /// Creates a type error kind, the actual type being the one of the
+    /// given object.
+    ///
+    pub fn wrong_type(expected: &str, object: &Object) -> ErrorKind {
+        ErrorKind::Type {
+            expected: expected.to_string(),
+            actual: object.type_string().to_string(),
+        }
+    }
+
+    /// Creates an arity error kind.
+    ///
+    pub fn arity(form: &str, min: usize, max: Option<usize>, actual: usize) -> ErrorKind {
+        ErrorKind::Arity {
+            form: form.to_string(),
+            min,
+            max,
+            actual,
+        }
+    }
+
+    /// Creates a range error kind, the valid indexes being `start..end`.
+    ///
+    pub fn range(index: i64, start: usize, end: usize) -> ErrorKind {
+        ErrorKind::Range {
+            index,
+            start: start as i64,
+            end: end as i64,
+        }
+    }
+
+    /// Gets the stable code of the kind.
+    ///
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::Evaluation => "E0000",
+            Self::Syntax { .. } => "E0001",
+            Self::Form => "E0002",
+            Self::UnboundSymbol { .. } => "E0003",
+            Self::Constant { .. } => "E0004",
+            Self::Arity { .. } => "E0005",
+            Self::Type { .. } => "E0006",
+            Self::Range { .. } => "E0007",
+            Self::Arithmetic => "E0008",
+            Self::Io { .. } => "E0009",
+            Self::Module { .. } => "E0010",
+            Self::User { .. } => "E0011",
+        }
+    }
+
+    /// Gets the name of the kind, as used in keywords.
+    ///
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Evaluation => "evaluation",
+            Self::Syntax { .. } => "syntax",
+            Self::Form => "form",
+            Self::UnboundSymbol { .. } => "unbound-symbol",
+            Self::Constant { .. } => "constant",
+            Self::Arity { .. } => "arity",
+            Self::Type { .. } => "type",
+            Self::Range { .. } => "range",
+            Self::Arithmetic => "arithmetic",
+            Self::Io { .. } => "io",
+            Self::Module { .. } => "module",
+            Self::User { .. } => "user",
+        }
+    }
 }
 
 impl Error {
     /// Creates a new error.
     ///
     pub fn new(message: &str) -> Self {
+        Self::new_kind(ErrorKind::Evaluation, message)
+    }
+
+    /// Creates a new error of the given kind.
+    ///
+    pub fn new_kind(kind: ErrorKind, message: &str) -> Self {
         Self {
+            kind: Box::new(kind),
             message: message.to_string(),
             frames: Vec::new(),
         }
     }
 
+    /// Creates a new error raising a user value, with a keyword tag.
+    ///
+    pub fn new_thrown(tag: &str, value: Object) -> Self {
+        let message = format!("uncaught throw :{} {}", tag, value);
+        let kind = ErrorKind::User {
+            tag: tag.to_string(),
+            value,
+        };
+
+        Self::new_kind(kind, &message)
+    }
+
+    /// Gets the kind of the error.
+    ///
+    pub fn kind(&self) -> &ErrorKind {
+        &self.kind
+    }
+
+    /// Gets the stable code of the error.
+    ///
+    pub fn code(&self) -> &'static str {
+        self.kind.code()
+    }
+
+    /// Gets the message of the error.
+    ///
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Gets the stack frames of the error, from the innermost to the
+    /// outermost.
+    ///
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// Gets the keyword tag of the error, without the leading ':'.
+    /// Errors not raised by `throw` are tagged `error`.
+    ///
+    pub fn tag(&self) -> &str {
+        match self.kind() {
+            ErrorKind::User { tag, .. } => tag,
+            _ => "error",
+        }
+    }
+
+    /// Gets the value raised by `throw`, if any.
+    ///
+    pub fn value(&self) -> Option<&Object> {
+        match self.kind() {
+            ErrorKind::User { value, .. } => Some(value),
+            _ => None,
+        }
+    }
+
     /// Creates a new error an wraps it into a `Result::Err`.
     pub fn err<T>(message: &str) -> Result<T, Self> {
         Err(Self::new(message))
     }
 
+    /// Creates a new error of the given kind and wraps it into a
+    /// `Result::Err`.
+    ///
+    pub fn err_kind<T>(kind: ErrorKind, message: &str) -> Result<T, Self> {
+        Err(Self::new_kind(kind, message))
+    }
+
     pub fn newf(message: &str, name: &str, location: Location) -> Self {
         Self::new(message).push(name, location)
     }
@@ -57,23 +256,6 @@ impl Error {
         Err(self.push(name, location))
     }
 
-    /// Converts the error into a string.
-    ///
-    pub fn to_string(&self) -> String {
-        let mut res = String::new();
-
-        res.push_str(&format!("Evaluation error : {}\n\n", self.message));
-        res.push_str("Stacktrace :\n");
-
-        self.frames.iter().enumerate().fold(res, |r, (i, x)| {
-            if i == 0 {
-                r + &format!("  >>>> : '{}' {}\n", x.name, x.location)
-            } else {
-                r + &format!("  {:0>4} : '{}' {}\n", i, x.name, x.location)
-            }
-        })
-    }
-
     /// Converts the error into a string.
     ///
     pub fn to_string_light(&self) -> String {
@@ -95,3 +277,20 @@ impl Error {
             })
     }
 }
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Evaluation error : {}\n", self.message)?;
+        writeln!(f, "Stacktrace :")?;
+
+        for (i, x) in self.frames.iter().enumerate() {
+            if i == 0 {
+                writeln!(f, "  >>>> : '{}' {}", x.name, x.location)?;
+            } else {
+                writeln!(f, "  {:0>4} : '{}' {}", i, x.name, x.location)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@ use crate::nl::core::object::*;
 
 use std::fmt;
 use std::fmt::Display;
+use std::rc::Rc;
 
 /// Intern location, used when the info comes from
 /// NeoLisp itself.
@@ -40,20 +41,21 @@ impl DirectLocation {
         }
     }
 
+    /// Checks if two locations point to the same span of the same file.
+    ///
+    pub fn same_span(&self, other: &DirectLocation) -> bool {
+        self.file == other.file && self.index == other.index && self.length == other.length
+    }
+
     /// Completes the direct location.
     ///
-    pub fn complete(&mut self, file: &String, source: &String) {
+    pub fn complete(&mut self, file: &str, source: &str) {
         let before = &source[0..self.index];
+        let line_start = before.rfind('\n').map_or(0, |x| x + 1);
 
-        let (last_newline, line) = before
-            .chars()
-            .enumerate()
-            .filter(|(_, x)| *x == '\n')
-            .fold((0usize, 0usize), |(_, count), (idx, _)| (idx, count + 1));
-
-        self.file = file.clone();
-        self.line = 1 + line;
-        self.column = 1 + (self.index - last_newline);
+        self.file = file.to_string();
+        self.line = 1 + before.matches('\n').count();
+        self.column = 1 + before[line_start..].chars().count();
     }
 }
 
@@ -63,7 +65,8 @@ impl DirectLocation {
 #[derive(Clone)]
 pub struct ExpansionLocation {
     pub macro_name: String,
-    pub form: Object,
+    /// Macro call, shared by the objects of its expansion.
+    pub form: Rc<Object>,
 
     pub localisation: DirectLocation,
 }
@@ -114,37 +117,25 @@ impl Location {
     /// Checks if there is no location info.
     ///
     pub fn is_none(&self) -> bool {
-        match self {
-            Self::None => true,
-            _ => false,
-        }
+        matches!(self, Self::None)
     }
 
     /// Checks if the location info comes from NeoLisp itself.
     ///
     pub fn is_intern(&self) -> bool {
-        match self {
-            Self::Intern(_) => true,
-            _ => false,
-        }
+        matches!(self, Self::Intern(_))
     }
 
     /// Checks if the location info comes from a source file.
     ///
     pub fn is_direct(&self) -> bool {
-        match self {
-            Self::Direct(_) => true,
-            _ => false,
-        }
+        matches!(self, Self::Direct(_))
     }
 
     /// Checks if the location info comes from a macro expansion.
     ///
     pub fn is_expansion(&self) -> bool {
-        match self {
-            Self::Expansion(_) => true,
-            _ => false,
-        }
+        matches!(self, Self::Expansion(_))
     }
 
     /// Gets the location as intern.
@@ -279,7 +270,7 @@ impl Display for Location {
                     write!(f, "{}", x)?;
 
                     if i + 1 < v.len() {
-                        write!(f, "\n")?;
+                        writeln!(f)?;
                     }
                 }
 
@@ -1,3 +1,4 @@
+pub mod diagnostic;
 pub mod error;
 #[macro_use]
 pub mod location;
@@ -0,0 +1,68 @@
+use super::*;
+
+use num_traits::{ToPrimitive, Zero};
+
+impl Object {
+    /// Creates a new integer object from an arbitrary-precision integer.
+    /// The result is an Integer if the value fits in 64 bits, a Bignum
+    /// otherwise.
+    ///
+    pub fn bignum(v: BigInt) -> Self {
+        match v.to_i64() {
+            Some(x) => Self::integer(x),
+            None => Self::Bignum(ObjectInfo::new_empty(), v),
+        }
+    }
+
+    /// Checks if the object is a bignum.
+    ///
+    pub fn is_bignum(&self) -> bool {
+        matches!(self, Self::Bignum(_, _))
+    }
+
+    /// Gets the arbitrary-precision integer of the object.
+    ///
+    pub fn as_bignum(&self) -> Option<&BigInt> {
+        match self {
+            Self::Bignum(_, v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Converts the object into an arbitrary-precision integer.
+    ///
+    pub fn to_bignum(&self) -> BigInt {
+        match self {
+            Self::Bignum(_, v) => v.clone(),
+            Self::Rational(_, v) => v.to_integer(),
+            _ => BigInt::from(self.to_integer()),
+        }
+    }
+
+    /// Gets the arbitrary-precision integer of the object.
+    /// Panics if the object isn't a bignum.
+    ///
+    pub fn get_bignum(&self) -> &BigInt {
+        self.as_bignum().expect("Expected an Object::Bignum.")
+    }
+}
+
+/// Converts a bignum to a float, saturating to the infinities.
+///
+pub(super) fn bignum_to_float(v: &BigInt) -> f64 {
+    v.to_f64().unwrap_or(if v < &BigInt::zero() {
+        f64::NEG_INFINITY
+    } else {
+        f64::INFINITY
+    })
+}
+
+/// Converts a bignum to an integer, saturating to the bounds of `i64`.
+///
+pub(super) fn bignum_to_integer(v: &BigInt) -> i64 {
+    v.to_i64().unwrap_or(if v < &BigInt::zero() {
+        i64::MIN
+    } else {
+        i64::MAX
+    })
+}
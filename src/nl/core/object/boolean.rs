@@ -10,10 +10,7 @@ impl Object {
     /// Checks if the object is a boolean.
     ///
     pub fn is_bool(&self) -> bool {
-        match self {
-            Self::Bool(_, _) => true,
-            _ => false,
-        }
+        matches!(self, Self::Bool(_, _))
     }
 
     /// Gets the boolean value of the object.
@@ -32,12 +29,18 @@ impl Object {
             Self::Nil(_) => false,
             Self::Bool(_, v) => *v,
             Self::Integer(_, v) => *v != 0,
+            Self::Bignum(_, _) => true,
+            Self::Rational(_, _) => true,
             Self::Float(_, v) => *v != 0.0,
             Self::Char(_, v) => *v != '\0',
             Self::String(_, v) => !v.is_empty(),
             Self::Keyword(_, _) => true,
             Self::Symbol(_, _) => true,
             Self::List(_, v) => !v.is_empty(),
+            Self::Vector(_, v) => !v.is_empty(),
+            Self::Map(_, v) => !v.is_empty(),
+            Self::Set(_, v) => !v.is_empty(),
+            Self::Function(_, _) => true,
         }
     }
 
@@ -12,10 +12,7 @@ impl Object {
     /// Checks if the object is a char.
     ///
     pub fn is_char(&self) -> bool {
-        match self {
-            Self::Char(_, _) => true,
-            _ => false,
-        }
+        matches!(self, Self::Char(_, _))
     }
 
     /// Gets the char in the object.
@@ -40,31 +37,33 @@ impl Object {
                 }
             }
             Self::Integer(_, v) => char::from_u32(*v as u32).unwrap_or('\0'),
+            Self::Bignum(_, _) | Self::Rational(_, _) => '\0',
             Self::Float(_, v) => char::from_u32(*v as u32).unwrap_or('\0'),
             Self::Char(_, v) => *v,
             Self::String(_, v) => {
-                if v.len() > 0 {
+                if !v.is_empty() {
                     v.chars().next().unwrap()
                 } else {
                     '\0'
                 }
             }
             Self::Keyword(_, v) => {
-                if v.len() > 0 {
+                if !v.is_empty() {
                     v.chars().next().unwrap()
                 } else {
                     '\0'
                 }
             }
             Self::Symbol(_, v) => {
-                if v.len() > 0 {
+                if !v.is_empty() {
                     v.chars().next().unwrap()
                 } else {
                     '\0'
                 }
             }
-            Self::List(_, v) => {
-                if v.len() > 0 {
+            Self::Function(_, _) => '\0',
+            Self::List(_, _) | Self::Vector(_, _) | Self::Map(_, _) | Self::Set(_, _) => {
+                if self.to_bool() {
                     1 as char
                 } else {
                     '\0'
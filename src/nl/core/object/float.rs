@@ -3,22 +3,19 @@ use super::*;
 impl Object {
     /// Creates a new float object.
     ///
-    pub fn float(v: f32) -> Self {
+    pub fn float(v: f64) -> Self {
         Self::Float(ObjectInfo::new_empty(), v)
     }
 
     /// Checks if the object is a float.
     ///
     pub fn is_float(&self) -> bool {
-        match self {
-            Self::Float(_, _) => true,
-            _ => false,
-        }
+        matches!(self, Self::Float(_, _))
     }
 
     /// Gets the float of the object.
     ///
-    pub fn as_float(&self) -> Option<f32> {
+    pub fn as_float(&self) -> Option<f64> {
         match self {
             Self::Float(_, v) => Some(*v),
             _ => None,
@@ -27,7 +24,7 @@ impl Object {
 
     /// Converts the object into a float.
     ///
-    pub fn to_float(&self) -> f32 {
+    pub fn to_float(&self) -> f64 {
         match self {
             Self::Nil(_) => 0.0,
             Self::Bool(_, v) => {
@@ -37,13 +34,19 @@ impl Object {
                     0.0
                 }
             }
-            Self::Integer(_, v) => *v as f32,
+            Self::Integer(_, v) => *v as f64,
+            Self::Bignum(_, v) => bignum_to_float(v),
+            Self::Rational(_, v) => rational_to_float(v),
             Self::Float(_, v) => *v,
-            Self::Char(_, v) => (*v as u32) as f32,
-            Self::String(_, v) => v.len() as f32,
-            Self::Keyword(_, v) => v.len() as f32,
-            Self::Symbol(_, v) => v.len() as f32,
-            Self::List(_, v) => v.len() as f32,
+            Self::Char(_, v) => (*v as u32) as f64,
+            Self::String(_, v) => v.len() as f64,
+            Self::Keyword(_, v) => v.len() as f64,
+            Self::Symbol(_, v) => v.len() as f64,
+            Self::List(_, v) => v.len() as f64,
+            Self::Vector(_, v) => v.len() as f64,
+            Self::Map(_, v) => v.len() as f64,
+            Self::Set(_, v) => v.len() as f64,
+            Self::Function(_, _) => 0.0,
         }
     }
 
@@ -56,13 +59,13 @@ impl Object {
     /// Gets the float of the object.
     /// Panics if the object isn't a float.
     ///
-    pub fn get_float(&self) -> f32 {
+    pub fn get_float(&self) -> f64 {
         self.as_float().expect("Expected an Object::Float.")
     }
 
     /// Sets the object to be a float.
     ///
-    pub fn set_float(&mut self, v: f32) -> &mut Self {
+    pub fn set_float(&mut self, v: f64) -> &mut Self {
         *self = Self::float(v);
         self
     }
@@ -0,0 +1,62 @@
+use super::*;
+
+use crate::nl::interpreter::{CustomForm, Environment};
+
+use std::rc::Rc;
+
+/// A first-class function.
+///
+/// It holds a custom form along with the environment captured where the
+/// function was created.
+///
+#[derive(Clone)]
+pub struct Function {
+    /// Name of the function, used in stack traces.
+    pub name: Option<String>,
+    /// Arguments and body of the function.
+    pub form: Rc<CustomForm>,
+    /// Environments captured when the function was created, from the
+    /// outermost to the innermost.
+    pub environment: Vec<Environment>,
+}
+
+impl Function {
+    /// Gets the name of the function, as shown in stack traces.
+    ///
+    pub fn display_name(&self) -> &str {
+        match &self.name {
+            Some(v) => v,
+            None => "lambda",
+        }
+    }
+}
+
+impl Object {
+    /// Creates a new function object.
+    ///
+    pub fn function(v: Function) -> Self {
+        Self::Function(ObjectInfo::new_empty(), Rc::new(v))
+    }
+
+    /// Checks if the object is a function.
+    ///
+    pub fn is_function(&self) -> bool {
+        matches!(self, Self::Function(_, _))
+    }
+
+    /// Gets the function in the object.
+    ///
+    pub fn as_function(&self) -> Option<&Rc<Function>> {
+        match self {
+            Self::Function(_, v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Gets the function in the object.
+    /// Panics if the object isn't a function.
+    ///
+    pub fn get_function(&self) -> &Rc<Function> {
+        self.as_function().expect("Expected an Object::Function.")
+    }
+}
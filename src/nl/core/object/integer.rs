@@ -3,22 +3,19 @@ use super::*;
 impl Object {
     /// Creates a new boolean object.
     ///
-    pub fn integer(v: i32) -> Self {
+    pub fn integer(v: i64) -> Self {
         Self::Integer(ObjectInfo::new_empty(), v)
     }
 
     /// Checks if the object is an integer.
     ///
     pub fn is_integer(&self) -> bool {
-        match self {
-            Self::Integer(_, _) => true,
-            _ => false,
-        }
+        matches!(self, Self::Integer(_, _))
     }
 
     /// Gets the integer value of the object.
     ///
-    pub fn as_integer(&self) -> Option<i32> {
+    pub fn as_integer(&self) -> Option<i64> {
         match self {
             Self::Integer(_, v) => Some(*v),
             _ => None,
@@ -27,7 +24,7 @@ impl Object {
 
     /// Converts the object into an integer.
     ///
-    pub fn to_integer(&self) -> i32 {
+    pub fn to_integer(&self) -> i64 {
         match self {
             Self::Nil(_) => 0,
             Self::Bool(_, v) => {
@@ -38,12 +35,18 @@ impl Object {
                 }
             }
             Self::Integer(_, v) => *v,
-            Self::Float(_, v) => *v as i32,
-            Self::Char(_, v) => *v as i32,
-            Self::String(_, v) => v.len() as i32,
-            Self::Keyword(_, v) => v.len() as i32,
-            Self::Symbol(_, v) => v.len() as i32,
-            Self::List(_, v) => v.len() as i32,
+            Self::Bignum(_, v) => bignum_to_integer(v),
+            Self::Rational(_, v) => bignum_to_integer(&v.to_integer()),
+            Self::Float(_, v) => *v as i64,
+            Self::Char(_, v) => *v as i64,
+            Self::String(_, v) => v.len() as i64,
+            Self::Keyword(_, v) => v.len() as i64,
+            Self::Symbol(_, v) => v.len() as i64,
+            Self::List(_, v) => v.len() as i64,
+            Self::Vector(_, v) => v.len() as i64,
+            Self::Map(_, v) => v.len() as i64,
+            Self::Set(_, v) => v.len() as i64,
+            Self::Function(_, _) => 0,
         }
     }
 
@@ -56,13 +59,13 @@ impl Object {
     /// Gets the integer value of the object.
     /// Panics if the object isn't an integer.
     ///
-    pub fn get_integer(&self) -> i32 {
+    pub fn get_integer(&self) -> i64 {
         self.as_integer().expect("Expected an Object::Integer.")
     }
 
     /// Sets the object to be an integer.
     ///
-    pub fn set_integer(&mut self, v: i32) -> &mut Self {
+    pub fn set_integer(&mut self, v: i64) -> &mut Self {
         *self = Self::integer(v);
         self
     }
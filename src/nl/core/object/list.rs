@@ -10,10 +10,7 @@ impl Object {
     /// Checks if the object is a list.
     ///
     pub fn is_list(&self) -> bool {
-        match self {
-            Self::List(_, _) => true,
-            _ => false,
-        }
+        matches!(self, Self::List(_, _))
     }
 
     /// Gets the list of the object.
@@ -41,12 +38,20 @@ impl Object {
             Self::Nil(_) => Vec::new(),
             Self::Bool(_, v) => vec![Self::bool(*v)],
             Self::Integer(_, v) => vec![Self::integer(*v)],
+            Self::Bignum(_, _) | Self::Rational(_, _) => vec![self.clone()],
             Self::Float(_, v) => vec![Self::float(*v)],
             Self::Char(_, v) => vec![Self::char(*v)],
             Self::String(_, v) => vec![Self::string(v.clone())],
             Self::Keyword(_, v) => vec![Self::keyword(v.clone())],
             Self::Symbol(_, v) => vec![Self::symbol(v.clone())],
             Self::List(_, v) => v.clone(),
+            Self::Vector(_, v) => v.clone(),
+            Self::Map(_, v) => v
+                .iter()
+                .map(|(key, value)| Self::list(vec![key.clone(), value.clone()]))
+                .collect(),
+            Self::Set(_, v) => v.iter().cloned().collect(),
+            Self::Function(_, _) => vec![self.clone()],
         }
     }
 
@@ -0,0 +1,40 @@
+use super::*;
+
+impl Object {
+    /// Creates a new map object.
+    ///
+    pub fn map(v: IndexMap<Object, Object>) -> Self {
+        Self::Map(ObjectInfo::new_empty(), v)
+    }
+
+    /// Checks if the object is a map.
+    ///
+    pub fn is_map(&self) -> bool {
+        matches!(self, Self::Map(_, _))
+    }
+
+    /// Gets the map of the object.
+    ///
+    pub fn as_map(&self) -> Option<&IndexMap<Object, Object>> {
+        match self {
+            Self::Map(_, v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Gets the map of the object.
+    ///
+    pub fn as_map_mut(&mut self) -> Option<&mut IndexMap<Object, Object>> {
+        match self {
+            Self::Map(_, v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Gets the map of the object.
+    /// Panics if the object isn't a map.
+    ///
+    pub fn get_map(&self) -> &IndexMap<Object, Object> {
+        self.as_map().expect("Expected an Object::Map.")
+    }
+}
@@ -1,26 +1,33 @@
+mod bignum;
 mod boolean;
 mod character;
 mod float;
+mod function;
 mod info;
 mod integer;
 mod keyword;
 mod list;
+mod map;
 mod nil;
+mod rational;
+mod set;
 mod string;
 mod symbol;
+mod vector;
 
-pub use boolean::*;
-pub use character::*;
-pub use float::*;
+pub use function::*;
 pub use info::*;
-pub use integer::*;
-pub use keyword::*;
-pub use list::*;
-pub use nil::*;
-pub use string::*;
-pub use symbol::*;
 
+use bignum::*;
+use indexmap::{IndexMap, IndexSet};
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use rational::*;
+
+use std::collections::hash_map::DefaultHasher;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::rc::Rc;
 
 /// A LISP object.
 ///
@@ -34,9 +41,14 @@ pub enum Object {
     /// A boolean.
     Bool(ObjectInfo, bool),
     /// An integer.
-    Integer(ObjectInfo, i32),
+    Integer(ObjectInfo, i64),
+    /// An arbitrary-precision integer, only used for values which don't fit
+    /// in an Integer.
+    Bignum(ObjectInfo, BigInt),
+    /// An exact rational, always normalized, whose denominator isn't 1.
+    Rational(ObjectInfo, BigRational),
     /// A float.
-    Float(ObjectInfo, f32),
+    Float(ObjectInfo, f64),
     /// A single character.
     Char(ObjectInfo, char),
     /// A string.
@@ -47,6 +59,14 @@ pub enum Object {
     Symbol(ObjectInfo, String),
     /// A list.
     List(ObjectInfo, Vec<Object>),
+    /// A vector. Unlike a list, it evaluates to itself.
+    Vector(ObjectInfo, Vec<Object>),
+    /// A map, keeping the insertion order of its keys.
+    Map(ObjectInfo, IndexMap<Object, Object>),
+    /// A set, keeping the insertion order of its elements.
+    Set(ObjectInfo, IndexSet<Object>),
+    /// A function, with its captured environment.
+    Function(ObjectInfo, Rc<Function>),
 }
 
 impl Object {
@@ -57,12 +77,18 @@ impl Object {
             Self::Nil(_) => "Nil",
             Self::Bool(_, _) => "Bool",
             Self::Integer(_, _) => "Integer",
+            Self::Bignum(_, _) => "Bignum",
+            Self::Rational(_, _) => "Rational",
             Self::Float(_, _) => "Float",
             Self::Char(_, _) => "Char",
             Self::String(_, _) => "String",
             Self::Keyword(_, _) => "Keyword",
             Self::Symbol(_, _) => "Symbol",
             Self::List(_, _) => "List",
+            Self::Vector(_, _) => "Vector",
+            Self::Map(_, _) => "Map",
+            Self::Set(_, _) => "Set",
+            Self::Function(_, _) => "Function",
         }
     }
 
@@ -73,12 +99,18 @@ impl Object {
             Self::Nil(i) => i,
             Self::Bool(i, _) => i,
             Self::Integer(i, _) => i,
+            Self::Bignum(i, _) => i,
+            Self::Rational(i, _) => i,
             Self::Float(i, _) => i,
             Self::Char(i, _) => i,
             Self::String(i, _) => i,
             Self::Keyword(i, _) => i,
             Self::Symbol(i, _) => i,
             Self::List(i, _) => i,
+            Self::Vector(i, _) => i,
+            Self::Map(i, _) => i,
+            Self::Set(i, _) => i,
+            Self::Function(i, _) => i,
         }
     }
 
@@ -89,12 +121,18 @@ impl Object {
             Self::Nil(i) => i,
             Self::Bool(i, _) => i,
             Self::Integer(i, _) => i,
+            Self::Bignum(i, _) => i,
+            Self::Rational(i, _) => i,
             Self::Float(i, _) => i,
             Self::Char(i, _) => i,
             Self::String(i, _) => i,
             Self::Keyword(i, _) => i,
             Self::Symbol(i, _) => i,
             Self::List(i, _) => i,
+            Self::Vector(i, _) => i,
+            Self::Map(i, _) => i,
+            Self::Set(i, _) => i,
+            Self::Function(i, _) => i,
         }
     }
 
@@ -112,13 +150,16 @@ impl Object {
             Self::Nil(_) => println!("{}Nil", i),
             Self::Bool(_, v) => println!("{}Bool : {}", i, v),
             Self::Integer(_, v) => println!("{}Integer : {}", i, v),
+            Self::Bignum(_, v) => println!("{}Bignum : {}", i, v),
+            Self::Rational(_, v) => println!("{}Rational : {}", i, v),
             Self::Float(_, v) => println!("{}Float : {}", i, v),
             Self::Char(_, v) => println!("{}Char : {:?}", i, v),
             Self::String(_, v) => println!("{}String : {:?}", i, v),
             Self::Keyword(_, v) => println!("{}Keyword : {}", i, v),
             Self::Symbol(_, v) => println!("{}Symbol : {}", i, v),
+            Self::Function(_, v) => println!("{}Function : {}", i, v.display_name()),
             Self::List(_, v) => {
-                if v.len() == 0 {
+                if v.is_empty() {
                     println!("{}Empty list", &i);
                 } else {
                     println!("{}List : ", &i);
@@ -127,21 +168,60 @@ impl Object {
                     }
                 }
             }
+            Self::Vector(_, v) => {
+                println!("{}Vector : ", &i);
+                for x in v {
+                    x._dump(format!("{}  ", i))
+                }
+            }
+            Self::Map(_, v) => {
+                println!("{}Map : ", &i);
+                for (key, value) in v {
+                    key._dump(format!("{}  ", i));
+                    value._dump(format!("{}    ", i));
+                }
+            }
+            Self::Set(_, v) => {
+                println!("{}Set : ", &i);
+                for x in v {
+                    x._dump(format!("{}  ", i))
+                }
+            }
         }
     }
 
-    pub fn complete_location(&mut self, file: &String, source: &String) {
-        self.get_info_mut().location.as_direct_mut().and_then(|x| {
+    pub fn complete_location(&mut self, file: &str, source: &str) {
+        if let Some(x) = self.get_info_mut().location.as_direct_mut() {
             x.complete(file, source);
-            Some(true)
-        });
+        }
 
-        self.as_list_mut().and_then(|x| {
-            for y in x {
-                y.complete_location(file, source)
+        match self {
+            Self::List(_, v) | Self::Vector(_, v) => {
+                for x in v {
+                    x.complete_location(file, source)
+                }
+            }
+            Self::Map(_, v) => {
+                *v = v
+                    .drain(..)
+                    .map(|(mut key, mut value)| {
+                        key.complete_location(file, source);
+                        value.complete_location(file, source);
+                        (key, value)
+                    })
+                    .collect();
+            }
+            Self::Set(_, v) => {
+                *v = v
+                    .drain(..)
+                    .map(|mut x| {
+                        x.complete_location(file, source);
+                        x
+                    })
+                    .collect();
             }
-            Some(true)
-        });
+            _ => {}
+        }
     }
 }
 
@@ -151,6 +231,10 @@ impl fmt::Display for Object {
             Object::Nil(_) => write!(f, "nil"),
             Object::Bool(_, v) => write!(f, "{}", v),
             Object::Integer(_, v) => write!(f, "{}", v),
+            Object::Bignum(_, v) => write!(f, "{}", v),
+            Object::Rational(_, v) => write!(f, "{}", v),
+            Object::Float(_, v) if v.is_finite() && v.fract() == 0.0 => write!(f, "{:.1}", v),
+            Object::Float(_, v) if v.is_nan() => write!(f, "nan"),
             Object::Float(_, v) => write!(f, "{}", v),
             Object::Char(_, v) => match v {
                 '\n' => write!(f, "#newline"),
@@ -162,6 +246,7 @@ impl fmt::Display for Object {
             Object::String(_, v) => write!(f, "{:?}", v),
             Object::Keyword(_, v) => write!(f, ":{}", v),
             Object::Symbol(_, v) => write!(f, "{}", v),
+            Object::Function(_, v) => write!(f, "#<function {}>", v.display_name()),
             Object::List(_, v) => {
                 write!(f, "(")?;
                 for (i, x) in v.iter().enumerate() {
@@ -173,6 +258,131 @@ impl fmt::Display for Object {
                 }
                 write!(f, ")")
             }
+            Object::Vector(_, v) => {
+                write!(f, "[")?;
+                for (i, x) in v.iter().enumerate() {
+                    x.fmt(f)?;
+
+                    if i + 1 < v.len() {
+                        write!(f, " ")?;
+                    }
+                }
+                write!(f, "]")
+            }
+            Object::Map(_, v) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in v.iter().enumerate() {
+                    write!(f, "{} {}", key, value)?;
+
+                    if i + 1 < v.len() {
+                        write!(f, " ")?;
+                    }
+                }
+                write!(f, "}}")
+            }
+            Object::Set(_, v) => {
+                write!(f, "#{{")?;
+                for (i, x) in v.iter().enumerate() {
+                    x.fmt(f)?;
+
+                    if i + 1 < v.len() {
+                        write!(f, " ")?;
+                    }
+                }
+                write!(f, "}}")
+            }
         }
     }
 }
+
+/// Structural equality, ignoring the information associated with the
+/// objects. `nil` is equal to the empty list, maps and sets are equal
+/// regardless of their order, and functions are only equal to themselves.
+/// Floats are compared by their bits : NaN is equal to itself, and `0.0`
+/// isn't equal to `-0.0`. The numeric comparison is done by `=`.
+///
+/// This equality is the one of `contains?`, `index-of` and the literal
+/// patterns of `match` : `-0.0` doesn't match `0.0` there, although
+/// `(= 0.0 -0.0)` is true.
+///
+impl PartialEq for Object {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Nil(_), Self::Nil(_)) => true,
+            (Self::Nil(_), Self::List(_, v)) | (Self::List(_, v), Self::Nil(_)) => v.is_empty(),
+            (Self::Bool(_, a), Self::Bool(_, b)) => a == b,
+            (Self::Integer(_, a), Self::Integer(_, b)) => a == b,
+            (Self::Bignum(_, a), Self::Bignum(_, b)) => a == b,
+            (Self::Rational(_, a), Self::Rational(_, b)) => a == b,
+            (Self::Float(_, a), Self::Float(_, b)) => a.to_bits() == b.to_bits(),
+            (Self::Char(_, a), Self::Char(_, b)) => a == b,
+            (Self::String(_, a), Self::String(_, b)) => a == b,
+            (Self::Keyword(_, a), Self::Keyword(_, b)) => a == b,
+            (Self::Symbol(_, a), Self::Symbol(_, b)) => a == b,
+            (Self::List(_, a), Self::List(_, b)) => a == b,
+            (Self::Vector(_, a), Self::Vector(_, b)) => a == b,
+            (Self::Map(_, a), Self::Map(_, b)) => a == b,
+            (Self::Set(_, a), Self::Set(_, b)) => a == b,
+            (Self::Function(_, a), Self::Function(_, b)) => Rc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+/// Objects are used as map keys and set elements. The equality is total
+/// since floats are compared by their bits.
+///
+impl Eq for Object {}
+
+/// Structural hashing, consistent with the equality. Floats are hashed by
+/// their bits, so `0.0` and `-0.0` are distinct map keys and set elements.
+///
+impl Hash for Object {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        match self {
+            Self::Nil(_) => {
+                "List".hash(state);
+                0usize.hash(state);
+            }
+            Self::Bool(_, v) => v.hash(state),
+            Self::Integer(_, v) => v.hash(state),
+            Self::Bignum(_, v) => v.hash(state),
+            Self::Rational(_, v) => v.hash(state),
+            Self::Float(_, v) => v.to_bits().hash(state),
+            Self::Char(_, v) => v.hash(state),
+            Self::String(_, v) => v.hash(state),
+            Self::Keyword(_, v) => v.hash(state),
+            Self::Symbol(_, v) => v.hash(state),
+            Self::List(_, v) => {
+                "List".hash(state);
+                v.hash(state);
+            }
+            Self::Vector(_, v) => {
+                "Vector".hash(state);
+                v.hash(state);
+            }
+            Self::Map(_, v) => {
+                "Map".hash(state);
+                v.len().hash(state);
+                unordered_hash(v.iter()).hash(state);
+            }
+            Self::Set(_, v) => {
+                "Set".hash(state);
+                v.len().hash(state);
+                unordered_hash(v.iter()).hash(state);
+            }
+            Self::Function(_, v) => std::ptr::hash(Rc::as_ptr(v), state),
+        }
+    }
+}
+
+/// Hashes a collection independently of the order of its elements.
+///
+fn unordered_hash<T: Hash>(iter: impl Iterator<Item = T>) -> u64 {
+    iter.map(|x| {
+        let mut hasher = DefaultHasher::new();
+        x.hash(&mut hasher);
+        hasher.finish()
+    })
+    .fold(0, u64::wrapping_add)
+}
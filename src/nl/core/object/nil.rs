@@ -10,10 +10,7 @@ impl Object {
     /// Returns true if the object is nil.
     ///
     pub fn is_nil(&self) -> bool {
-        match self {
-            Self::Nil(_) => true,
-            _ => false,
-        }
+        matches!(self, Self::Nil(_))
     }
 
     /// Sets the object to be nil.
@@ -0,0 +1,53 @@
+use super::*;
+
+use num_traits::{One, ToPrimitive};
+
+impl Object {
+    /// Creates a new number object from an exact rational. The result is an
+    /// Integer (or a Bignum) if the denominator is 1, a Rational otherwise.
+    ///
+    pub fn rational(v: BigRational) -> Self {
+        if v.denom().is_one() {
+            Self::bignum(v.to_integer())
+        } else {
+            Self::Rational(ObjectInfo::new_empty(), v)
+        }
+    }
+
+    /// Checks if the object is a rational.
+    ///
+    pub fn is_rational(&self) -> bool {
+        matches!(self, Self::Rational(_, _))
+    }
+
+    /// Gets the rational of the object.
+    ///
+    pub fn as_rational(&self) -> Option<&BigRational> {
+        match self {
+            Self::Rational(_, v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Converts the object into an exact rational.
+    ///
+    pub fn to_rational(&self) -> BigRational {
+        match self {
+            Self::Rational(_, v) => v.clone(),
+            _ => BigRational::from_integer(self.to_bignum()),
+        }
+    }
+
+    /// Gets the rational of the object.
+    /// Panics if the object isn't a rational.
+    ///
+    pub fn get_rational(&self) -> &BigRational {
+        self.as_rational().expect("Expected an Object::Rational.")
+    }
+}
+
+/// Converts a rational to a float.
+///
+pub(super) fn rational_to_float(v: &BigRational) -> f64 {
+    v.to_f64().unwrap_or(f64::NAN)
+}
@@ -0,0 +1,40 @@
+use super::*;
+
+impl Object {
+    /// Creates a new set object.
+    ///
+    pub fn set(v: IndexSet<Object>) -> Self {
+        Self::Set(ObjectInfo::new_empty(), v)
+    }
+
+    /// Checks if the object is a set.
+    ///
+    pub fn is_set(&self) -> bool {
+        matches!(self, Self::Set(_, _))
+    }
+
+    /// Gets the set of the object.
+    ///
+    pub fn as_set(&self) -> Option<&IndexSet<Object>> {
+        match self {
+            Self::Set(_, v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Gets the set of the object.
+    ///
+    pub fn as_set_mut(&mut self) -> Option<&mut IndexSet<Object>> {
+        match self {
+            Self::Set(_, v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Gets the set of the object.
+    /// Panics if the object isn't a set.
+    ///
+    pub fn get_set(&self) -> &IndexSet<Object> {
+        self.as_set().expect("Expected an Object::Set.")
+    }
+}
@@ -10,10 +10,7 @@ impl Object {
     /// Checks if the object is a string.
     ///
     pub fn is_string(&self) -> bool {
-        match self {
-            Self::String(_, _) => true,
-            _ => false,
-        }
+        matches!(self, Self::String(_, _))
     }
 
     /// Gets the string in the object.
@@ -36,6 +33,7 @@ impl Object {
 
     /// Converts the object into a string.
     ///
+    #[allow(clippy::inherent_to_string_shadow_display)]
     pub fn to_string(&self) -> String {
         match self {
             Self::Nil(_) => "nil".to_string(),
@@ -47,11 +45,16 @@ impl Object {
                 }
             }
             Self::Integer(_, v) => v.to_string(),
+            Self::Bignum(_, v) => v.to_string(),
+            Self::Rational(_, v) => v.to_string(),
             Self::Float(_, v) => v.to_string(),
             Self::Char(_, v) => v.to_string(),
             Self::String(_, v) => v.clone(),
             Self::Keyword(_, v) => v.clone(),
             Self::Symbol(_, v) => v.clone(),
+            Self::Vector(_, _) | Self::Map(_, _) | Self::Set(_, _) | Self::Function(_, _) => {
+                format!("{}", self)
+            }
             Self::List(_, v) => {
                 let mut r = "(".to_string();
                 for i in v {
@@ -10,10 +10,7 @@ impl Object {
     /// Checks if the object is a symbol.
     ///
     pub fn is_symbol(&self) -> bool {
-        match self {
-            Self::Symbol(_, _) => true,
-            _ => false,
-        }
+        matches!(self, Self::Symbol(_, _))
     }
 
     /// Gets the symbol in the object.
@@ -47,11 +44,16 @@ impl Object {
                 }
             }
             Self::Integer(_, v) => v.to_string(),
+            Self::Bignum(_, v) => v.to_string(),
+            Self::Rational(_, v) => v.to_string(),
             Self::Float(_, v) => v.to_string(),
             Self::Char(_, v) => v.to_string(),
             Self::String(_, v) => v.clone(),
             Self::Keyword(_, v) => v.clone(),
             Self::Symbol(_, v) => v.clone(),
+            Self::Vector(_, _) | Self::Map(_, _) | Self::Set(_, _) | Self::Function(_, _) => {
+                format!("{}", self)
+            }
             Self::List(_, v) => {
                 let mut r = "(".to_string();
                 for i in v {
@@ -0,0 +1,54 @@
+use super::*;
+
+impl Object {
+    /// Creates a new vector object.
+    ///
+    pub fn vector(v: Vec<Object>) -> Self {
+        Self::Vector(ObjectInfo::new_empty(), v)
+    }
+
+    /// Checks if the object is a vector.
+    ///
+    pub fn is_vector(&self) -> bool {
+        matches!(self, Self::Vector(_, _))
+    }
+
+    /// Gets the vector of the object.
+    ///
+    pub fn as_vector(&self) -> Option<&Vec<Object>> {
+        match self {
+            Self::Vector(_, v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Gets the vector of the object.
+    ///
+    pub fn as_vector_mut(&mut self) -> Option<&mut Vec<Object>> {
+        match self {
+            Self::Vector(_, v) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Gets the vector of the object.
+    /// Panics if the object isn't a vector.
+    ///
+    pub fn get_vector(&self) -> &Vec<Self> {
+        self.as_vector().expect("Expected an Object::Vector.")
+    }
+
+    /// Gets the vector of the object.
+    /// Panics if the object isn't a vector.
+    ///
+    pub fn get_vector_mut(&mut self) -> &mut Vec<Self> {
+        self.as_vector_mut().expect("Expected an Object::Vector.")
+    }
+
+    /// Sets the object to be a vector.
+    ///
+    pub fn set_vector(&mut self, v: Vec<Self>) -> &mut Self {
+        *self = Self::vector(v);
+        self
+    }
+}
@@ -0,0 +1,351 @@
+use super::syntax::*;
+
+/// Forms whose body is indented by two columns, with the number of
+/// arguments kept on the line of their name.
+///
+const BODY_FORMS: [(&str, usize); 18] = [
+    ("def", 1),
+    ("defconst", 1),
+    ("set!", 1),
+    ("defndynamic", 2),
+    ("defmacro", 2),
+    ("fn", 1),
+    ("lambda", 1),
+    ("let", 1),
+    ("while", 1),
+    ("when", 1),
+    ("unless", 1),
+    ("do", 0),
+    ("try", 0),
+    ("catch", 1),
+    ("finally", 0),
+    ("cond", 0),
+    ("case", 1),
+    ("match", 1),
+];
+
+/// Indentation of the body of a form.
+///
+const BODY_INDENT: usize = 2;
+
+/// How the items of a sequence are placed when they don't fit on a line.
+///
+#[derive(Clone, Copy, PartialEq)]
+enum Style {
+    /// One item per line.
+    Items,
+    /// Two items per line, for maps and `let` bindings.
+    Pairs,
+}
+
+/// Lays out items within a maximum line width.
+///
+pub struct Layout {
+    width: usize,
+}
+
+/// Text being laid out.
+///
+struct Writer {
+    out: String,
+    /// Whether the next item continues the current line.
+    fresh: bool,
+    /// Whether a blank line precedes the next item.
+    blank: bool,
+}
+
+impl Layout {
+    pub fn new(width: usize) -> Layout {
+        Layout { width }
+    }
+
+    /// Lays out the items of a source file, one top-level item per line.
+    ///
+    pub fn file(&self, items: &[Item]) -> String {
+        let mut writer = Writer::new("");
+        self.lines(&mut writer, items, 0, 0, Style::Items);
+
+        let mut out = writer.out;
+
+        if !out.is_empty() {
+            out.push('\n');
+        }
+
+        out
+    }
+
+    /// Lays out a node starting at a column. `trailing` is the number of
+    /// characters following the node on its last line.
+    ///
+    fn node(&self, node: &Node, column: usize, trailing: usize, style: Style) -> String {
+        if let Some(v) = flat(node) {
+            if column + v.chars().count() + trailing <= self.width {
+                return v;
+            }
+        }
+
+        match node {
+            Node::Atom(v) => v.clone(),
+            Node::Prefix(prefix, v) => {
+                let text = self.node(v, column + prefix.len(), trailing, style);
+                format!("{}{}", prefix, text)
+            }
+            Node::Sequence { open, close, items } => {
+                let style = if *open == "{" { Style::Pairs } else { style };
+                self.sequence(open, close, items, column, trailing, style)
+            }
+        }
+    }
+
+    /// Lays out a sequence on several lines.
+    ///
+    /// A form keeps its name and its first arguments on the first line,
+    /// the other items are aligned below :
+    ///
+    /// ```text
+    /// (defndynamic name (args)      (if cond        (call arg0
+    ///   body...)                        then            arg1)
+    ///                                   else)
+    /// ```
+    ///
+    fn sequence(
+        &self,
+        open: &str,
+        close: &str,
+        items: &[Item],
+        column: usize,
+        trailing: usize,
+        style: Style,
+    ) -> String {
+        let inner = column + open.len();
+        let name = match items.first() {
+            Some(Item::Node(Node::Atom(v)))
+                if open == "(" && style == Style::Items && is_symbol(v) =>
+            {
+                Some(v.as_str())
+            }
+            _ => None,
+        };
+
+        let (count, indent) = match name {
+            Some("if") => (2, inner + 3),
+            Some(v) => match body_arguments(v, items) {
+                Some(count) => (count + 1, column + BODY_INDENT),
+                None if self.aligns_arguments(v, items, inner) => (2, inner + v.len() + 1),
+                None => (1, inner),
+            },
+            None if style == Style::Pairs => (0, inner),
+            None => (1, inner),
+        };
+
+        let trailing = trailing + close.len();
+        let mut writer = Writer::new(open);
+        let mut head_column = inner;
+        let mut i = 0;
+
+        // The first items, on the line of the opening character.
+        while i < count {
+            let node = match items.get(i) {
+                Some(Item::Node(v)) => v,
+                _ => break,
+            };
+
+            if i > 0 {
+                writer.out.push(' ');
+                head_column += 1;
+            }
+
+            let style = match name {
+                Some("let") if i == 1 => Style::Pairs,
+                _ => Style::Items,
+            };
+            let last = if i + 1 == items.len() { trailing } else { 0 };
+
+            let text = self.node(node, head_column, last, style);
+            head_column = end_column(&text, head_column);
+            writer.out.push_str(&text);
+            writer.fresh = false;
+            i += 1;
+        }
+
+        let style = if count == 0 { style } else { Style::Items };
+        self.lines(&mut writer, &items[i..], indent, trailing, style);
+
+        if ends_with_comment(items) {
+            writer.fresh = false;
+            writer.new_line(column);
+        }
+
+        writer.out.push_str(close);
+        writer.out
+    }
+
+    /// Lays out items one (or two) per line, at a column. A trailing comment
+    /// stays at the end of the line of the item it follows.
+    ///
+    fn lines(
+        &self,
+        writer: &mut Writer,
+        items: &[Item],
+        column: usize,
+        trailing: usize,
+        style: Style,
+    ) {
+        let last = match items.iter().rposition(|x| !matches!(x, Item::Blank)) {
+            Some(i) if matches!(items[i], Item::Node(_)) => Some(i),
+            _ => None,
+        };
+        let trailing_of = |i: usize| if Some(i) == last { trailing } else { 0 };
+
+        let mut i = 0;
+
+        while i < items.len() {
+            match (&items[i], items.get(i + 1)) {
+                (Item::Node(key), Some(Item::Node(value))) if style == Style::Pairs => {
+                    writer.new_line(column);
+
+                    let key = self.node(key, column, 0, Style::Items);
+                    let value_column = end_column(&key, column) + 1;
+                    let value = self.node(value, value_column, trailing_of(i + 1), Style::Items);
+
+                    writer.out.push_str(&key);
+                    writer.out.push(' ');
+                    writer.out.push_str(&value);
+                    i += 1;
+                }
+                (Item::Node(node), _) => {
+                    writer.new_line(column);
+                    let text = self.node(node, column, trailing_of(i), Style::Items);
+                    writer.out.push_str(&text);
+                }
+                (
+                    Item::Comment {
+                        text,
+                        trailing: true,
+                    },
+                    _,
+                ) if !writer.fresh => {
+                    writer.out.push(' ');
+                    writer.out.push_str(text);
+                }
+                (Item::Comment { text, .. }, _) => {
+                    writer.new_line(column);
+                    writer.out.push_str(text);
+                }
+                (Item::Blank, _) => writer.blank = true,
+            }
+
+            i += 1;
+        }
+    }
+
+    /// Checks if the arguments of a call are aligned with its first
+    /// argument, rather than with its name.
+    ///
+    fn aligns_arguments(&self, name: &str, items: &[Item], inner: usize) -> bool {
+        let column = inner + name.len() + 1;
+
+        match items.get(1) {
+            Some(Item::Node(v)) => match flat(v) {
+                Some(v) => column + v.chars().count() <= self.width,
+                None => column <= self.width / 2,
+            },
+            _ => false,
+        }
+    }
+}
+
+impl Writer {
+    fn new(open: &str) -> Writer {
+        Writer {
+            out: open.to_string(),
+            fresh: true,
+            blank: false,
+        }
+    }
+
+    /// Starts a new line at a column, after a blank line if one preceded
+    /// the item. The first item continues the current line.
+    ///
+    fn new_line(&mut self, column: usize) {
+        if self.fresh {
+            self.fresh = false;
+        } else {
+            if self.blank {
+                self.out.push('\n');
+            }
+
+            self.out.push('\n');
+            self.out.push_str(&" ".repeat(column));
+        }
+
+        self.blank = false;
+    }
+}
+
+/// Lays out a node on a single line, if it has no comments.
+///
+fn flat(node: &Node) -> Option<String> {
+    match node {
+        Node::Atom(v) if v.contains('\n') => None,
+        Node::Atom(v) => Some(v.clone()),
+        Node::Prefix(prefix, v) => Some(format!("{}{}", prefix, flat(v)?)),
+        Node::Sequence { open, close, items } => {
+            let mut parts = Vec::with_capacity(items.len());
+
+            for item in items {
+                match item {
+                    Item::Node(v) => parts.push(flat(v)?),
+                    Item::Comment { .. } => return None,
+                    Item::Blank => (),
+                }
+            }
+
+            Some(format!("{}{}{}", open, parts.join(" "), close))
+        }
+    }
+}
+
+/// Gets the number of arguments kept on the line of a body form, or `None`
+/// if the form has no body.
+///
+fn body_arguments(name: &str, items: &[Item]) -> Option<usize> {
+    let count = BODY_FORMS.iter().find(|(x, _)| *x == name)?.1;
+
+    // `(catch :tag e handler...)`
+    match items.get(1) {
+        Some(Item::Node(Node::Atom(v))) if name == "catch" && v.starts_with(':') => Some(count + 1),
+        _ => Some(count),
+    }
+}
+
+/// Checks if an atom is a symbol, which can name a form.
+///
+fn is_symbol(atom: &str) -> bool {
+    let mut chars = atom.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some('+'), Some(c)) | (Some('-'), Some(c)) | (Some('.'), Some(c)) => !c.is_ascii_digit(),
+        (Some(c), _) => !c.is_ascii_digit() && !"\":#".contains(c),
+        (None, _) => false,
+    }
+}
+
+/// Checks if the last item of a sequence is a comment, after which the
+/// sequence can't be closed on the same line.
+///
+fn ends_with_comment(items: &[Item]) -> bool {
+    matches!(
+        items.iter().rev().find(|x| !matches!(x, Item::Blank)),
+        Some(Item::Comment { .. })
+    )
+}
+
+/// Gets the column following a text laid out from a column.
+///
+fn end_column(text: &str, column: usize) -> usize {
+    match text.rfind('\n') {
+        Some(i) => text[i + 1..].chars().count(),
+        None => column + text.chars().count(),
+    }
+}
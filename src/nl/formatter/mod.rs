@@ -0,0 +1,51 @@
+mod layout;
+mod syntax;
+
+use crate::nl::core::error::*;
+use crate::nl::interpreter::read_source;
+
+use layout::*;
+use syntax::*;
+
+/// Default maximum width of the lines.
+///
+pub const DEFAULT_WIDTH: usize = 80;
+
+/// Formats source files : indents the forms, wraps the lines exceeding a
+/// width, keeps the comments and collapses the consecutive blank lines.
+///
+pub struct Formatter {
+    width: usize,
+}
+
+impl Default for Formatter {
+    fn default() -> Self {
+        Formatter {
+            width: DEFAULT_WIDTH,
+        }
+    }
+}
+
+impl Formatter {
+    pub fn new() -> Formatter {
+        Formatter::default()
+    }
+
+    /// Sets the maximum width of the lines. Longer lines are only kept if
+    /// they can't be wrapped, e.g. for a long string.
+    ///
+    pub fn with_width(mut self, width: usize) -> Self {
+        self.width = width;
+        self
+    }
+
+    /// Formats a source file.
+    ///
+    /// Returns the syntax error of the source, if any.
+    ///
+    pub fn format(&self, file: &str, source: &str) -> Result<String, Error> {
+        read_source(file, source)?;
+
+        Ok(Layout::new(self.width).file(&parse(source)))
+    }
+}
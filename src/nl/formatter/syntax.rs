@@ -0,0 +1,216 @@
+use crate::nl::reader::is_name_char;
+
+/// An element of a sequence or of a source file, keeping the comments and
+/// the blank lines the reader discards.
+///
+pub enum Item {
+    Node(Node),
+    /// A comment, without its end of line.
+    Comment {
+        text: String,
+        /// Whether the comment follows a node on the same line.
+        trailing: bool,
+    },
+    /// One or more blank lines after an item.
+    Blank,
+}
+
+/// An expression, as written in a source file.
+///
+pub enum Node {
+    /// An atom (number, string, symbol...) with its exact spelling.
+    Atom(String),
+    /// A reader macro (`'`, `` ` `` or `,`) followed by its expression.
+    Prefix(&'static str, Box<Node>),
+    /// A list, a vector, a map or a set.
+    Sequence {
+        open: &'static str,
+        close: &'static str,
+        items: Vec<Item>,
+    },
+}
+
+/// Character names of the character literals, e.g. `#space`.
+///
+const CHAR_NAMES: [&str; 4] = ["space", "newline", "tab", "carriage-return"];
+
+/// Parses a source file into items.
+///
+/// The source must have been checked by the reader : the syntax errors
+/// aren't reported, the unexpected characters are read as atoms.
+///
+pub fn parse(source: &str) -> Vec<Item> {
+    let mut parser = Parser { source, index: 0 };
+    parser.items(None)
+}
+
+struct Parser<'s> {
+    source: &'s str,
+    index: usize,
+}
+
+impl<'s> Parser<'s> {
+    fn rest(&self) -> &'s str {
+        &self.source[self.index..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    /// Parses items until the closing character of a sequence, which is
+    /// consumed, or until the end of the source.
+    ///
+    fn items(&mut self, close: Option<char>) -> Vec<Item> {
+        let mut items = Vec::new();
+
+        loop {
+            let newlines = self.skip_spacing();
+
+            if newlines > 1 && !matches!(items.last(), None | Some(Item::Blank)) {
+                items.push(Item::Blank);
+            }
+
+            match self.peek() {
+                None => break,
+                Some(c) if Some(c) == close => {
+                    self.index += c.len_utf8();
+                    break;
+                }
+                Some(';') => {
+                    let trailing = newlines == 0 && matches!(items.last(), Some(Item::Node(_)));
+                    let text = self.comment();
+                    items.push(Item::Comment { text, trailing });
+                }
+                Some(_) => {
+                    let node = self.node(&mut items);
+                    items.push(Item::Node(node));
+                }
+            }
+        }
+
+        items
+    }
+
+    /// Skips the spaces, returning the number of line feeds skipped.
+    ///
+    fn skip_spacing(&mut self) -> usize {
+        let rest = self.rest();
+        let length = rest.len() - rest.trim_start_matches([' ', '\t', '\r', '\n']).len();
+        let newlines = rest[..length].matches('\n').count();
+
+        self.index += length;
+        newlines
+    }
+
+    /// Reads a comment, up to the end of its line.
+    ///
+    fn comment(&mut self) -> String {
+        let rest = self.rest();
+        let length = rest.find(['\r', '\n']).unwrap_or(rest.len());
+
+        self.index += length;
+        rest[..length].trim_end().to_string()
+    }
+
+    /// Parses a node. The comments placed between a reader macro and its
+    /// expression are moved before the reader macro, into `items`.
+    ///
+    fn node(&mut self, items: &mut Vec<Item>) -> Node {
+        let rest = self.rest();
+
+        for prefix in ["'", "`", ","] {
+            if rest.starts_with(prefix) {
+                self.index += prefix.len();
+
+                loop {
+                    self.skip_spacing();
+
+                    if self.peek() != Some(';') {
+                        break;
+                    }
+
+                    let text = self.comment();
+                    items.push(Item::Comment {
+                        text,
+                        trailing: false,
+                    });
+                }
+
+                return Node::Prefix(prefix, Box::new(self.node(items)));
+            }
+        }
+
+        for (open, close) in [("(", ")"), ("[", "]"), ("{", "}"), ("#{", "}")] {
+            if rest.starts_with(open) {
+                self.index += open.len();
+
+                return Node::Sequence {
+                    open,
+                    close,
+                    items: self.items(close.chars().next()),
+                };
+            }
+        }
+
+        let length = atom_length(rest);
+        self.index += length;
+
+        Node::Atom(rest[..length].to_string())
+    }
+}
+
+/// Gets the length of the atom at the start of a text, in bytes.
+///
+fn atom_length(text: &str) -> usize {
+    if let Some(v) = text.strip_prefix('"') {
+        let mut escaped = false;
+
+        for (i, c) in v.char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => return i + 2,
+                _ => (),
+            }
+        }
+
+        return text.len();
+    }
+
+    if let Some(v) = text.strip_prefix("r\"") {
+        let mut i = 0;
+
+        while let Some(j) = v[i..].find('"') {
+            i += j + 1;
+
+            if !v[i..].starts_with('"') {
+                return i + 2;
+            }
+
+            i += 1;
+        }
+
+        return text.len();
+    }
+
+    if let Some(v) = text.strip_prefix('#') {
+        if let Some(name) = CHAR_NAMES.iter().find(|x| v.starts_with(*x)) {
+            return name.len() + 1;
+        }
+
+        if v.starts_with('\\') && v[1..].starts_with(['n', 't', 'r', '"']) {
+            return 3;
+        }
+
+        return 1 + v.chars().next().map_or(0, |x| x.len_utf8());
+    }
+
+    let length = text.len() - text.trim_start_matches(is_name_char).len();
+
+    if length == 0 {
+        text.chars().next().map_or(0, |x| x.len_utf8())
+    } else {
+        length
+    }
+}
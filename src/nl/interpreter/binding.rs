@@ -1,8 +1,13 @@
 use crate::nl::core::error::*;
 use crate::nl::core::object::*;
 
+use super::bytecode::Chunk;
 use super::scope::Scope;
 
+use std::cell::OnceCell;
+use std::fmt;
+use std::rc::Rc;
+
 /// Function signature for all special form handlers.
 /// The arguments are passed as-in, without being evaluated.
 ///
@@ -19,22 +24,129 @@ pub type EvalForm = fn(Vec<Object>) -> Result<Object, Error>;
 #[derive(Clone)]
 pub struct CustomForm {
     pub location: Location,
-    pub arguments: Vec<String>,
+    pub arguments: ArgumentList,
     pub body: Vec<Object>,
+    /// Bytecode of the body, compiled on the first call from the virtual
+    /// machine.
+    pub code: OnceCell<Chunk>,
+}
+
+/// An optional or keyword argument of a custom form.
+///
+#[derive(Clone)]
+pub struct OptionalArgument {
+    pub name: String,
+    /// Expression evaluated when the argument isn't given.
+    /// The argument is `nil` if there is no default expression.
+    pub default: Option<Object>,
+}
+
+/// Argument list of a custom form.
+///
+/// `(req0 req1 &optional opt0 (opt1 default) &rest rest &key key0 (key1 default))`
+///
+#[derive(Clone, Default)]
+pub struct ArgumentList {
+    /// Required arguments.
+    pub required: Vec<String>,
+    /// Arguments following `&optional`.
+    pub optional: Vec<OptionalArgument>,
+    /// Argument following `&rest`, receiving the remaining arguments as a list.
+    pub rest: Option<String>,
+    /// Arguments following `&key`, given as `:name value` pairs.
+    pub keys: Vec<OptionalArgument>,
+}
+
+impl ArgumentList {
+    /// Gets the minimum number of arguments.
+    ///
+    pub fn min_count(&self) -> usize {
+        self.required.len()
+    }
+
+    /// Gets the maximum number of arguments, or `None` if unbounded.
+    ///
+    pub fn max_count(&self) -> Option<usize> {
+        if self.rest.is_some() || !self.keys.is_empty() {
+            None
+        } else {
+            Some(self.required.len() + self.optional.len())
+        }
+    }
+
+    /// Describes the number of arguments accepted, as used in arity errors.
+    ///
+    pub fn arity_string(&self) -> String {
+        match self.max_count() {
+            Some(max) if max == self.min_count() => format!("{}", max),
+            Some(max) => format!("{} to {}", self.min_count(), max),
+            None => format!("at least {}", self.min_count()),
+        }
+    }
+}
+
+impl fmt::Display for OptionalArgument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.default {
+            Some(v) => write!(f, "({} {})", self.name, v),
+            None => write!(f, "{}", self.name),
+        }
+    }
+}
+
+impl fmt::Display for ArgumentList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts: Vec<String> = self.required.clone();
+
+        if !self.optional.is_empty() {
+            parts.push("&optional".to_string());
+            parts.extend(self.optional.iter().map(|x| x.to_string()));
+        }
+
+        if let Some(v) = &self.rest {
+            parts.push("&rest".to_string());
+            parts.push(v.clone());
+        }
+
+        if !self.keys.is_empty() {
+            parts.push("&key".to_string());
+            parts.extend(self.keys.iter().map(|x| x.to_string()));
+        }
+
+        write!(f, "({})", parts.join(" "))
+    }
 }
 
 /// A binding.
 ///
+#[derive(Clone)]
 pub enum Binding {
     /// A dynamic variable binding.
     DynamicVariable(Object),
+    /// A constant variable binding, with the location of its definition.
+    ConstantVariable(Object, Location),
 
     /// A special form binding.
     SpecialForm(SpecialForm),
     /// An eval form binding.
     EvalForm(EvalForm),
     /// A dynamic form binding.
-    DynamicForm(CustomForm),
+    DynamicForm(Rc<CustomForm>),
     /// A macro form binding.
-    MacroForm(CustomForm),
+    MacroForm(Rc<CustomForm>),
+}
+
+impl Binding {
+    /// Gets the kind of the binding as a string.
+    ///
+    pub fn kind_string(&self) -> &'static str {
+        match self {
+            Self::DynamicVariable(_) => "DynamicVariable",
+            Self::ConstantVariable(_, _) => "ConstantVariable",
+            Self::SpecialForm(_) => "SpecialForm",
+            Self::EvalForm(_) => "EvalForm",
+            Self::DynamicForm(_) => "DynamicForm",
+            Self::MacroForm(_) => "MacroForm",
+        }
+    }
 }
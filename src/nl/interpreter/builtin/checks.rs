@@ -1,6 +1,8 @@
 use crate::nl::core::object::*;
 use crate::nl::interpreter::*;
 
+use super::check_arg_count;
+
 /// Registers all builtin checks forms into the given scope.
 ///
 pub fn register_builtin_check_forms(scope: &mut Scope) {
@@ -13,7 +15,11 @@ pub fn register_builtin_check_forms(scope: &mut Scope) {
         .register_eval_form("is-string?", is_string)
         .register_eval_form("is-keyword?", is_keyword)
         .register_eval_form("is-symbol?", is_symbol)
-        .register_eval_form("is-list?", is_list);
+        .register_eval_form("is-list?", is_list)
+        .register_eval_form("is-vector?", is_vector)
+        .register_eval_form("is-map?", is_map)
+        .register_eval_form("is-set?", is_set)
+        .register_eval_form("is-function?", is_function);
 }
 
 /// `is-nil?` eval form.
@@ -21,12 +27,7 @@ pub fn register_builtin_check_forms(scope: &mut Scope) {
 /// `(is-nil? v)`
 ///
 fn is_nil(args: Vec<Object>) -> Result<Object, Error> {
-    if args.len() != 1 {
-        return Error::err(&format!(
-            "'is-nil?' only receives 1 argument, got {} instead.",
-            args.len()
-        ));
-    }
+    check_arg_count("is-nil?", &args, 1)?;
 
     Ok(Object::bool(args[0].is_nil()))
 }
@@ -36,29 +37,21 @@ fn is_nil(args: Vec<Object>) -> Result<Object, Error> {
 /// `(is-bool? v)`
 ///
 fn is_bool(args: Vec<Object>) -> Result<Object, Error> {
-    if args.len() != 1 {
-        return Error::err(&format!(
-            "'is-bool?' only receives 1 argument, got {} instead.",
-            args.len()
-        ));
-    }
+    check_arg_count("is-bool?", &args, 1)?;
 
     Ok(Object::bool(args[0].is_bool()))
 }
 
 /// `is-integer?` eval form.
 ///
+/// True for Integers and Bignums.
+///
 /// `(is-integer? v)`
 ///
 fn is_integer(args: Vec<Object>) -> Result<Object, Error> {
-    if args.len() != 1 {
-        return Error::err(&format!(
-            "'is-int?' only receives 1 argument, got {} instead.",
-            args.len()
-        ));
-    }
-
-    Ok(Object::bool(args[0].is_integer()))
+    check_arg_count("is-int?", &args, 1)?;
+
+    Ok(Object::bool(args[0].is_integer() || args[0].is_bignum()))
 }
 
 /// `is-float?` eval form.
@@ -66,12 +59,7 @@ fn is_integer(args: Vec<Object>) -> Result<Object, Error> {
 /// `(is-float? v)`
 ///
 fn is_float(args: Vec<Object>) -> Result<Object, Error> {
-    if args.len() != 1 {
-        return Error::err(&format!(
-            "'is-float?' only receives 1 argument, got {} instead.",
-            args.len()
-        ));
-    }
+    check_arg_count("is-float?", &args, 1)?;
 
     Ok(Object::bool(args[0].is_float()))
 }
@@ -81,12 +69,7 @@ fn is_float(args: Vec<Object>) -> Result<Object, Error> {
 /// `(is-char? v)`
 ///
 fn is_char(args: Vec<Object>) -> Result<Object, Error> {
-    if args.len() != 1 {
-        return Error::err(&format!(
-            "'is-char?' only receives 1 argument, got {} instead.",
-            args.len()
-        ));
-    }
+    check_arg_count("is-char?", &args, 1)?;
 
     Ok(Object::bool(args[0].is_char()))
 }
@@ -96,12 +79,7 @@ fn is_char(args: Vec<Object>) -> Result<Object, Error> {
 /// `(is-string? v)`
 ///
 fn is_string(args: Vec<Object>) -> Result<Object, Error> {
-    if args.len() != 1 {
-        return Error::err(&format!(
-            "'is-string?' only receives 1 argument, got {} instead.",
-            args.len()
-        ));
-    }
+    check_arg_count("is-string?", &args, 1)?;
 
     Ok(Object::bool(args[0].is_string()))
 }
@@ -111,12 +89,7 @@ fn is_string(args: Vec<Object>) -> Result<Object, Error> {
 /// `(is-keyword? v)`
 ///
 fn is_keyword(args: Vec<Object>) -> Result<Object, Error> {
-    if args.len() != 1 {
-        return Error::err(&format!(
-            "'is-keyword?' only receives 1 argument, got {} instead.",
-            args.len()
-        ));
-    }
+    check_arg_count("is-keyword?", &args, 1)?;
 
     Ok(Object::bool(args[0].is_keyword()))
 }
@@ -126,12 +99,7 @@ fn is_keyword(args: Vec<Object>) -> Result<Object, Error> {
 /// `(is-symbol? v)`
 ///
 fn is_symbol(args: Vec<Object>) -> Result<Object, Error> {
-    if args.len() != 1 {
-        return Error::err(&format!(
-            "'is-symbol?' only receives 1 argument, got {} instead.",
-            args.len()
-        ));
-    }
+    check_arg_count("is-symbol?", &args, 1)?;
 
     Ok(Object::bool(args[0].is_symbol()))
 }
@@ -141,12 +109,47 @@ fn is_symbol(args: Vec<Object>) -> Result<Object, Error> {
 /// `(is-list? v)`
 ///
 fn is_list(args: Vec<Object>) -> Result<Object, Error> {
-    if args.len() != 1 {
-        return Error::err(&format!(
-            "'is-list?' only receives 1 argument, got {} instead.",
-            args.len()
-        ));
-    }
+    check_arg_count("is-list?", &args, 1)?;
 
     Ok(Object::bool(args[0].is_list()))
 }
+
+/// `is-vector?` eval form.
+///
+/// `(is-vector? v)`
+///
+fn is_vector(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("is-vector?", &args, 1)?;
+
+    Ok(Object::bool(args[0].is_vector()))
+}
+
+/// `is-map?` eval form.
+///
+/// `(is-map? v)`
+///
+fn is_map(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("is-map?", &args, 1)?;
+
+    Ok(Object::bool(args[0].is_map()))
+}
+
+/// `is-set?` eval form.
+///
+/// `(is-set? v)`
+///
+fn is_set(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("is-set?", &args, 1)?;
+
+    Ok(Object::bool(args[0].is_set()))
+}
+
+/// `is-function?` eval form.
+///
+/// `(is-function? v)`
+///
+fn is_function(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("is-function?", &args, 1)?;
+
+    Ok(Object::bool(args[0].is_function()))
+}
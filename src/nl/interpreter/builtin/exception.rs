@@ -0,0 +1,298 @@
+use crate::nl::core::object::*;
+use crate::nl::interpreter::*;
+
+use super::flow::evaluate_body;
+use super::{check_arg_count, check_arg_range};
+
+/// Registers all exception handling builtin forms.
+///
+pub fn register_builtin_exception_forms(scope: &mut Scope) {
+    scope
+        .register_eval_form("throw", throw)
+        .register_special_form("try", try_catch)
+        .register_eval_form("error-message", error_message)
+        .register_eval_form("error-tag", error_tag)
+        .register_eval_form("error-kind", error_kind)
+        .register_eval_form("error-code", error_code)
+        .register_eval_form("error-value", error_value)
+        .register_eval_form("error-frames", error_frames);
+}
+
+/// `throw` eval form.
+///
+/// `(throw :tag)`
+/// `(throw :tag value)`
+///
+fn throw(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_range("throw", &args, 1, 2)?;
+
+    let tag = match args[0].as_keyword() {
+        Some(v) => v,
+        None => {
+            return Error::err_kind(
+                ErrorKind::wrong_type("Keyword", &args[0]),
+                &format!(
+                    "'throw' requires a Keyword as its first argument, got a {} : {}",
+                    args[0].type_string(),
+                    args[0]
+                ),
+            )
+        }
+    };
+
+    let value = match args.get(1) {
+        Some(v) => v.clone(),
+        None => Object::nil(),
+    };
+
+    Err(Error::new_thrown(tag, value))
+}
+
+/// A `catch` clause of a `try` form.
+///
+struct CatchClause<'a> {
+    /// Tag of the caught errors, or `None` to catch every error.
+    tag: Option<&'a String>,
+    variable: &'a String,
+    body: &'a [Object],
+}
+
+/// `try` special form.
+///
+/// Errors raised by `throw` are matched on their tag, other errors are
+/// tagged `:error`. A catch clause without tag catches every error. The
+/// `finally` body is always evaluated, and its result is discarded.
+///
+/// `(try body... (catch :tag e handler...) (catch e handler...) (finally cleanup...))`
+///
+fn try_catch(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    let mut body_len = args.len();
+    let mut clauses = Vec::new();
+    let mut finally: Option<&[Object]> = None;
+
+    for (i, arg) in args.iter().enumerate() {
+        match clause_name(arg) {
+            Some("catch") => {
+                if finally.is_some() {
+                    return Error::err_kind(
+                        ErrorKind::Form,
+                        &format!(
+                            "invalid 'try' syntax at {}.\nThe 'finally' clause must be the last one.",
+                            arg
+                        ),
+                    );
+                }
+
+                body_len = body_len.min(i);
+                clauses.push(parse_catch_clause(arg)?);
+            }
+            Some("finally") => {
+                if finally.is_some() {
+                    return Error::err_kind(
+                        ErrorKind::Form,
+                        &format!(
+                            "invalid 'try' syntax at {}.\nOnly one 'finally' clause is allowed.",
+                            arg
+                        ),
+                    );
+                }
+
+                body_len = body_len.min(i);
+                finally = Some(&arg.get_list()[1..]);
+            }
+            _ => {
+                if body_len < args.len() {
+                    return Error::err_kind(
+                        ErrorKind::Form,
+                        &format!(
+                            "invalid 'try' syntax at {}.\nExpected a 'catch' or 'finally' clause.",
+                            arg
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    let depth = scope.depth();
+    let mut result = evaluate_body(scope, &args[..body_len]);
+
+    if let Err(e) = result {
+        scope.unwind(depth);
+
+        let clause = clauses.iter().find(|x| match x.tag {
+            Some(tag) => tag == e.tag(),
+            None => true,
+        });
+
+        result = match clause {
+            Some(clause) => {
+                scope.enter(Mode::Evaluation);
+                scope.insert(
+                    clause.variable.clone(),
+                    Binding::DynamicVariable(error_to_object(&e)),
+                );
+
+                let r = evaluate_body(scope, clause.body);
+                scope.unwind(depth);
+
+                r
+            }
+            None => Err(e),
+        };
+    }
+
+    if let Some(body) = finally {
+        if let Err(e) = evaluate_body(scope, body) {
+            scope.unwind(depth);
+            return Err(e);
+        }
+    }
+
+    result
+}
+
+/// Gets the name of a `try` clause, if the object is one.
+///
+fn clause_name(object: &Object) -> Option<&str> {
+    match object.as_list()?.first()? {
+        Object::Symbol(_, v) if v == "catch" || v == "finally" => Some(v),
+        _ => None,
+    }
+}
+
+/// Parses a `catch` clause.
+///
+/// `(catch :tag e handler...)`
+/// `(catch e handler...)`
+///
+fn parse_catch_clause(object: &Object) -> Result<CatchClause<'_>, Error> {
+    let list = object.get_list();
+
+    let (tag, rest) = match list.get(1) {
+        Some(Object::Keyword(_, v)) => (Some(v), &list[2..]),
+        _ => (None, &list[1..]),
+    };
+
+    match rest.first() {
+        Some(Object::Symbol(_, variable)) => Ok(CatchClause {
+            tag,
+            variable,
+            body: &rest[1..],
+        }),
+        _ => Error::err_kind(
+            ErrorKind::Form,
+            &format!(
+                "invalid 'catch' syntax at {}.\nSyntax : (catch :tag variable body...) or (catch variable body...)",
+                object
+            ),
+        ),
+    }
+}
+
+/// Converts a caught error into an object.
+///
+/// `(:message "..." :tag :tag :kind :kind :code "E0000" :value value :frames (("name" "location") ...))`
+///
+fn error_to_object(error: &Error) -> Object {
+    let frames = error
+        .frames()
+        .iter()
+        .map(|x| {
+            Object::list(vec![
+                Object::string(x.name.clone()),
+                Object::string(x.location.to_string()),
+            ])
+        })
+        .collect();
+
+    let value = match error.value() {
+        Some(v) => v.clone(),
+        None => Object::nil(),
+    };
+
+    Object::list(vec![
+        Object::keyword("message".to_string()),
+        Object::string(error.message().to_string()),
+        Object::keyword("tag".to_string()),
+        Object::keyword(error.tag().to_string()),
+        Object::keyword("kind".to_string()),
+        Object::keyword(error.kind().name().to_string()),
+        Object::keyword("code".to_string()),
+        Object::string(error.code().to_string()),
+        Object::keyword("value".to_string()),
+        value,
+        Object::keyword("frames".to_string()),
+        Object::list(frames),
+    ])
+}
+
+/// Gets a field of a caught error object.
+///
+fn get_error_field(form: &str, args: &[Object], field: &str) -> Result<Object, Error> {
+    check_arg_count(form, args, 1)?;
+
+    if let Some(list) = args[0].as_list() {
+        for pair in list.chunks(2) {
+            if pair.len() == 2 && pair[0].as_keyword().map(|x| x.as_str()) == Some(field) {
+                return Ok(pair[1].clone());
+            }
+        }
+    }
+
+    Error::err_kind(
+        ErrorKind::wrong_type("error", &args[0]),
+        &format!(
+            "'{}' requires an error caught by 'try', got : {}",
+            form, args[0]
+        ),
+    )
+}
+
+/// `error-message` eval form.
+///
+/// `(error-message e)`
+///
+fn error_message(args: Vec<Object>) -> Result<Object, Error> {
+    get_error_field("error-message", &args, "message")
+}
+
+/// `error-tag` eval form.
+///
+/// `(error-tag e)`
+///
+fn error_tag(args: Vec<Object>) -> Result<Object, Error> {
+    get_error_field("error-tag", &args, "tag")
+}
+
+/// `error-kind` eval form.
+///
+/// `(error-kind e)`
+///
+fn error_kind(args: Vec<Object>) -> Result<Object, Error> {
+    get_error_field("error-kind", &args, "kind")
+}
+
+/// `error-code` eval form.
+///
+/// `(error-code e)`
+///
+fn error_code(args: Vec<Object>) -> Result<Object, Error> {
+    get_error_field("error-code", &args, "code")
+}
+
+/// `error-value` eval form.
+///
+/// `(error-value e)`
+///
+fn error_value(args: Vec<Object>) -> Result<Object, Error> {
+    get_error_field("error-value", &args, "value")
+}
+
+/// `error-frames` eval form.
+///
+/// `(error-frames e)`
+///
+fn error_frames(args: Vec<Object>) -> Result<Object, Error> {
+    get_error_field("error-frames", &args, "frames")
+}
@@ -1,6 +1,8 @@
 use crate::nl::core::object::*;
 use crate::nl::interpreter::*;
 
+use super::{check_arg_count, check_arg_range, check_min_arg_count};
+
 /// Registers all builtin flow control forms.
 ///
 pub fn register_builtin_flow_forms(scope: &mut Scope) {
@@ -9,7 +11,14 @@ pub fn register_builtin_flow_forms(scope: &mut Scope) {
         .register_special_form("while", flow_while)
         .register_special_form("break", flow_break)
         .register_special_form("do", flow_do)
-        .register_special_form("let", flow_let);
+        .register_special_form("let", flow_let)
+        .register_special_form("when", flow_when)
+        .register_special_form("unless", flow_unless)
+        .register_special_form("cond", flow_cond)
+        .register_special_form("case", flow_case)
+        .register_special_form("and", flow_and)
+        .register_special_form("or", flow_or)
+        .register_special_form("not", flow_not);
 }
 
 /// `if` special form.
@@ -18,53 +27,33 @@ pub fn register_builtin_flow_forms(scope: &mut Scope) {
 /// `(if cond then else)`
 ///
 fn flow_if(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
-    if args.len() < 2 || args.len() > 3 {
-        return Error::err(&format!(
-            "`if` receives 2 or 3 arguments, got {} instead.",
-            args.len()
-        ));
-    }
-
-    match evaluate(scope, &args[0]) {
-        Ok(v) => {
-            if !v.is_bool() {
-                return Error::err(&format!(
-                    "Expected an Bool, got a '{}' from {}",
-                    v.type_string(),
-                    v
-                ));
-            }
-
-            if args.len() == 2 {
-                if v.get_bool() {
-                    match evaluate(scope.enter(Mode::Evaluation), &args[1]) {
-                        Err(e) => return Err(e),
-                        _ => {}
-                    };
-                    scope.leave();
-                }
-
-                return Ok(Object::nil());
-            }
+    evaluate_if(scope, args, false).map(Tail::into_value)
+}
 
-            if args.len() == 3 {
-                return if v.get_bool() {
-                    let r = evaluate(scope.enter(Mode::Evaluation), &args[1]);
-                    scope.leave();
+fn evaluate_if(scope: &mut Scope, args: &[Object], tail: bool) -> Result<Tail, Error> {
+    check_arg_range("if", args, 2, 3)?;
 
-                    r
-                } else {
-                    let r = evaluate(scope.enter(Mode::Evaluation), &args[2]);
-                    scope.leave();
+    let v = evaluate(scope, &args[0])?;
 
-                    r
-                };
-            }
+    if !v.is_bool() {
+        return Err(condition_error("if", &v, &args[0]));
+    }
 
-            unreachable!()
+    if args.len() == 2 {
+        if v.get_bool() {
+            evaluate(scope.enter(Mode::Evaluation), &args[1])?;
+            scope.leave();
         }
-        Err(v) => Err(v),
+
+        return Ok(Tail::Value(Object::nil()));
     }
+
+    let branch = if v.get_bool() {
+        &args[1..2]
+    } else {
+        &args[2..]
+    };
+    evaluate_block(scope, branch, tail)
 }
 
 /// `while` special form.
@@ -72,12 +61,7 @@ fn flow_if(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
 /// `(while cond body...)`
 ///
 fn flow_while(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
-    if args.len() < 2 {
-        return Error::err(&format!(
-            "'while' requires at least 2 arguments, got {} instead.",
-            args.len()
-        ));
-    }
+    check_min_arg_count("while", args, 2)?;
 
     let cond = &args[0];
 
@@ -85,11 +69,7 @@ fn flow_while(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
         match evaluate(scope, cond) {
             Ok(o) => {
                 if !o.is_bool() {
-                    return Error::err(&format!(
-                        "'while' requires a Bool, get a '{}' from {}",
-                        o.type_string(),
-                        cond
-                    ));
+                    return Err(condition_error("while", &o, cond));
                 }
 
                 if !o.get_bool() {
@@ -99,10 +79,7 @@ fn flow_while(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
                 scope.enter_loop(Mode::Evaluation);
 
                 for expr in args.iter().skip(1) {
-                    match evaluate(scope, expr) {
-                        Err(e) => return Err(e),
-                        Ok(_) => {}
-                    }
+                    evaluate(scope, expr)?;
                 }
 
                 if scope.is_loop_broken() {
@@ -124,11 +101,14 @@ fn flow_while(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
 /// `(break)`
 ///
 fn flow_break(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
-    if args.len() != 0 {
-        return Error::err(&format!(
-            "'break' doesn't take any argument, got {} instead.",
-            args.len()
-        ));
+    if !args.is_empty() {
+        return Error::err_kind(
+            ErrorKind::arity("break", 0, Some(0), args.len()),
+            &format!(
+                "'break' doesn't take any argument, got {} instead.",
+                args.len()
+            ),
+        );
     }
 
     if !scope.is_in_loop() {
@@ -145,19 +125,52 @@ fn flow_break(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
 /// `(do body...)`
 ///
 fn flow_do(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    evaluate_body(scope, args)
+}
+
+/// Evaluates a body in a new scope level, returning the value of its last
+/// expression (or nil if the body is empty).
+///
+pub fn evaluate_body(scope: &mut Scope, body: &[Object]) -> Result<Object, Error> {
+    evaluate_block(scope, body, false).map(Tail::into_value)
+}
+
+/// Evaluates a body in a new scope level, the last expression in tail
+/// position if `tail` is true. The level stays open if a call is pending.
+///
+fn evaluate_block(scope: &mut Scope, body: &[Object], tail: bool) -> Result<Tail, Error> {
     scope.enter(Mode::Evaluation);
 
-    let mut result = Object::nil();
+    let result = evaluate_last(scope, body, tail);
 
-    for expr in args {
-        match evaluate(scope, expr) {
-            Ok(v) => result = v,
-            Err(v) => return Err(v),
-        }
+    if !matches!(result, Ok(Tail::Call(_))) {
+        scope.leave();
     }
 
-    scope.leave();
-    Ok(result)
+    result
+}
+
+/// Evaluates a flow control form in tail position of a custom form body.
+///
+/// Returns `None` if the form doesn't pass the tail position on to one of
+/// its expressions.
+///
+pub(crate) fn evaluate_tail_form(
+    scope: &mut Scope,
+    name: &str,
+    args: &[Object],
+) -> Option<Result<Tail, Error>> {
+    let result = match name {
+        "if" => evaluate_if(scope, args, true),
+        "do" => evaluate_block(scope, args, true),
+        "let" => evaluate_let(scope, args, true),
+        "when" | "unless" => evaluate_when(scope, name, args, true),
+        "cond" => evaluate_cond(scope, args, true),
+        "case" => evaluate_case(scope, args, true),
+        _ => return None,
+    };
+
+    Some(result)
 }
 
 /// `let` special form.
@@ -165,30 +178,24 @@ fn flow_do(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
 /// `(let (var0 expr0 var1 expr1 ...) body...)`
 ///
 fn flow_let(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
-    if args.len() < 2 {
-        return Error::err(&format!(
-            "'let' requires at least 2 arguments, got {} instead.",
-            args.len()
-        ));
-    }
+    evaluate_let(scope, args, false).map(Tail::into_value)
+}
+
+fn evaluate_let(scope: &mut Scope, args: &[Object], tail: bool) -> Result<Tail, Error> {
+    check_min_arg_count("let", args, 2)?;
 
     scope.enter(Mode::Evaluation);
 
-    match flow_let_define_bindings(scope, &args[0]) {
-        Some(err) => return Err(err),
-        None => {}
+    if let Some(err) = flow_let_define_bindings(scope, &args[0]) {
+        return Err(err);
     }
 
-    let mut result = Object::nil();
+    let result = evaluate_last(scope, &args[1..], tail)?;
 
-    for expr in args.iter().skip(1) {
-        match evaluate(scope, expr) {
-            Ok(v) => result = v,
-            Err(v) => return Err(v),
-        }
+    if let Tail::Value(_) = result {
+        scope.leave();
     }
 
-    scope.leave();
     Ok(result)
 }
 
@@ -196,19 +203,25 @@ fn flow_let(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
 ///
 fn flow_let_define_bindings(scope: &mut Scope, list: &Object) -> Option<Error> {
     if !list.is_list() {
-        return Some(Error::new(&format!(
-            "invalid 'let' syntax at {}.\nSyntax : (let (v0 expr0 v1 expr1 ...) body...)",
-            list
-        )));
+        return Some(Error::new_kind(
+            ErrorKind::Form,
+            &format!(
+                "invalid 'let' syntax at {}.\nSyntax : (let (v0 expr0 v1 expr1 ...) body...)",
+                list
+            ),
+        ));
     }
 
     let vec = list.get_list();
 
-    if vec.len() % 2 != 0 {
-        return Some(Error::new(&format!(
-            "invalid 'let' syntax at {}.\nSyntax : (let (v0 expr0 v1 expr1 ...) body...)",
-            list
-        )));
+    if !vec.len().is_multiple_of(2) {
+        return Some(Error::new_kind(
+            ErrorKind::Form,
+            &format!(
+                "invalid 'let' syntax at {}.\nSyntax : (let (v0 expr0 v1 expr1 ...) body...)",
+                list
+            ),
+        ));
     }
 
     let mut i = 0;
@@ -217,10 +230,13 @@ fn flow_let_define_bindings(scope: &mut Scope, list: &Object) -> Option<Error> {
         let expr = &vec[i + 1];
 
         if !name.is_symbol() {
-            return Some(Error::new(&format!(
-                "invalid 'let' syntax at {}.\nA variable name must be a Symbol.",
-                name
-            )));
+            return Some(Error::new_kind(
+                ErrorKind::Form,
+                &format!(
+                    "invalid 'let' syntax at {}.\nA variable name must be a Symbol.",
+                    name
+                ),
+            ));
         }
 
         let binding = Binding::DynamicVariable(match evaluate(scope, expr) {
@@ -235,3 +251,259 @@ fn flow_let_define_bindings(scope: &mut Scope, list: &Object) -> Option<Error> {
 
     None
 }
+
+/// Evaluates the condition of a flow control form, which must be a Bool.
+///
+pub fn evaluate_condition(scope: &mut Scope, form: &str, cond: &Object) -> Result<bool, Error> {
+    let v = evaluate(scope, cond)?;
+
+    match v.as_bool() {
+        Some(b) => Ok(b),
+        None => Err(condition_error(form, &v, cond)),
+    }
+}
+
+/// Builds the error of a condition which isn't a Bool. `if` and `while`
+/// report the value, the other forms report the condition and its location.
+///
+pub(crate) fn condition_error(form: &str, value: &Object, cond: &Object) -> Error {
+    let kind = ErrorKind::wrong_type("Bool", value);
+
+    match form {
+        "if" => Error::new_kind(
+            kind,
+            &format!(
+                "Expected an Bool, got a '{}' from {}",
+                value.type_string(),
+                value
+            ),
+        ),
+        "while" => Error::new_kind(
+            kind,
+            &format!(
+                "'while' requires a Bool, get a '{}' from {}",
+                value.type_string(),
+                cond
+            ),
+        ),
+        _ => Error::new_kind(
+            kind,
+            &format!(
+                "'{}' requires a Bool, got a '{}' from {}",
+                form,
+                value.type_string(),
+                cond
+            ),
+        )
+        .push(form, cond.get_info().location.clone()),
+    }
+}
+
+/// `when` special form.
+///
+/// `(when cond body...)`
+///
+fn flow_when(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    evaluate_when(scope, "when", args, false).map(Tail::into_value)
+}
+
+/// `unless` special form.
+///
+/// `(unless cond body...)`
+///
+fn flow_unless(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    evaluate_when(scope, "unless", args, false).map(Tail::into_value)
+}
+
+/// Evaluates `when`, or `unless` whose body is evaluated if its condition
+/// is false.
+///
+fn evaluate_when(
+    scope: &mut Scope,
+    form: &str,
+    args: &[Object],
+    tail: bool,
+) -> Result<Tail, Error> {
+    check_min_arg_count(form, args, 1)?;
+
+    if evaluate_condition(scope, form, &args[0])? == (form == "when") {
+        evaluate_block(scope, &args[1..], tail)
+    } else {
+        Ok(Tail::Value(Object::nil()))
+    }
+}
+
+/// Checks if a clause of `cond` or `case` is an `else` clause.
+///
+fn is_else_clause(clause: &[Object]) -> bool {
+    match clause.first() {
+        Some(Object::Symbol(_, v)) => v == "else",
+        _ => false,
+    }
+}
+
+/// Gets the clauses of `cond` or `case`, checking that they are non-empty
+/// lists and that the `else` clause is the last one.
+///
+fn get_clauses<'a>(form: &str, args: &'a [Object]) -> Result<Vec<&'a [Object]>, Error> {
+    let mut result = Vec::with_capacity(args.len());
+
+    for (i, arg) in args.iter().enumerate() {
+        let clause = match arg.as_list() {
+            Some(v) if !v.is_empty() => v,
+            _ => {
+                return Error::new_kind(
+                    ErrorKind::Form,
+                    &format!(
+                        "invalid '{}' syntax at {}.\nA clause must be a non-empty list.",
+                        form, arg
+                    ),
+                )
+                .push_err(form, arg.get_info().location.clone())
+            }
+        };
+
+        if is_else_clause(clause) && i + 1 != args.len() {
+            return Error::new_kind(
+                ErrorKind::Form,
+                &format!(
+                    "invalid '{}' syntax at {}.\nThe 'else' clause must be the last one.",
+                    form, arg
+                ),
+            )
+            .push_err(form, arg.get_info().location.clone());
+        }
+
+        result.push(&clause[..]);
+    }
+
+    Ok(result)
+}
+
+/// `cond` special form.
+///
+/// A clause without body returns the value of its test.
+///
+/// `(cond (test0 body0...) (test1 body1...) ... (else body...))`
+///
+fn flow_cond(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    evaluate_cond(scope, args, false).map(Tail::into_value)
+}
+
+fn evaluate_cond(scope: &mut Scope, args: &[Object], tail: bool) -> Result<Tail, Error> {
+    for clause in get_clauses("cond", args)? {
+        if is_else_clause(clause) {
+            return evaluate_block(scope, &clause[1..], tail);
+        }
+
+        if evaluate_condition(scope, "cond", &clause[0])? {
+            if clause.len() == 1 {
+                return Ok(Tail::Value(Object::bool(true)));
+            }
+
+            return evaluate_block(scope, &clause[1..], tail);
+        }
+    }
+
+    Ok(Tail::Value(Object::nil()))
+}
+
+/// Checks if a `case` key matches a value. Only Integer, Char, Keyword and
+/// String keys are allowed.
+///
+fn case_key_matches(key: &Object, value: &Object) -> Result<bool, Error> {
+    match (key, value) {
+        (Object::Integer(_, a), Object::Integer(_, b)) => Ok(a == b),
+        (Object::Char(_, a), Object::Char(_, b)) => Ok(a == b),
+        (Object::Keyword(_, a), Object::Keyword(_, b)) => Ok(a == b),
+        (Object::String(_, a), Object::String(_, b)) => Ok(a == b),
+        (Object::Integer(_, _), _)
+        | (Object::Char(_, _), _)
+        | (Object::Keyword(_, _), _)
+        | (Object::String(_, _), _) => Ok(false),
+        _ => Error::errf(
+            &format!(
+                "'case' only accepts Integer, Char, Keyword or String keys, got a {} : {}",
+                key.type_string(),
+                key
+            ),
+            "case",
+            key.get_info().location.clone(),
+        ),
+    }
+}
+
+/// `case` special form.
+///
+/// `(case expr (key0 body0...) ((key1 key2) body1...) ... (else body...))`
+///
+fn flow_case(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    evaluate_case(scope, args, false).map(Tail::into_value)
+}
+
+fn evaluate_case(scope: &mut Scope, args: &[Object], tail: bool) -> Result<Tail, Error> {
+    check_min_arg_count("case", args, 1)?;
+
+    let value = evaluate(scope, &args[0])?;
+
+    for clause in get_clauses("case", &args[1..])? {
+        if is_else_clause(clause) {
+            return evaluate_block(scope, &clause[1..], tail);
+        }
+
+        let keys = match &clause[0] {
+            Object::List(_, v) => &v[..],
+            _ => &clause[..1],
+        };
+
+        for key in keys {
+            if case_key_matches(key, &value)? {
+                return evaluate_block(scope, &clause[1..], tail);
+            }
+        }
+    }
+
+    Ok(Tail::Value(Object::nil()))
+}
+
+/// `and` special form.
+///
+/// Stops at the first false argument.
+///
+/// `(and a b ...)`
+///
+fn flow_and(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    for arg in args {
+        if !evaluate_condition(scope, "and", arg)? {
+            return Ok(Object::bool(false));
+        }
+    }
+
+    Ok(Object::bool(true))
+}
+
+/// `or` special form.
+///
+/// Stops at the first true argument.
+///
+/// `(or a b ...)`
+///
+fn flow_or(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    for arg in args {
+        if evaluate_condition(scope, "or", arg)? {
+            return Ok(Object::bool(true));
+        }
+    }
+
+    Ok(Object::bool(false))
+}
+
+/// `not` special form.
+///
+/// `(not a)`
+///
+fn flow_not(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    check_arg_count("not", args, 1)?;
+
+    Ok(Object::bool(!evaluate_condition(scope, "not", &args[0])?))
+}
@@ -1,10 +1,23 @@
 use crate::nl::core::object::*;
 use crate::nl::interpreter::*;
 
+use super::{check_arg_count, check_min_arg_count};
+
+use std::cell::OnceCell;
+use std::rc::Rc;
+
 /// Registers all function builtin forms.
 ///
 pub fn register_builtin_function_forms(scope: &mut Scope) {
-    scope.register_special_form("defndynamic", defndynamic);
+    scope
+        .register_special_form("defndynamic", defndynamic)
+        .register_special_form("defmacro", defmacro)
+        .register_special_form("macroexpand", form_macroexpand)
+        .register_special_form("macroexpand-1", form_macroexpand_1)
+        .register_special_form("fn", lambda)
+        .register_special_form("lambda", lambda)
+        .register_special_form("funcall", funcall)
+        .register_special_form("apply", apply);
 }
 
 /// `defndynamic` special form.
@@ -12,71 +25,376 @@ pub fn register_builtin_function_forms(scope: &mut Scope) {
 /// `(defndynamic name (arg0 arg1 ...) body...)`
 ///
 pub fn defndynamic(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
-    if args.len() < 3 {
-        return Error::errf(
-            &format!(
-                "'defndynamic' requires at least 3 arguments, got {} instead.",
-                args.len()
-            ),
-            "defndynamic",
-            intern_location!(),
-        );
+    let (name, form) = parse_custom_form("defndynamic", args)?;
+
+    scope.insert(name, Binding::DynamicForm(Rc::new(form)));
+
+    Ok(Object::nil())
+}
+
+/// `defmacro` special form.
+///
+/// The arguments of a macro are passed unevaluated, and the object returned
+/// by its body is evaluated in the scope of the caller.
+///
+/// `(defmacro name (arg0 arg1 ...) body...)`
+///
+pub fn defmacro(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    let (name, form) = parse_custom_form("defmacro", args)?;
+
+    scope.insert(name, Binding::MacroForm(Rc::new(form)));
+
+    Ok(Object::nil())
+}
+
+/// `macroexpand-1` special form.
+///
+/// Evaluates its argument, then expands it once if it is a macro call,
+/// without evaluating the expansion.
+///
+/// `(macroexpand-1 '(macro-name args...))`
+///
+fn form_macroexpand_1(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    check_arg_count("macroexpand-1", args, 1)?;
+
+    let object = evaluate(scope, &args[0])?;
+
+    match macroexpand_1(scope, &object)? {
+        Some(v) => Ok(v),
+        None => Ok(object),
     }
+}
+
+/// `macroexpand` special form.
+///
+/// Evaluates its argument, then expands it until it isn't a macro call
+/// anymore, without evaluating the expansion.
+///
+/// `(macroexpand '(macro-name args...))`
+///
+fn form_macroexpand(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    check_arg_count("macroexpand", args, 1)?;
+
+    let object = evaluate(scope, &args[0])?;
+
+    macroexpand(scope, &object)
+}
+
+/// Parses the arguments of the custom form definition forms
+/// (`defndynamic` and `defmacro`).
+///
+/// `(form name (arg0 arg1 ...) body...)`
+///
+fn parse_custom_form(form: &str, args: &[Object]) -> Result<(String, CustomForm), Error> {
+    check_min_arg_count(form, args, 3)?;
 
     if !args[0].is_symbol() {
-        return Error::errf(
+        return Error::new_kind(
+            ErrorKind::Form,
             &format!(
-                "'defndynamic' requires a symbol as its first argument, got : {}",
-                args[0]
+                "'{}' requires a symbol as its first argument, got : {}",
+                form, args[0]
             ),
-            "defndynamic",
-            intern_location!(),
-        );
+        )
+        .push_err(form, intern_location!());
     }
 
     let name = args[0].get_symbol().clone();
 
     if !args[1].is_list() {
-        return Error::errf(
+        return Error::new_kind(
+            ErrorKind::Form,
             &format!(
-                "'defndynamic' requires a list of symbol as its second argument, got : {}",
+                "'{}' requires a list of symbol as its second argument, got : {}",
+                form, args[1]
+            ),
+        )
+        .push_err(form, intern_location!());
+    }
+
+    let arguments = parse_argument_list(form, args[1].get_list())?;
+    let mut body = Vec::new();
+
+    for obj in args.iter().skip(2) {
+        body.push(obj.clone());
+    }
+
+    Ok((
+        name,
+        CustomForm {
+            location: args[0].get_info().location.clone(),
+            arguments,
+            body,
+            code: OnceCell::new(),
+        },
+    ))
+}
+
+/// `fn` and `lambda` special forms.
+///
+/// Creates a function capturing the current environment.
+///
+/// `(fn (arg0 arg1 ...) body...)`
+///
+fn lambda(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    check_min_arg_count("fn", args, 2)?;
+
+    if !args[0].is_list() {
+        return Error::new_kind(
+            ErrorKind::Form,
+            &format!(
+                "'fn' requires a list of symbol as its first argument, got : {}",
                 args[0]
             ),
-            "defndynamic",
-            intern_location!(),
+        )
+        .push_err("fn", intern_location!());
+    }
+
+    let arguments = parse_argument_list("fn", args[0].get_list())?;
+
+    let function = Function {
+        name: None,
+        form: Rc::new(CustomForm {
+            location: args[0].get_info().location.clone(),
+            arguments,
+            body: args[1..].to_vec(),
+            code: OnceCell::new(),
+        }),
+        environment: scope.capture(),
+    };
+
+    Ok(Object::function(function))
+}
+
+/// `funcall` special form.
+///
+/// `f` is a function, or the name of an eval form or a dynamic form, given
+/// as is or as a quoted symbol.
+///
+/// `(funcall f arg0 arg1 ...)`
+///
+fn funcall(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    check_min_arg_count("funcall", args, 1)?;
+
+    let callee = get_callee(scope, "funcall", &args[0])?;
+    let values = evaluate_list(scope, &args[1..])?;
+
+    call_callee(scope, callee, values)
+}
+
+/// `apply` special form.
+///
+/// `f` is given like in `funcall`. The last argument must be a list, whose
+/// elements are appended to the other arguments.
+///
+/// `(apply f arg0 arg1 ... list)`
+///
+fn apply(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    check_min_arg_count("apply", args, 2)?;
+
+    let callee = get_callee(scope, "apply", &args[0])?;
+    let mut values = evaluate_list(scope, &args[1..])?;
+
+    let last = values.pop().unwrap();
+
+    if !last.is_list() && !last.is_nil() {
+        return Error::err_kind(
+            ErrorKind::wrong_type("List", &last),
+            &format!(
+                "'apply' requires a List as its last argument, got a {} : {}",
+                last.type_string(),
+                last
+            ),
         );
     }
 
-    let mut dynform_args = Vec::new();
-    let mut dynform_body = Vec::new();
+    values.extend(last.to_list());
+
+    call_callee(scope, callee, values)
+}
+
+/// Operator called by `funcall` or `apply`.
+///
+enum Callee {
+    Function(Rc<Function>),
+    /// An eval form, along with the symbol naming it.
+    Eval(Object, EvalForm),
+    /// A dynamic form, along with the symbol naming it.
+    Dynamic(Object, Rc<CustomForm>),
+}
+
+/// Gets the operator to call from the first argument of `funcall` or
+/// `apply` : a symbol naming a form is used as is, any other argument is
+/// evaluated.
+///
+fn get_callee(scope: &mut Scope, form: &str, object: &Object) -> Result<Callee, Error> {
+    if let Some(v) = get_named_form(scope, object) {
+        return Ok(v);
+    }
+
+    let value = evaluate(scope, object)?;
+
+    if let Some(v) = get_named_form(scope, &value) {
+        return Ok(v);
+    }
+
+    match value.as_function() {
+        Some(v) => Ok(Callee::Function(v.clone())),
+        None => Error::err_kind(
+            ErrorKind::wrong_type("Function", &value),
+            &format!(
+                "'{}' requires a Function or the name of a form as its first argument, got a {} : {}",
+                form,
+                value.type_string(),
+                value
+            ),
+        ),
+    }
+}
+
+/// Gets the eval form or the dynamic form named by a symbol.
+///
+fn get_named_form(scope: &Scope, object: &Object) -> Option<Callee> {
+    match scope.get_binding(object.as_symbol()?)? {
+        Binding::EvalForm(f) => Some(Callee::Eval(object.clone(), f)),
+        Binding::DynamicForm(form) => Some(Callee::Dynamic(object.clone(), form)),
+        _ => None,
+    }
+}
+
+/// Calls the operator of `funcall` or `apply` with evaluated arguments.
+///
+fn call_callee(scope: &mut Scope, callee: Callee, args: Vec<Object>) -> Result<Object, Error> {
+    let (symbol, result) = match callee {
+        Callee::Function(f) => return call_function(scope, &f, args),
+        Callee::Eval(symbol, f) => {
+            let result = f(args);
+            (symbol, result)
+        }
+        Callee::Dynamic(symbol, form) => {
+            let location = symbol.get_info().location.clone();
+            let result = call_dynamic_form(scope, symbol.get_symbol(), &location, &form, args);
+            (symbol, result)
+        }
+    };
+
+    Error::rethrow(
+        result,
+        symbol.get_symbol(),
+        symbol.get_info().location.clone(),
+    )
+}
+
+/// Section of an argument list being parsed.
+///
+#[derive(Clone, Copy, PartialEq)]
+enum ArgumentSection {
+    Required,
+    Optional,
+    Rest,
+    Key,
+}
+
+/// Parses the argument list of a custom form.
+///
+/// `(req0 req1 &optional opt0 (opt1 default) &rest rest &key key0 (key1 default))`
+///
+pub fn parse_argument_list(form: &str, list: &[Object]) -> Result<ArgumentList, Error> {
+    let mut result = ArgumentList::default();
+    let mut section = ArgumentSection::Required;
+    let mut names: Vec<&String> = Vec::new();
 
-    for (i, arg) in args[1].get_list().iter().enumerate() {
-        if !arg.is_symbol() {
-            return Error::errf(
+    for (i, arg) in list.iter().enumerate() {
+        let syntax_error = |message: &str| {
+            Error::new_kind(
+                ErrorKind::Form,
                 &format!(
-                    "'defndynamic' only accepts symbols for the argument list, for the {} argument, got : {}",
-                    i+1, arg
+                    "'{}' : invalid argument list {}, for the {} argument : {}",
+                    form,
+                    Object::list(list.to_vec()),
+                    i + 1,
+                    message
                 ),
-                "defndynamic",
-                intern_location!()
-            );
+            )
+            .push_err(form, arg.get_info().location.clone())
+        };
+
+        let (name, default) = match arg {
+            Object::Symbol(_, v) if v.starts_with('&') => {
+                let next = match v.as_str() {
+                    "&optional" => ArgumentSection::Optional,
+                    "&rest" => ArgumentSection::Rest,
+                    "&key" => ArgumentSection::Key,
+                    _ => return syntax_error(&format!("unknown marker '{}'.", v)),
+                };
+
+                if next as u8 <= section as u8 {
+                    return syntax_error(&format!(
+                        "'{}' is misplaced, the expected order is &optional, &rest then &key.",
+                        v
+                    ));
+                }
+
+                if section == ArgumentSection::Rest && result.rest.is_none() {
+                    return syntax_error("'&rest' must be followed by a symbol.");
+                }
+
+                section = next;
+                continue;
+            }
+            Object::Symbol(_, v) => (v, None),
+            Object::List(_, v)
+                if section == ArgumentSection::Optional || section == ArgumentSection::Key =>
+            {
+                if v.len() != 2 || !v[0].is_symbol() {
+                    return syntax_error(&format!(
+                        "expected a symbol or a (symbol default) pair, got : {}",
+                        arg
+                    ));
+                }
+
+                (v[0].get_symbol(), Some(v[1].clone()))
+            }
+            _ => return syntax_error(&format!("expected a symbol, got : {}", arg)),
+        };
+
+        if names.contains(&name) {
+            return syntax_error(&format!("'{}' is defined twice.", name));
         }
 
-        dynform_args.push(arg.get_symbol().clone());
-    }
+        names.push(name);
 
-    for obj in args.iter().skip(2) {
-        dynform_body.push(obj.clone());
+        match section {
+            ArgumentSection::Required => result.required.push(name.clone()),
+            ArgumentSection::Optional => result.optional.push(OptionalArgument {
+                name: name.clone(),
+                default,
+            }),
+            ArgumentSection::Rest => {
+                if result.rest.is_some() {
+                    return syntax_error("'&rest' must be followed by a single symbol.");
+                }
+
+                result.rest = Some(name.clone());
+            }
+            ArgumentSection::Key => result.keys.push(OptionalArgument {
+                name: name.clone(),
+                default,
+            }),
+        }
     }
 
-    scope.insert(
-        name,
-        Binding::DynamicForm(CustomForm {
-            location: args[0].get_info().location.clone(),
-            arguments: dynform_args,
-            body: dynform_body,
-        }),
-    );
+    if section == ArgumentSection::Rest && result.rest.is_none() {
+        return Error::new_kind(
+            ErrorKind::Form,
+            &format!(
+                "'{}' : invalid argument list {}, '&rest' must be followed by a symbol.",
+                form,
+                Object::list(list.to_vec())
+            ),
+        )
+        .push_err(form, intern_location!());
+    }
 
-    Ok(Object::nil())
+    Ok(result)
 }
@@ -1,6 +1,8 @@
 use crate::nl::core::object::*;
 use crate::nl::interpreter::*;
 
+use super::check_arg_count;
+
 /// Registers all I/O builtin forms.
 ///
 pub fn register_builtin_io_forms(scope: &mut Scope) {
@@ -14,16 +16,7 @@ pub fn register_builtin_io_forms(scope: &mut Scope) {
 /// `(to-string v)`
 ///
 fn to_string(args: Vec<Object>) -> Result<Object, Error> {
-    if args.len() != 1 {
-        return Error::errf(
-            &format!(
-                "'to-string' only receives 1 argument, got {} instead.",
-                args.len()
-            ),
-            "to-string",
-            intern_location!(),
-        );
-    }
+    check_arg_count("to-string", &args, 1)?;
 
     Ok(Object::string(format!("{}", args[0])))
 }
@@ -33,26 +26,17 @@ fn to_string(args: Vec<Object>) -> Result<Object, Error> {
 /// `(println "text")`
 ///
 fn println(args: Vec<Object>) -> Result<Object, Error> {
-    if args.len() != 1 {
-        return Error::errf(
-            &format!(
-                "'println' only receives 1 argument, got {} instead.",
-                args.len()
-            ),
-            "to-string",
-            intern_location!(),
-        );
-    }
+    check_arg_count("println", &args, 1)?;
 
     if !&args[0].is_string() {
-        return Error::errf(
+        return Error::new_kind(
+            ErrorKind::wrong_type("String", &args[0]),
             &format!(
                 "'println' only receives a String, got a {} instead.",
                 &args[0].type_string()
             ),
-            "to-string",
-            intern_location!(),
-        );
+        )
+        .push_err("to-string", intern_location!());
     }
 
     println!("{}", &args[0].get_string());
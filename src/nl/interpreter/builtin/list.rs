@@ -0,0 +1,304 @@
+use crate::nl::core::object::*;
+use crate::nl::interpreter::*;
+
+use super::{check_arg_count, check_arg_range, check_min_arg_count, get_index_argument};
+
+use std::cmp::Ordering;
+
+/// Registers all list manipulation builtin forms.
+///
+/// Every form receiving a list also accepts `nil`, treated as the empty
+/// list. Every form returning a list returns the empty list `()`, never
+/// `nil`, when the result is empty.
+///
+pub fn register_builtin_list_forms(scope: &mut Scope) {
+    scope
+        .register_eval_form("list", list)
+        .register_eval_form("cons", cons)
+        .register_eval_form("first", first)
+        .register_eval_form("rest", rest)
+        .register_eval_form("nth", nth)
+        .register_eval_form("length", length)
+        .register_eval_form("append", append)
+        .register_eval_form("reverse", reverse)
+        .register_eval_form("concat", concat)
+        .register_eval_form("slice", slice)
+        .register_eval_form("contains?", contains)
+        .register_eval_form("index-of", index_of)
+        .register_eval_form("sort", sort);
+}
+
+/// Gets the elements of a list argument, `nil` being the empty list.
+///
+fn get_list_argument(form: &str, args: &[Object], index: usize) -> Result<Vec<Object>, Error> {
+    match &args[index] {
+        Object::List(_, _) | Object::Nil(_) => Ok(args[index].to_list()),
+        v => Error::err_kind(
+            ErrorKind::wrong_type("List", v),
+            &format!(
+                "'{}' requires a List as argument {}, got a {} : {}",
+                form,
+                index + 1,
+                v.type_string(),
+                v
+            ),
+        ),
+    }
+}
+
+/// `list` eval form.
+///
+/// `(list v0 v1 ...)`
+///
+fn list(args: Vec<Object>) -> Result<Object, Error> {
+    Ok(Object::list(args))
+}
+
+/// `cons` eval form.
+///
+/// Creates a new list with the given value prepended.
+///
+/// `(cons v list)`
+///
+fn cons(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("cons", &args, 2)?;
+
+    let mut result = vec![args[0].clone()];
+    result.extend(get_list_argument("cons", &args, 1)?);
+
+    Ok(Object::list(result))
+}
+
+/// `first` eval form.
+///
+/// Returns `nil` if the list is empty.
+///
+/// `(first list)`
+///
+fn first(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("first", &args, 1)?;
+
+    let list = get_list_argument("first", &args, 0)?;
+
+    match list.into_iter().next() {
+        Some(v) => Ok(v),
+        None => Ok(Object::nil()),
+    }
+}
+
+/// `rest` eval form.
+///
+/// Returns the list without its first element, or the empty list if the
+/// list is empty.
+///
+/// `(rest list)`
+///
+fn rest(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("rest", &args, 1)?;
+
+    let list = get_list_argument("rest", &args, 0)?;
+
+    Ok(Object::list(list.into_iter().skip(1).collect()))
+}
+
+/// `nth` eval form.
+///
+/// `(nth list index)`
+///
+fn nth(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("nth", &args, 2)?;
+
+    let list = get_list_argument("nth", &args, 0)?;
+    let index = get_index_argument("nth", &args, 1, list.len())?;
+
+    Ok(list[index].clone())
+}
+
+/// `length` eval form.
+///
+/// `(length list)`
+///
+fn length(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("length", &args, 1)?;
+
+    let list = get_list_argument("length", &args, 0)?;
+
+    Ok(Object::integer(list.len() as i64))
+}
+
+/// `append` eval form.
+///
+/// Creates a new list with the given values appended.
+///
+/// `(append list v0 v1 ...)`
+///
+fn append(args: Vec<Object>) -> Result<Object, Error> {
+    check_min_arg_count("append", &args, 1)?;
+
+    let mut result = get_list_argument("append", &args, 0)?;
+    result.extend(args[1..].iter().cloned());
+
+    Ok(Object::list(result))
+}
+
+/// `reverse` eval form.
+///
+/// `(reverse list)`
+///
+fn reverse(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("reverse", &args, 1)?;
+
+    let mut result = get_list_argument("reverse", &args, 0)?;
+    result.reverse();
+
+    Ok(Object::list(result))
+}
+
+/// `concat` eval form.
+///
+/// `(concat list0 list1 ...)`
+///
+fn concat(args: Vec<Object>) -> Result<Object, Error> {
+    let mut result = Vec::new();
+
+    for i in 0..args.len() {
+        result.extend(get_list_argument("concat", &args, i)?);
+    }
+
+    Ok(Object::list(result))
+}
+
+/// `slice` eval form.
+///
+/// Returns the elements from `start` (included) to `end` (excluded, the
+/// end of the list by default).
+///
+/// `(slice list start)`
+/// `(slice list start end)`
+///
+fn slice(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_range("slice", &args, 2, 3)?;
+
+    let list = get_list_argument("slice", &args, 0)?;
+    let start = get_index_argument("slice", &args, 1, list.len() + 1)?;
+    let end = if args.len() == 3 {
+        get_index_argument("slice", &args, 2, list.len() + 1)?
+    } else {
+        list.len()
+    };
+
+    if start > end {
+        return Error::err_kind(
+            ErrorKind::range(start as i64, 0, end + 1),
+            &format!(
+                "'slice' : start index {} is greater than end index {}.",
+                start, end
+            ),
+        );
+    }
+
+    Ok(Object::list(list[start..end].to_vec()))
+}
+
+/// `contains?` eval form.
+///
+/// Checks if a List or a Set contains an element, or if a Map contains a
+/// key.
+///
+/// `(contains? list v)`
+/// `(contains? set v)`
+/// `(contains? map key)`
+///
+fn contains(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("contains?", &args, 2)?;
+
+    match &args[0] {
+        Object::Map(_, v) => Ok(Object::bool(v.contains_key(&args[1]))),
+        Object::Set(_, v) => Ok(Object::bool(v.contains(&args[1]))),
+        _ => {
+            let list = get_list_argument("contains?", &args, 0)?;
+
+            Ok(Object::bool(list.contains(&args[1])))
+        }
+    }
+}
+
+/// `index-of` eval form.
+///
+/// Returns the index of the first element equal to the given value, or
+/// `nil` if there is none.
+///
+/// `(index-of list v)`
+///
+fn index_of(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("index-of", &args, 2)?;
+
+    let list = get_list_argument("index-of", &args, 0)?;
+
+    match list.iter().position(|x| *x == args[1]) {
+        Some(i) => Ok(Object::integer(i as i64)),
+        None => Ok(Object::nil()),
+    }
+}
+
+/// `sort` eval form.
+///
+/// Sorts the list in ascending order. The elements must all be numbers,
+/// or all be Chars, Strings, Keywords or Symbols.
+///
+/// `(sort list)`
+///
+fn sort(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("sort", &args, 1)?;
+
+    let mut list = get_list_argument("sort", &args, 0)?;
+
+    for pair in list.windows(2) {
+        if compare(&pair[0], &pair[1]).is_none() {
+            return Error::err(&format!(
+                "'sort' can't compare {} with {}.",
+                pair[0], pair[1]
+            ));
+        }
+    }
+
+    if let Some(v) = list.iter().find(|x| compare(x, x).is_none()) {
+        return Error::err(&format!(
+            "'sort' can't compare a {} : {}",
+            v.type_string(),
+            v
+        ));
+    }
+
+    list.sort_by(|a, b| compare(a, b).unwrap_or(Ordering::Equal));
+
+    Ok(Object::list(list))
+}
+
+/// Compares two objects of comparable types.
+///
+fn compare(a: &Object, b: &Object) -> Option<Ordering> {
+    match (a, b) {
+        (Object::Integer(_, x), Object::Integer(_, y)) => Some(x.cmp(y)),
+        (Object::Float(_, x), Object::Float(_, y)) => x.partial_cmp(y),
+        (
+            Object::Integer(_, _) | Object::Bignum(_, _) | Object::Rational(_, _),
+            Object::Integer(_, _) | Object::Bignum(_, _) | Object::Rational(_, _),
+        ) => Some(a.to_rational().cmp(&b.to_rational())),
+        (
+            Object::Integer(_, _)
+            | Object::Bignum(_, _)
+            | Object::Rational(_, _)
+            | Object::Float(_, _),
+            Object::Integer(_, _)
+            | Object::Bignum(_, _)
+            | Object::Rational(_, _)
+            | Object::Float(_, _),
+        ) => a.to_float().partial_cmp(&b.to_float()),
+        (Object::Char(_, x), Object::Char(_, y)) => Some(x.cmp(y)),
+        (Object::String(_, x), Object::String(_, y)) => Some(x.cmp(y)),
+        (Object::Keyword(_, x), Object::Keyword(_, y)) => Some(x.cmp(y)),
+        (Object::Symbol(_, x), Object::Symbol(_, y)) => Some(x.cmp(y)),
+        _ => None,
+    }
+}
@@ -0,0 +1,237 @@
+use crate::nl::core::object::*;
+use crate::nl::interpreter::*;
+
+use super::{check_arg_count, check_arg_range, check_min_arg_count};
+
+use indexmap::{IndexMap, IndexSet};
+
+/// Registers all map and set builtin forms.
+///
+/// Maps and sets are never mutated, these forms return new objects.
+///
+pub fn register_builtin_map_forms(scope: &mut Scope) {
+    scope
+        .register_eval_form("get", get)
+        .register_eval_form("assoc", assoc)
+        .register_eval_form("dissoc", dissoc)
+        .register_eval_form("keys", keys)
+        .register_eval_form("vals", vals)
+        .register_eval_form("merge", merge)
+        .register_eval_form("union", union)
+        .register_eval_form("intersection", intersection);
+}
+
+/// Gets a Map argument.
+///
+fn get_map_argument<'a>(
+    form: &str,
+    args: &'a [Object],
+    index: usize,
+) -> Result<&'a IndexMap<Object, Object>, Error> {
+    match &args[index] {
+        Object::Map(_, v) => Ok(v),
+        v => Error::err_kind(
+            ErrorKind::wrong_type("Map", v),
+            &format!(
+                "'{}' requires a Map as argument {}, got a {} : {}",
+                form,
+                index + 1,
+                v.type_string(),
+                v
+            ),
+        ),
+    }
+}
+
+/// Gets a Set argument.
+///
+fn get_set_argument<'a>(
+    form: &str,
+    args: &'a [Object],
+    index: usize,
+) -> Result<&'a IndexSet<Object>, Error> {
+    match &args[index] {
+        Object::Set(_, v) => Ok(v),
+        v => Error::err_kind(
+            ErrorKind::wrong_type("Set", v),
+            &format!(
+                "'{}' requires a Set as argument {}, got a {} : {}",
+                form,
+                index + 1,
+                v.type_string(),
+                v
+            ),
+        ),
+    }
+}
+
+/// `get` eval form.
+///
+/// Gets the value associated with a key in a Map, or the element equal to
+/// the key in a Set. Returns the default value (`nil` by default) if there
+/// is none.
+///
+/// `(get map key)`
+/// `(get map key default)`
+///
+fn get(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_range("get", &args, 2, 3)?;
+
+    let found = match &args[0] {
+        Object::Map(_, v) => v.get(&args[1]),
+        Object::Set(_, v) => v.get(&args[1]),
+        v => {
+            return Error::err_kind(
+                ErrorKind::wrong_type("Map or Set", v),
+                &format!(
+                    "'get' requires a Map or a Set as argument 1, got a {} : {}",
+                    v.type_string(),
+                    v
+                ),
+            )
+        }
+    };
+
+    match (found, args.get(2)) {
+        (Some(v), _) => Ok(v.clone()),
+        (None, Some(default)) => Ok(default.clone()),
+        (None, None) => Ok(Object::nil()),
+    }
+}
+
+/// `assoc` eval form.
+///
+/// `(assoc map key0 value0 key1 value1 ...)`
+///
+fn assoc(args: Vec<Object>) -> Result<Object, Error> {
+    if args.len() < 3 || args.len().is_multiple_of(2) {
+        return Error::err_kind(
+            ErrorKind::arity("assoc", 3, None, args.len()),
+            &format!(
+                "'assoc' requires a Map followed by key value pairs, got {} arguments instead.",
+                args.len()
+            ),
+        );
+    }
+
+    let mut result = get_map_argument("assoc", &args, 0)?.clone();
+
+    for pair in args[1..].chunks(2) {
+        result.insert(pair[0].clone(), pair[1].clone());
+    }
+
+    Ok(Object::map(result))
+}
+
+/// `dissoc` eval form.
+///
+/// Removes keys from a Map, or elements from a Set.
+///
+/// `(dissoc map key0 key1 ...)`
+/// `(dissoc set v0 v1 ...)`
+///
+fn dissoc(args: Vec<Object>) -> Result<Object, Error> {
+    check_min_arg_count("dissoc", &args, 1)?;
+
+    match &args[0] {
+        Object::Map(_, v) => {
+            let mut result = v.clone();
+
+            for key in &args[1..] {
+                result.shift_remove(key);
+            }
+
+            Ok(Object::map(result))
+        }
+        Object::Set(_, v) => {
+            let mut result = v.clone();
+
+            for x in &args[1..] {
+                result.shift_remove(x);
+            }
+
+            Ok(Object::set(result))
+        }
+        v => Error::err_kind(
+            ErrorKind::wrong_type("Map or Set", v),
+            &format!(
+                "'dissoc' requires a Map or a Set as argument 1, got a {} : {}",
+                v.type_string(),
+                v
+            ),
+        ),
+    }
+}
+
+/// `keys` eval form.
+///
+/// `(keys map)`
+///
+fn keys(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("keys", &args, 1)?;
+
+    let map = get_map_argument("keys", &args, 0)?;
+
+    Ok(Object::list(map.keys().cloned().collect()))
+}
+
+/// `vals` eval form.
+///
+/// `(vals map)`
+///
+fn vals(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("vals", &args, 1)?;
+
+    let map = get_map_argument("vals", &args, 0)?;
+
+    Ok(Object::list(map.values().cloned().collect()))
+}
+
+/// `merge` eval form.
+///
+/// When a key is in several Maps, the value of the last one is kept.
+///
+/// `(merge map0 map1 ...)`
+///
+fn merge(args: Vec<Object>) -> Result<Object, Error> {
+    let mut result = IndexMap::new();
+
+    for i in 0..args.len() {
+        for (key, value) in get_map_argument("merge", &args, i)? {
+            result.insert(key.clone(), value.clone());
+        }
+    }
+
+    Ok(Object::map(result))
+}
+
+/// `union` eval form.
+///
+/// `(union set0 set1 ...)`
+///
+fn union(args: Vec<Object>) -> Result<Object, Error> {
+    let mut result = IndexSet::new();
+
+    for i in 0..args.len() {
+        result.extend(get_set_argument("union", &args, i)?.iter().cloned());
+    }
+
+    Ok(Object::set(result))
+}
+
+/// `intersection` eval form.
+///
+/// `(intersection set0 set1 ...)`
+///
+fn intersection(args: Vec<Object>) -> Result<Object, Error> {
+    check_min_arg_count("intersection", &args, 1)?;
+
+    let mut result = get_set_argument("intersection", &args, 0)?.clone();
+
+    for i in 1..args.len() {
+        let other = get_set_argument("intersection", &args, i)?;
+        result.retain(|x| other.contains(x));
+    }
+
+    Ok(Object::set(result))
+}
@@ -0,0 +1,509 @@
+use crate::nl::core::object::*;
+use crate::nl::interpreter::*;
+
+use super::{check_arg_count, check_min_arg_count};
+
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_rational::BigRational;
+use num_traits::{Signed, ToPrimitive, Zero};
+
+use std::cmp::Ordering;
+
+/// Registers all builtin arithmetic and comparison forms.
+///
+/// Numeric promotion rules :
+/// - if all the operands are Integers or Bignums, the result is an Integer,
+///   or a Bignum if it doesn't fit in 64 bits ;
+/// - if at least one operand is a Rational and none is a Float, the result
+///   is exact : a Rational, or an Integer if its denominator is 1 ;
+/// - if at least one operand is a Float, every operand is converted to a Float
+///   and the result is a Float.
+///
+/// Integer arithmetic never overflows : it is promoted to Bignums instead.
+/// Dividing by zero (Integer, Rational or Float) raises an error.
+///
+pub fn register_builtin_math_forms(scope: &mut Scope) {
+    scope
+        .register_eval_form("+", math_add)
+        .register_eval_form("-", math_sub)
+        .register_eval_form("*", math_mul)
+        .register_eval_form("/", math_div)
+        .register_eval_form("mod", math_mod)
+        .register_eval_form("rem", math_rem)
+        .register_eval_form("abs", math_abs)
+        .register_eval_form("min", math_min)
+        .register_eval_form("max", math_max)
+        .register_eval_form("=", math_eq)
+        .register_eval_form("/=", math_ne)
+        .register_eval_form("<", math_lt)
+        .register_eval_form("<=", math_le)
+        .register_eval_form(">", math_gt)
+        .register_eval_form(">=", math_ge);
+}
+
+/// A numeric operand, extracted from an object.
+///
+#[derive(Clone)]
+enum Number {
+    Integer(i64),
+    Big(BigInt),
+    Ratio(BigRational),
+    Float(f64),
+}
+
+/// The implementations of an arithmetic operation for each representation.
+///
+/// `int` returns `None` when the operation overflows, the operation is then
+/// done on Bignums.
+///
+struct Operation {
+    int: fn(i64, i64) -> Option<i64>,
+    big: fn(&BigInt, &BigInt) -> BigInt,
+    ratio: fn(&BigRational, &BigRational) -> BigRational,
+    float: fn(f64, f64) -> f64,
+}
+
+impl Number {
+    /// Extracts a number from the given object.
+    ///
+    fn from_object(name: &str, index: usize, object: &Object) -> Result<Number, Error> {
+        match object {
+            Object::Integer(_, v) => Ok(Number::Integer(*v)),
+            Object::Bignum(_, v) => Ok(Number::Big(v.clone())),
+            Object::Rational(_, v) => Ok(Number::Ratio(v.clone())),
+            Object::Float(_, v) => Ok(Number::Float(*v)),
+            _ => Error::err_kind(
+                ErrorKind::wrong_type("Number", object),
+                &format!(
+                    "'{}' only receives numbers, got a {} as argument {} : {}",
+                    name,
+                    object.type_string(),
+                    index + 1,
+                    object
+                ),
+            ),
+        }
+    }
+
+    fn to_float(&self) -> f64 {
+        match self {
+            Number::Integer(v) => *v as f64,
+            Number::Float(v) => *v,
+            _ => self.clone().into_object().to_float(),
+        }
+    }
+
+    fn to_big(&self) -> BigInt {
+        match self {
+            Number::Integer(v) => BigInt::from(*v),
+            Number::Big(v) => v.clone(),
+            _ => unreachable!("Only Integers are promoted to Bignums."),
+        }
+    }
+
+    fn to_ratio(&self) -> BigRational {
+        match self {
+            Number::Ratio(v) => v.clone(),
+            Number::Float(_) => unreachable!("Floats are never promoted to Rationals."),
+            _ => BigRational::from_integer(self.to_big()),
+        }
+    }
+
+    fn is_float(&self) -> bool {
+        matches!(self, Number::Float(_))
+    }
+
+    fn is_ratio(&self) -> bool {
+        matches!(self, Number::Ratio(_))
+    }
+
+    fn is_zero(&self) -> bool {
+        match self {
+            Number::Integer(v) => *v == 0,
+            Number::Big(v) => v.is_zero(),
+            Number::Ratio(v) => v.is_zero(),
+            Number::Float(v) => *v == 0.0,
+        }
+    }
+
+    /// Creates a number from a Bignum, demoting it to an Integer if it fits.
+    ///
+    fn from_big(v: BigInt) -> Number {
+        match v.to_i64() {
+            Some(x) => Number::Integer(x),
+            None => Number::Big(v),
+        }
+    }
+
+    /// Creates a number from a Rational, demoting it to an Integer if its
+    /// denominator is 1.
+    ///
+    fn from_ratio(v: BigRational) -> Number {
+        if v.is_integer() {
+            Number::from_big(v.to_integer())
+        } else {
+            Number::Ratio(v)
+        }
+    }
+
+    fn into_object(self) -> Object {
+        match self {
+            Number::Integer(v) => Object::integer(v),
+            Number::Big(v) => Object::bignum(v),
+            Number::Ratio(v) => Object::rational(v),
+            Number::Float(v) => Object::float(v),
+        }
+    }
+}
+
+/// Extracts all the numbers from the arguments of an eval form.
+///
+fn get_numbers(name: &str, args: &[Object]) -> Result<Vec<Number>, Error> {
+    let mut result = Vec::with_capacity(args.len());
+
+    for (i, arg) in args.iter().enumerate() {
+        result.push(Number::from_object(name, i, arg)?);
+    }
+
+    Ok(result)
+}
+
+/// Applies a binary operation with the numeric promotion rules.
+///
+fn apply_binary(op: &Operation, a: &Number, b: &Number) -> Number {
+    match (a, b) {
+        (Number::Integer(x), Number::Integer(y)) => match (op.int)(*x, *y) {
+            Some(v) => Number::Integer(v),
+            None => Number::from_big((op.big)(&a.to_big(), &b.to_big())),
+        },
+        _ if a.is_float() || b.is_float() => Number::Float((op.float)(a.to_float(), b.to_float())),
+        _ if a.is_ratio() || b.is_ratio() => {
+            Number::from_ratio((op.ratio)(&a.to_ratio(), &b.to_ratio()))
+        }
+        _ => Number::from_big((op.big)(&a.to_big(), &b.to_big())),
+    }
+}
+
+/// Folds the arguments of a variadic arithmetic form from left to right.
+///
+fn fold_numbers(op: &Operation, init: Number, numbers: &[Number]) -> Object {
+    let mut acc = init;
+
+    for n in numbers {
+        acc = apply_binary(op, &acc, n);
+    }
+
+    acc.into_object()
+}
+
+/// Checks that a divisor is not zero.
+///
+fn check_divisor(name: &str, n: &Number) -> Result<(), Error> {
+    if n.is_zero() {
+        return Error::err_kind(
+            ErrorKind::Arithmetic,
+            &format!("division by zero in '{}'.", name),
+        );
+    }
+
+    Ok(())
+}
+
+const ADD: Operation = Operation {
+    int: i64::checked_add,
+    big: |a, b| a + b,
+    ratio: |a, b| a + b,
+    float: |a, b| a + b,
+};
+
+const SUB: Operation = Operation {
+    int: i64::checked_sub,
+    big: |a, b| a - b,
+    ratio: |a, b| a - b,
+    float: |a, b| a - b,
+};
+
+const MUL: Operation = Operation {
+    int: i64::checked_mul,
+    big: |a, b| a * b,
+    ratio: |a, b| a * b,
+    float: |a, b| a * b,
+};
+
+const DIV: Operation = Operation {
+    int: i64::checked_div,
+    big: |a, b| a / b,
+    ratio: |a, b| a / b,
+    float: |a, b| a / b,
+};
+
+const MOD: Operation = Operation {
+    int: |a, b| {
+        let r = a.checked_rem(b)?;
+
+        if r != 0 && (r < 0) != (b < 0) {
+            Some(r + b)
+        } else {
+            Some(r)
+        }
+    },
+    big: |a, b| a.mod_floor(b),
+    ratio: |a, b| a - b * (a / b).floor(),
+    float: |a, b| a - b * (a / b).floor(),
+};
+
+const REM: Operation = Operation {
+    int: i64::checked_rem,
+    big: |a, b| a % b,
+    ratio: |a, b| a % b,
+    float: |a, b| a % b,
+};
+
+/// `+` eval form.
+///
+/// `(+ a b ...)`
+///
+fn math_add(args: Vec<Object>) -> Result<Object, Error> {
+    let numbers = get_numbers("+", &args)?;
+
+    Ok(fold_numbers(&ADD, Number::Integer(0), &numbers))
+}
+
+/// `-` eval form.
+///
+/// `(- a)` negates `a`.
+/// `(- a b ...)`
+///
+fn math_sub(args: Vec<Object>) -> Result<Object, Error> {
+    check_min_arg_count("-", &args, 1)?;
+
+    let numbers = get_numbers("-", &args)?;
+
+    if numbers.len() == 1 {
+        return Ok(fold_numbers(&SUB, Number::Integer(0), &numbers));
+    }
+
+    Ok(fold_numbers(&SUB, numbers[0].clone(), &numbers[1..]))
+}
+
+/// `*` eval form.
+///
+/// `(* a b ...)`
+///
+fn math_mul(args: Vec<Object>) -> Result<Object, Error> {
+    let numbers = get_numbers("*", &args)?;
+
+    Ok(fold_numbers(&MUL, Number::Integer(1), &numbers))
+}
+
+/// `/` eval form.
+///
+/// Integer division truncates toward zero, use a Rational operand for an
+/// exact division.
+///
+/// `(/ a)` computes `(/ 1 a)`.
+/// `(/ a b ...)`
+///
+fn math_div(args: Vec<Object>) -> Result<Object, Error> {
+    check_min_arg_count("/", &args, 1)?;
+
+    let numbers = get_numbers("/", &args)?;
+
+    let (init, rest) = if numbers.len() == 1 {
+        (Number::Integer(1), &numbers[..])
+    } else {
+        (numbers[0].clone(), &numbers[1..])
+    };
+
+    let mut acc = init;
+
+    for n in rest {
+        check_divisor("/", n)?;
+        acc = apply_binary(&DIV, &acc, n);
+    }
+
+    Ok(acc.into_object())
+}
+
+/// `mod` eval form.
+///
+/// The result has the sign of the divisor.
+///
+/// `(mod a b)`
+///
+fn math_mod(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("mod", &args, 2)?;
+
+    let numbers = get_numbers("mod", &args)?;
+    check_divisor("mod", &numbers[1])?;
+
+    Ok(apply_binary(&MOD, &numbers[0], &numbers[1]).into_object())
+}
+
+/// `rem` eval form.
+///
+/// The result has the sign of the dividend.
+///
+/// `(rem a b)`
+///
+fn math_rem(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("rem", &args, 2)?;
+
+    let numbers = get_numbers("rem", &args)?;
+    check_divisor("rem", &numbers[1])?;
+
+    Ok(apply_binary(&REM, &numbers[0], &numbers[1]).into_object())
+}
+
+/// `abs` eval form.
+///
+/// `(abs a)`
+///
+fn math_abs(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("abs", &args, 1)?;
+
+    let n = match Number::from_object("abs", 0, &args[0])? {
+        Number::Integer(v) => match v.checked_abs() {
+            Some(r) => Number::Integer(r),
+            None => Number::from_big(BigInt::from(v).abs()),
+        },
+        Number::Big(v) => Number::from_big(v.abs()),
+        Number::Ratio(v) => Number::Ratio(v.abs()),
+        Number::Float(v) => Number::Float(v.abs()),
+    };
+
+    Ok(n.into_object())
+}
+
+/// Selects the minimum or maximum of the arguments.
+///
+fn select_number(name: &str, args: &[Object], wanted: Ordering) -> Result<Object, Error> {
+    check_min_arg_count(name, args, 1)?;
+
+    let numbers = get_numbers(name, args)?;
+    let any_float = numbers.iter().any(Number::is_float);
+
+    let mut result = &numbers[0];
+
+    for n in &numbers[1..] {
+        if compare(n, result) == Some(wanted) {
+            result = n;
+        }
+    }
+
+    if any_float {
+        Ok(Object::float(result.to_float()))
+    } else {
+        Ok(result.clone().into_object())
+    }
+}
+
+/// `min` eval form.
+///
+/// `(min a b ...)`
+///
+fn math_min(args: Vec<Object>) -> Result<Object, Error> {
+    select_number("min", &args, Ordering::Less)
+}
+
+/// `max` eval form.
+///
+/// `(max a b ...)`
+///
+fn math_max(args: Vec<Object>) -> Result<Object, Error> {
+    select_number("max", &args, Ordering::Greater)
+}
+
+/// Compares two numbers. Returns `None` if one of them is NaN.
+///
+fn compare(a: &Number, b: &Number) -> Option<Ordering> {
+    match (a, b) {
+        (Number::Integer(x), Number::Integer(y)) => Some(x.cmp(y)),
+        _ if a.is_float() || b.is_float() => a.to_float().partial_cmp(&b.to_float()),
+        _ if a.is_ratio() || b.is_ratio() => Some(a.to_ratio().cmp(&b.to_ratio())),
+        _ => Some(a.to_big().cmp(&b.to_big())),
+    }
+}
+
+/// Checks that every consecutive pair of arguments satisfies the predicate.
+///
+fn compare_chain(
+    name: &str,
+    args: &[Object],
+    pred: fn(Option<Ordering>) -> bool,
+) -> Result<Object, Error> {
+    check_min_arg_count(name, args, 1)?;
+
+    let numbers = get_numbers(name, args)?;
+
+    Ok(Object::bool(
+        numbers.windows(2).all(|w| pred(compare(&w[0], &w[1]))),
+    ))
+}
+
+/// `=` eval form.
+///
+/// `(= a b ...)`
+///
+fn math_eq(args: Vec<Object>) -> Result<Object, Error> {
+    compare_chain("=", &args, |o| o == Some(Ordering::Equal))
+}
+
+/// `/=` eval form.
+///
+/// True if no two arguments are equal.
+///
+/// `(/= a b ...)`
+///
+fn math_ne(args: Vec<Object>) -> Result<Object, Error> {
+    check_min_arg_count("/=", &args, 1)?;
+
+    let numbers = get_numbers("/=", &args)?;
+
+    for (i, a) in numbers.iter().enumerate() {
+        for b in &numbers[i + 1..] {
+            if compare(a, b) == Some(Ordering::Equal) {
+                return Ok(Object::bool(false));
+            }
+        }
+    }
+
+    Ok(Object::bool(true))
+}
+
+/// `<` eval form.
+///
+/// `(< a b ...)`
+///
+fn math_lt(args: Vec<Object>) -> Result<Object, Error> {
+    compare_chain("<", &args, |o| o == Some(Ordering::Less))
+}
+
+/// `<=` eval form.
+///
+/// `(<= a b ...)`
+///
+fn math_le(args: Vec<Object>) -> Result<Object, Error> {
+    compare_chain("<=", &args, |o| {
+        o == Some(Ordering::Less) || o == Some(Ordering::Equal)
+    })
+}
+
+/// `>` eval form.
+///
+/// `(> a b ...)`
+///
+fn math_gt(args: Vec<Object>) -> Result<Object, Error> {
+    compare_chain(">", &args, |o| o == Some(Ordering::Greater))
+}
+
+/// `>=` eval form.
+///
+/// `(>= a b ...)`
+///
+fn math_ge(args: Vec<Object>) -> Result<Object, Error> {
+    compare_chain(">=", &args, |o| {
+        o == Some(Ordering::Greater) || o == Some(Ordering::Equal)
+    })
+}
@@ -1,12 +1,33 @@
 mod checks;
+mod exception;
 mod flow;
 mod function;
 mod io;
+mod list;
+mod map;
+mod math;
+mod module;
+mod pattern;
+mod string;
+mod variable;
+mod vector;
 
 use checks::*;
+use exception::*;
 use flow::*;
 use function::*;
 use io::*;
+use list::*;
+use map::*;
+use math::*;
+use module::*;
+use pattern::*;
+use string::*;
+use variable::*;
+use vector::*;
+
+pub(crate) use flow::{condition_error, evaluate_tail_form};
+pub(crate) use variable::{check_define, check_set, name_function};
 
 use super::*;
 use crate::nl::core::object::*;
@@ -21,45 +42,144 @@ pub fn register_all_builtin_forms(scope: &mut Scope) {
 
     register_builtin_check_forms(scope);
     register_builtin_io_forms(scope);
+    register_builtin_math_forms(scope);
+    register_builtin_list_forms(scope);
+    register_builtin_vector_forms(scope);
+    register_builtin_map_forms(scope);
+    register_builtin_string_forms(scope);
     register_builtin_flow_forms(scope);
+    register_builtin_pattern_forms(scope);
     register_builtin_function_forms(scope);
+    register_builtin_exception_forms(scope);
+    register_builtin_variable_forms(scope);
+    register_builtin_module_forms(scope);
 }
 
-/// `quote` special form.
-///
-/// `'(a b c)` = `(quote (a b c))`
+/// Checks the number of arguments of a form.
 ///
-fn quote(_: &mut Scope, args: &[Object]) -> Result<Object, Error> {
-    if args.len() != 1 {
-        return Error::errf(
+fn check_arg_count(form: &str, args: &[Object], count: usize) -> Result<(), Error> {
+    if args.len() != count {
+        return Error::err_kind(
+            ErrorKind::arity(form, count, Some(count), args.len()),
             &format!(
-                "'quote' only receives 1 argument, got {} instead.",
+                "'{}' only receives {} argument{}, got {} instead.",
+                form,
+                count,
+                if count > 1 { "s" } else { "" },
                 args.len()
             ),
-            "quote",
-            intern_location!(),
         );
     }
 
-    Ok(args[0].clone())
+    Ok(())
 }
 
-/// `equote` special form.
+/// Checks a form receives at least the given number of arguments.
 ///
-/// `\`(a b c ,(d e f))` = `(equote (a b c (escape-quote (d e f)))`
+fn check_min_arg_count(form: &str, args: &[Object], count: usize) -> Result<(), Error> {
+    if args.len() < count {
+        return Error::err_kind(
+            ErrorKind::arity(form, count, None, args.len()),
+            &format!(
+                "'{}' requires at least {} argument{}, got {} instead.",
+                form,
+                count,
+                if count > 1 { "s" } else { "" },
+                args.len()
+            ),
+        );
+    }
+
+    Ok(())
+}
+
+/// Checks a form receives `min` or `max` arguments.
 ///
-fn equote(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
-    if args.len() != 1 {
-        return Error::errf(
+fn check_arg_range(form: &str, args: &[Object], min: usize, max: usize) -> Result<(), Error> {
+    if args.len() < min || args.len() > max {
+        return Error::err_kind(
+            ErrorKind::arity(form, min, Some(max), args.len()),
             &format!(
-                "'equote' only receives 1 argument, got {} instead.",
+                "'{}' receives {} or {} arguments, got {} instead.",
+                form,
+                min,
+                max,
                 args.len()
             ),
-            "equote",
-            intern_location!(),
         );
     }
 
+    Ok(())
+}
+
+/// Gets an index argument, checking it is within `0..end`.
+///
+fn get_index_argument(
+    form: &str,
+    args: &[Object],
+    index: usize,
+    end: usize,
+) -> Result<usize, Error> {
+    let value = match args[index].as_integer() {
+        Some(v) => v,
+        None => {
+            return Error::err_kind(
+                ErrorKind::wrong_type("Integer", &args[index]),
+                &format!(
+                    "'{}' requires an Integer as argument {}, got a {} : {}",
+                    form,
+                    index + 1,
+                    args[index].type_string(),
+                    args[index]
+                ),
+            )
+        }
+    };
+
+    if value < 0 || value as usize >= end {
+        let message = if end == 0 {
+            // The collection is the first argument, `nil` being the empty list.
+            let collection = match &args[0] {
+                Object::Nil(_) => "List",
+                v => v.type_string(),
+            };
+
+            format!(
+                "'{}' : index {} is out of range of an empty {}.",
+                form, value, collection
+            )
+        } else {
+            format!(
+                "'{}' : index {} is out of range, expected 0 to {}.",
+                form,
+                value,
+                end - 1
+            )
+        };
+
+        return Error::err_kind(ErrorKind::range(value, 0, end), &message);
+    }
+
+    Ok(value as usize)
+}
+
+/// `quote` special form.
+///
+/// `'(a b c)` = `(quote (a b c))`
+///
+fn quote(_: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    check_arg_count("quote", args, 1)?;
+
+    Ok(args[0].clone())
+}
+
+/// `equote` special form.
+///
+/// `\`(a b c ,(d e f))` = `(equote (a b c (escape-quote (d e f)))`
+///
+fn equote(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    check_arg_count("equote", args, 1)?;
+
     Error::rethrow(escape_equote(scope, &args[0]), "equote", intern_location!())
 }
 
@@ -68,11 +188,11 @@ fn equote(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
 /// `,(a b c)` = `(escape-quote (a b c))`
 ///
 fn escape_quote(_: &mut Scope, _: &[Object]) -> Result<Object, Error> {
-    Error::errf(
+    Error::new_kind(
+        ErrorKind::Form,
         "'escape-quote' must be within a 'equote' form.",
-        "escape-quote",
-        intern_location!(),
     )
+    .push_err("escape-quote", intern_location!())
 }
 
 /// `escape-quote` special form, only when inside of a `equote` form.
@@ -82,16 +202,16 @@ fn escape_quote(_: &mut Scope, _: &[Object]) -> Result<Object, Error> {
 fn escape_equote(scope: &mut Scope, object: &Object) -> Result<Object, Error> {
     match object {
         Object::List(_, v) => {
-            if v.len() > 0 && v[0].is_symbol() && v[0].get_symbol() == "escape-quote" {
+            if !v.is_empty() && v[0].is_symbol() && v[0].get_symbol() == "escape-quote" {
                 if v.len() != 2 {
-                    return Error::errf(
+                    return Error::new_kind(
+                        ErrorKind::arity("escape-quote", 1, Some(1), v.len() - 1),
                         &format!(
                             "'escape-quote' requires 1 argument, got {} instead.",
                             v.len() - 1
                         ),
-                        "escape-quote",
-                        intern_location!(),
-                    );
+                    )
+                    .push_err("escape-quote", intern_location!());
                 }
 
                 return Error::rethrow(evaluate(scope, &v[1]), "escape-quote", intern_location!());
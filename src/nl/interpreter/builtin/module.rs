@@ -0,0 +1,142 @@
+use crate::nl::core::object::*;
+use crate::nl::interpreter::*;
+
+use super::check_min_arg_count;
+
+/// Registers all module builtin forms.
+///
+pub fn register_builtin_module_forms(scope: &mut Scope) {
+    scope
+        .register_special_form("require", require)
+        .register_special_form("import", import)
+        .register_special_form("export", export);
+}
+
+/// `require` special form.
+///
+/// Loads a module and binds its exports globally, prefixed by the name of
+/// the module (`module/name`) or by the given alias (`alias/name`).
+///
+/// `(require module)`
+/// `(require module :as alias)`
+///
+fn require(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    let prefix = match args {
+        [_] => None,
+        [_, Object::Keyword(_, k), Object::Symbol(_, alias)] if k == "as" => Some(alias),
+        _ => {
+            return Error::err_kind(
+                ErrorKind::Form,
+                &format!(
+                    "invalid 'require' syntax, got {} argument(s).\nSyntax : (require module) or (require module :as alias)",
+                    args.len()
+                ),
+            )
+        }
+    };
+
+    let name = get_module_name("require", &args[0])?;
+    let location = args[0].get_info().location.clone();
+    let exports = Error::rethrow(load_module(scope, &name), "require", location)?;
+    let prefix = prefix.unwrap_or(&name);
+
+    for (export, binding) in exports.iter() {
+        scope.insert_global(format!("{}/{}", prefix, export), binding.clone());
+    }
+
+    Ok(Object::nil())
+}
+
+/// `import` special form.
+///
+/// Loads a module and binds its exports globally without prefix. Only the
+/// given names are bound if there are any.
+///
+/// `(import module)`
+/// `(import module name0 name1 ...)`
+///
+fn import(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    check_min_arg_count("import", args, 1)?;
+
+    let name = get_module_name("import", &args[0])?;
+    let mut names = Vec::with_capacity(args.len() - 1);
+
+    for x in &args[1..] {
+        match x {
+            Object::Symbol(_, v) => names.push(v.clone()),
+            _ => {
+                return Error::new_kind(
+                    ErrorKind::Form,
+                    &format!("'import' requires symbols as names to import, got : {}", x),
+                )
+                .push_err("import", x.get_info().location.clone())
+            }
+        }
+    }
+
+    let location = args[0].get_info().location.clone();
+    let exports = Error::rethrow(load_module(scope, &name), "import", location)?;
+
+    for x in &names {
+        if !exports.iter().any(|(export, _)| export == x) {
+            return Error::err_kind(
+                ErrorKind::Module { name: name.clone() },
+                &format!("module '{}' doesn't export '{}'.", name, x),
+            );
+        }
+    }
+
+    for (export, binding) in exports.iter() {
+        if names.is_empty() || names.contains(export) {
+            scope.insert_global(export.clone(), binding.clone());
+        }
+    }
+
+    Ok(Object::nil())
+}
+
+/// `export` special form.
+///
+/// Restricts the definitions visible from the scopes requiring the module
+/// to the given names. Can be called several times.
+///
+/// `(export name0 name1 ...)`
+///
+fn export(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    let mut names = Vec::with_capacity(args.len());
+
+    for x in args {
+        match x {
+            Object::Symbol(_, v) => names.push(v.clone()),
+            _ => {
+                return Error::new_kind(
+                    ErrorKind::Form,
+                    &format!("'export' requires symbols as names to export, got : {}", x),
+                )
+                .push_err("export", x.get_info().location.clone())
+            }
+        }
+    }
+
+    scope.export(names);
+
+    Ok(Object::nil())
+}
+
+/// Gets the name of the module given to `require` or `import`.
+///
+fn get_module_name(form: &str, object: &Object) -> Result<String, Error> {
+    match object {
+        Object::Symbol(_, v) => Ok(v.clone()),
+        _ => Error::new_kind(
+            ErrorKind::wrong_type("Symbol", object),
+            &format!(
+                "'{}' requires a symbol as module name, got a {} : {}",
+                form,
+                object.type_string(),
+                object
+            ),
+        )
+        .push_err(form, object.get_info().location.clone()),
+    }
+}
@@ -0,0 +1,189 @@
+use crate::nl::core::object::*;
+use crate::nl::interpreter::*;
+
+use super::check_min_arg_count;
+use super::flow::{evaluate_body, evaluate_condition};
+
+/// Registers all pattern matching builtin forms.
+///
+pub fn register_builtin_pattern_forms(scope: &mut Scope) {
+    scope.register_special_form("match", pattern_match);
+}
+
+/// `match` special form.
+///
+/// Patterns :
+/// - `_` matches anything ;
+/// - a symbol matches anything and binds the value to it ;
+/// - a literal (nil, Bool, Integer, Float, Char, String, Keyword) matches an
+///   equal value ;
+/// - `'sym` matches the symbol `sym` ;
+/// - `(p0 p1 ...)` matches a list of the same length whose elements match ;
+/// - `(p0 p1 ... &rest r)` matches a list with at least as many elements,
+///   binding the remaining ones to the pattern following `&rest`.
+///
+/// A clause can have a guard, evaluated with the bindings of the pattern.
+///
+/// `(match expr (pattern body...) (pattern :when guard body...) ...)`
+///
+fn pattern_match(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    check_min_arg_count("match", args, 1)?;
+
+    let value = evaluate(scope, &args[0])?;
+
+    for arg in &args[1..] {
+        let clause = match arg.as_list() {
+            Some(v) if !v.is_empty() => v,
+            _ => {
+                return Error::new_kind(
+                    ErrorKind::Form,
+                    &format!(
+                        "invalid 'match' syntax at {}.\nSyntax : (match expr (pattern body...) (pattern :when guard body...) ...)",
+                        arg
+                    ),
+                )
+                .push_err("match", arg.get_info().location.clone())
+            }
+        };
+
+        let (guard, body) = match clause.get(1) {
+            Some(Object::Keyword(_, k)) if k == "when" => match clause.get(2) {
+                Some(v) => (Some(v), &clause[3..]),
+                None => {
+                    return Error::new_kind(
+                        ErrorKind::Form,
+                        &format!(
+                            "invalid 'match' syntax at {}.\n':when' requires a guard.",
+                            arg
+                        ),
+                    )
+                    .push_err("match", arg.get_info().location.clone())
+                }
+            },
+            _ => (None, &clause[1..]),
+        };
+
+        let mut bindings = Vec::new();
+
+        if !match_pattern(&clause[0], &value, &mut bindings)? {
+            continue;
+        }
+
+        let depth = scope.depth();
+        scope.enter(Mode::Evaluation);
+
+        for (name, v) in bindings {
+            scope.insert(name, Binding::DynamicVariable(v));
+        }
+
+        let accepted = match guard {
+            Some(g) => evaluate_condition(scope, "match", g),
+            None => Ok(true),
+        };
+
+        let result = match accepted {
+            Ok(true) => evaluate_body(scope, body).map(Some),
+            Ok(false) => Ok(None),
+            Err(e) => Err(e),
+        };
+
+        scope.unwind(depth);
+
+        if let Some(v) = result? {
+            return Ok(v);
+        }
+    }
+
+    Error::errf(
+        &format!("non-exhaustive 'match' : no pattern matches {}", value),
+        "match",
+        args[0].get_info().location.clone(),
+    )
+}
+
+/// Matches a value against a pattern, collecting the bindings.
+///
+fn match_pattern(
+    pattern: &Object,
+    value: &Object,
+    bindings: &mut Vec<(String, Object)>,
+) -> Result<bool, Error> {
+    match pattern {
+        Object::Symbol(_, v) if v == "_" => Ok(true),
+        Object::Symbol(_, v) => {
+            bindings.push((v.clone(), value.clone()));
+            Ok(true)
+        }
+        Object::List(_, v) if is_quote(v) => Ok(v[1] == *value),
+        Object::List(_, v) => match_list_pattern(pattern, v, value, bindings),
+        Object::Function(_, _) => Error::new_kind(
+            ErrorKind::Form,
+            &format!("invalid 'match' pattern : {}", pattern),
+        )
+        .push_err("match", pattern.get_info().location.clone()),
+        _ => Ok(pattern == value),
+    }
+}
+
+/// Matches a value against a list pattern, collecting the bindings.
+///
+fn match_list_pattern(
+    pattern: &Object,
+    patterns: &[Object],
+    value: &Object,
+    bindings: &mut Vec<(String, Object)>,
+) -> Result<bool, Error> {
+    let rest_index = patterns
+        .iter()
+        .position(|x| x.as_symbol().map(|s| s.as_str()) == Some("&rest"));
+
+    let (fixed, rest) = match rest_index {
+        Some(i) => {
+            if i + 2 != patterns.len() {
+                return Error::new_kind(
+                    ErrorKind::Form,
+                    &format!(
+                        "invalid 'match' pattern {}.\n'&rest' must be followed by a single pattern.",
+                        pattern
+                    ),
+                )
+                .push_err("match", pattern.get_info().location.clone());
+            }
+
+            (&patterns[..i], Some(&patterns[i + 1]))
+        }
+        None => (patterns, None),
+    };
+
+    let values = match value {
+        Object::List(_, v) => &v[..],
+        Object::Nil(_) => &[],
+        _ => return Ok(false),
+    };
+
+    let length_matches = match rest {
+        Some(_) => values.len() >= fixed.len(),
+        None => values.len() == fixed.len(),
+    };
+
+    if !length_matches {
+        return Ok(false);
+    }
+
+    for (p, v) in fixed.iter().zip(values) {
+        if !match_pattern(p, v, bindings)? {
+            return Ok(false);
+        }
+    }
+
+    match rest {
+        Some(p) => match_pattern(p, &Object::list(values[fixed.len()..].to_vec()), bindings),
+        None => Ok(true),
+    }
+}
+
+/// Checks if a list is a `(quote x)` form.
+///
+fn is_quote(list: &[Object]) -> bool {
+    list.len() == 2 && list[0].as_symbol().map(|x| x.as_str()) == Some("quote")
+}
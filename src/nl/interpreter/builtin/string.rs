@@ -0,0 +1,420 @@
+use crate::nl::core::object::*;
+use crate::nl::interpreter::*;
+
+use super::{check_arg_count, check_arg_range, get_index_argument};
+
+/// Registers all string manipulation builtin forms.
+///
+/// Indices and lengths are counted in Unicode scalar values (Chars), not in
+/// bytes.
+///
+pub fn register_builtin_string_forms(scope: &mut Scope) {
+    scope
+        .register_eval_form("string-length", string_length)
+        .register_eval_form("substring", substring)
+        .register_eval_form("string-append", string_append)
+        .register_eval_form("split", split)
+        .register_eval_form("join", join)
+        .register_eval_form("trim", trim)
+        .register_eval_form("upcase", upcase)
+        .register_eval_form("downcase", downcase)
+        .register_eval_form("starts-with?", starts_with)
+        .register_eval_form("ends-with?", ends_with)
+        .register_eval_form("string-index", string_index)
+        .register_eval_form("replace", replace)
+        .register_eval_form("char-at", char_at)
+        .register_eval_form("string->list", string_to_list)
+        .register_eval_form("list->string", list_to_string)
+        .register_eval_form("char->integer", char_to_integer)
+        .register_eval_form("integer->char", integer_to_char);
+}
+
+/// Gets a String argument.
+///
+fn get_string_argument<'a>(form: &str, args: &'a [Object], index: usize) -> Result<&'a str, Error> {
+    match &args[index] {
+        Object::String(_, v) => Ok(v),
+        v => Error::err_kind(
+            ErrorKind::wrong_type("String", v),
+            &format!(
+                "'{}' requires a String as argument {}, got a {} : {}",
+                form,
+                index + 1,
+                v.type_string(),
+                v
+            ),
+        ),
+    }
+}
+
+/// Gets a String or Char argument as a string.
+///
+fn get_text_argument(form: &str, args: &[Object], index: usize) -> Result<String, Error> {
+    match &args[index] {
+        Object::String(_, v) => Ok(v.clone()),
+        Object::Char(_, v) => Ok(v.to_string()),
+        v => Error::err_kind(
+            ErrorKind::wrong_type("String or Char", v),
+            &format!(
+                "'{}' requires a String or a Char as argument {}, got a {} : {}",
+                form,
+                index + 1,
+                v.type_string(),
+                v
+            ),
+        ),
+    }
+}
+
+/// `string-length` eval form.
+///
+/// `(string-length s)`
+///
+fn string_length(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("string-length", &args, 1)?;
+
+    let s = get_string_argument("string-length", &args, 0)?;
+
+    Ok(Object::integer(s.chars().count() as i64))
+}
+
+/// `substring` eval form.
+///
+/// Returns the Chars from `start` (included) to `end` (excluded, the end of
+/// the string by default).
+///
+/// `(substring s start)`
+/// `(substring s start end)`
+///
+fn substring(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_range("substring", &args, 2, 3)?;
+
+    let s = get_string_argument("substring", &args, 0)?;
+    let length = s.chars().count();
+    let start = get_index_argument("substring", &args, 1, length + 1)?;
+    let end = if args.len() == 3 {
+        get_index_argument("substring", &args, 2, length + 1)?
+    } else {
+        length
+    };
+
+    if start > end {
+        return Error::err_kind(
+            ErrorKind::range(start as i64, 0, end + 1),
+            &format!(
+                "'substring' : start index {} is greater than end index {}.",
+                start, end
+            ),
+        );
+    }
+
+    Ok(Object::string(
+        s.chars().skip(start).take(end - start).collect(),
+    ))
+}
+
+/// `string-append` eval form.
+///
+/// `(string-append s0 s1 ...)`
+///
+fn string_append(args: Vec<Object>) -> Result<Object, Error> {
+    let mut result = String::new();
+
+    for i in 0..args.len() {
+        result.push_str(&get_text_argument("string-append", &args, i)?);
+    }
+
+    Ok(Object::string(result))
+}
+
+/// `split` eval form.
+///
+/// `(split s separator)`
+///
+fn split(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("split", &args, 2)?;
+
+    let s = get_string_argument("split", &args, 0)?;
+    let separator = get_text_argument("split", &args, 1)?;
+
+    if separator.is_empty() {
+        return Error::err("'split' requires a non-empty separator.");
+    }
+
+    Ok(Object::list(
+        s.split(separator.as_str())
+            .map(|x| Object::string(x.to_string()))
+            .collect(),
+    ))
+}
+
+/// `join` eval form.
+///
+/// `(join list)`
+/// `(join list separator)`
+///
+fn join(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_range("join", &args, 1, 2)?;
+
+    if !args[0].is_list() && !args[0].is_nil() {
+        return Error::err_kind(
+            ErrorKind::wrong_type("List", &args[0]),
+            &format!(
+                "'join' requires a List as argument 1, got a {} : {}",
+                args[0].type_string(),
+                args[0]
+            ),
+        );
+    }
+
+    let separator = if args.len() == 2 {
+        get_text_argument("join", &args, 1)?
+    } else {
+        String::new()
+    };
+
+    let elements = args[0].to_list();
+    let mut parts = Vec::with_capacity(elements.len());
+
+    for (i, x) in elements.iter().enumerate() {
+        match x {
+            Object::String(_, v) => parts.push(v.clone()),
+            Object::Char(_, v) => parts.push(v.to_string()),
+            _ => {
+                return Error::err_kind(
+                    ErrorKind::wrong_type("String or Char", x),
+                    &format!(
+                        "'join' requires a List of Strings or Chars, got a {} as element {} : {}",
+                        x.type_string(),
+                        i,
+                        x
+                    ),
+                )
+            }
+        }
+    }
+
+    Ok(Object::string(parts.join(&separator)))
+}
+
+/// `trim` eval form.
+///
+/// `(trim s)`
+///
+fn trim(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("trim", &args, 1)?;
+
+    let s = get_string_argument("trim", &args, 0)?;
+
+    Ok(Object::string(s.trim().to_string()))
+}
+
+/// `upcase` eval form.
+///
+/// `(upcase s)`
+///
+fn upcase(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("upcase", &args, 1)?;
+
+    let s = get_string_argument("upcase", &args, 0)?;
+
+    Ok(Object::string(s.to_uppercase()))
+}
+
+/// `downcase` eval form.
+///
+/// `(downcase s)`
+///
+fn downcase(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("downcase", &args, 1)?;
+
+    let s = get_string_argument("downcase", &args, 0)?;
+
+    Ok(Object::string(s.to_lowercase()))
+}
+
+/// `starts-with?` eval form.
+///
+/// `(starts-with? s prefix)`
+///
+fn starts_with(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("starts-with?", &args, 2)?;
+
+    let s = get_string_argument("starts-with?", &args, 0)?;
+    let prefix = get_text_argument("starts-with?", &args, 1)?;
+
+    Ok(Object::bool(s.starts_with(prefix.as_str())))
+}
+
+/// `ends-with?` eval form.
+///
+/// `(ends-with? s suffix)`
+///
+fn ends_with(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("ends-with?", &args, 2)?;
+
+    let s = get_string_argument("ends-with?", &args, 0)?;
+    let suffix = get_text_argument("ends-with?", &args, 1)?;
+
+    Ok(Object::bool(s.ends_with(suffix.as_str())))
+}
+
+/// `string-index` eval form.
+///
+/// Returns the Char index of the first occurrence of the given String or
+/// Char, or `nil` if there is none.
+///
+/// `(string-index s pattern)`
+///
+fn string_index(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("string-index", &args, 2)?;
+
+    let s = get_string_argument("string-index", &args, 0)?;
+    let pattern = get_text_argument("string-index", &args, 1)?;
+
+    match s.find(pattern.as_str()) {
+        Some(i) => Ok(Object::integer(s[..i].chars().count() as i64)),
+        None => Ok(Object::nil()),
+    }
+}
+
+/// `replace` eval form.
+///
+/// Replaces every occurrence of `from` with `to`.
+///
+/// `(replace s from to)`
+///
+fn replace(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("replace", &args, 3)?;
+
+    let s = get_string_argument("replace", &args, 0)?;
+    let from = get_text_argument("replace", &args, 1)?;
+    let to = get_text_argument("replace", &args, 2)?;
+
+    if from.is_empty() {
+        return Error::err("'replace' requires a non-empty pattern.");
+    }
+
+    Ok(Object::string(s.replace(from.as_str(), &to)))
+}
+
+/// `char-at` eval form.
+///
+/// `(char-at s index)`
+///
+fn char_at(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("char-at", &args, 2)?;
+
+    let s = get_string_argument("char-at", &args, 0)?;
+    let index = get_index_argument("char-at", &args, 1, s.chars().count())?;
+
+    Ok(Object::char(s.chars().nth(index).unwrap()))
+}
+
+/// `string->list` eval form.
+///
+/// `(string->list s)`
+///
+fn string_to_list(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("string->list", &args, 1)?;
+
+    let s = get_string_argument("string->list", &args, 0)?;
+
+    Ok(Object::list(s.chars().map(Object::char).collect()))
+}
+
+/// `list->string` eval form.
+///
+/// `(list->string list)`
+///
+fn list_to_string(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("list->string", &args, 1)?;
+
+    if !args[0].is_list() && !args[0].is_nil() {
+        return Error::err_kind(
+            ErrorKind::wrong_type("List", &args[0]),
+            &format!(
+                "'list->string' requires a List as argument 1, got a {} : {}",
+                args[0].type_string(),
+                args[0]
+            ),
+        );
+    }
+
+    let mut result = String::new();
+
+    for (i, x) in args[0].to_list().iter().enumerate() {
+        match x.as_char() {
+            Some(c) => result.push(c),
+            None => {
+                return Error::err_kind(
+                    ErrorKind::wrong_type("Char", x),
+                    &format!(
+                        "'list->string' requires a List of Chars, got a {} as element {} : {}",
+                        x.type_string(),
+                        i,
+                        x
+                    ),
+                )
+            }
+        }
+    }
+
+    Ok(Object::string(result))
+}
+
+/// `char->integer` eval form.
+///
+/// `(char->integer c)`
+///
+fn char_to_integer(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("char->integer", &args, 1)?;
+
+    match args[0].as_char() {
+        Some(c) => Ok(Object::integer(c as i64)),
+        None => Error::err_kind(
+            ErrorKind::wrong_type("Char", &args[0]),
+            &format!(
+                "'char->integer' requires a Char, got a {} : {}",
+                args[0].type_string(),
+                args[0]
+            ),
+        ),
+    }
+}
+
+/// `integer->char` eval form.
+///
+/// `(integer->char i)`
+///
+fn integer_to_char(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("integer->char", &args, 1)?;
+
+    let value = match args[0].as_integer() {
+        Some(v) => v,
+        None => {
+            return Error::err_kind(
+                ErrorKind::wrong_type("Integer", &args[0]),
+                &format!(
+                    "'integer->char' requires an Integer, got a {} : {}",
+                    args[0].type_string(),
+                    args[0]
+                ),
+            )
+        }
+    };
+
+    let c = if value < 0 {
+        None
+    } else {
+        char::from_u32(value as u32)
+    };
+
+    match c {
+        Some(c) => Ok(Object::char(c)),
+        None => Error::err(&format!(
+            "'integer->char' : {} isn't a valid Unicode scalar value.",
+            value
+        )),
+    }
+}
@@ -0,0 +1,158 @@
+use crate::nl::core::object::*;
+use crate::nl::interpreter::*;
+
+use super::check_arg_count;
+
+use std::rc::Rc;
+
+/// Registers all variable definition builtin forms.
+///
+pub fn register_builtin_variable_forms(scope: &mut Scope) {
+    scope
+        .register_special_form("def", def)
+        .register_special_form("defconst", defconst)
+        .register_special_form("set!", set);
+}
+
+/// `def` special form.
+///
+/// Binds a variable in the global scope.
+///
+/// `(def name expr)`
+///
+fn def(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    let (name, value) = define_binding(scope, "def", args)?;
+
+    scope.insert_global(name, Binding::DynamicVariable(value));
+    Ok(Object::nil())
+}
+
+/// `defconst` special form.
+///
+/// Binds a constant in the global scope. A constant can't be mutated by
+/// `set!` nor redefined by `def` or `defconst`.
+///
+/// `(defconst name expr)`
+///
+fn defconst(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    let (name, value) = define_binding(scope, "defconst", args)?;
+    let location = args[0].get_info().location.clone();
+
+    scope.insert_global(name, Binding::ConstantVariable(value, location));
+    Ok(Object::nil())
+}
+
+/// `set!` special form.
+///
+/// Mutates the nearest existing binding of a variable.
+///
+/// `(set! name expr)`
+///
+fn set(scope: &mut Scope, args: &[Object]) -> Result<Object, Error> {
+    let name = check_syntax("set!", args)?;
+    let location = args[0].get_info().location.clone();
+
+    check_set(scope, &name, location.clone())?;
+
+    let value = Error::rethrow(evaluate(scope, &args[1]), "set!", location)?;
+
+    scope.set(name, Binding::DynamicVariable(value.clone()));
+    Ok(value)
+}
+
+/// Checks that `set!` can mutate the nearest binding of a name : it must
+/// be a variable which isn't a constant.
+///
+pub(crate) fn check_set(scope: &Scope, name: &str, location: Location) -> Result<(), Error> {
+    match scope.get_binding(name) {
+        None => Error::new_kind(
+            ErrorKind::UnboundSymbol {
+                name: name.to_string(),
+            },
+            &format!("'set!' : unbound symbol '{}'.", name),
+        )
+        .push_err(name, location),
+        Some(Binding::DynamicVariable(_)) => Ok(()),
+        Some(Binding::ConstantVariable(_, defined)) => constant_error(name, &defined, location),
+        Some(_) => Error::errf(
+            &format!("'set!' : '{}' is not a variable.", name),
+            name,
+            location,
+        ),
+    }
+}
+
+/// Checks the syntax of the variable definition forms and returns the
+/// variable name.
+///
+fn check_syntax(form: &str, args: &[Object]) -> Result<String, Error> {
+    check_arg_count(form, args, 2)?;
+
+    if !args[0].is_symbol() {
+        return Error::err_kind(
+            ErrorKind::Form,
+            &format!(
+                "'{}' requires a symbol as its first argument, got : {}",
+                form, args[0]
+            ),
+        );
+    }
+
+    Ok(args[0].get_symbol().clone())
+}
+
+/// Checks that a global definition doesn't override a constant, then
+/// evaluates the value of the definition.
+///
+fn define_binding(
+    scope: &mut Scope,
+    form: &str,
+    args: &[Object],
+) -> Result<(String, Object), Error> {
+    let name = check_syntax(form, args)?;
+    let location = args[0].get_info().location.clone();
+
+    check_define(scope, &name, location.clone())?;
+
+    let value = Error::rethrow(evaluate(scope, &args[1]), form, location)?;
+
+    Ok((name.clone(), name_function(value, &name)))
+}
+
+/// Checks that a global definition doesn't override a constant.
+///
+pub(crate) fn check_define(scope: &Scope, name: &str, location: Location) -> Result<(), Error> {
+    match scope.get_global_binding(name) {
+        Some(Binding::ConstantVariable(_, defined)) => constant_error(name, &defined, location),
+        _ => Ok(()),
+    }
+}
+
+/// Names an anonymous function after the variable it is bound to.
+///
+pub(crate) fn name_function(value: Object, name: &str) -> Object {
+    match value {
+        Object::Function(info, f) if f.name.is_none() => {
+            let mut named = (*f).clone();
+            named.name = Some(name.to_string());
+
+            Object::Function(info, Rc::new(named))
+        }
+        v => v,
+    }
+}
+
+/// Builds the error raised when mutating a constant.
+///
+fn constant_error<T>(name: &str, defined: &Location, mutated: Location) -> Result<T, Error> {
+    Error::new_kind(
+        ErrorKind::Constant {
+            name: name.to_string(),
+        },
+        &format!(
+            "'{}' is a constant and can't be mutated.\n  defined {}\n  mutated {}",
+            name, defined, mutated
+        ),
+    )
+    .push_err(name, mutated)
+}
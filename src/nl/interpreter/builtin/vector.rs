@@ -0,0 +1,141 @@
+use crate::nl::core::object::*;
+use crate::nl::interpreter::*;
+
+use super::{check_arg_count, check_min_arg_count, get_index_argument};
+
+/// Registers all vector builtin forms.
+///
+/// Vectors are never mutated, these forms return new objects.
+///
+pub fn register_builtin_vector_forms(scope: &mut Scope) {
+    scope
+        .register_eval_form("vector", vector)
+        .register_eval_form("vector-length", vector_length)
+        .register_eval_form("vector-ref", vector_ref)
+        .register_eval_form("vector-set", vector_set)
+        .register_eval_form("vector-push", vector_push)
+        .register_eval_form("vector->list", vector_to_list)
+        .register_eval_form("list->vector", list_to_vector);
+}
+
+/// Gets a Vector argument.
+///
+fn get_vector_argument<'a>(
+    form: &str,
+    args: &'a [Object],
+    index: usize,
+) -> Result<&'a Vec<Object>, Error> {
+    match &args[index] {
+        Object::Vector(_, v) => Ok(v),
+        v => Error::err_kind(
+            ErrorKind::wrong_type("Vector", v),
+            &format!(
+                "'{}' requires a Vector as argument {}, got a {} : {}",
+                form,
+                index + 1,
+                v.type_string(),
+                v
+            ),
+        ),
+    }
+}
+
+/// `vector` eval form.
+///
+/// Unlike a `[v0 v1 ...]` literal, the elements are evaluated.
+///
+/// `(vector v0 v1 ...)`
+///
+fn vector(args: Vec<Object>) -> Result<Object, Error> {
+    Ok(Object::vector(args))
+}
+
+/// `vector-length` eval form.
+///
+/// `(vector-length vector)`
+///
+fn vector_length(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("vector-length", &args, 1)?;
+
+    let vector = get_vector_argument("vector-length", &args, 0)?;
+
+    Ok(Object::integer(vector.len() as i64))
+}
+
+/// `vector-ref` eval form.
+///
+/// `(vector-ref vector index)`
+///
+fn vector_ref(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("vector-ref", &args, 2)?;
+
+    let vector = get_vector_argument("vector-ref", &args, 0)?;
+    let index = get_index_argument("vector-ref", &args, 1, vector.len())?;
+
+    Ok(vector[index].clone())
+}
+
+/// `vector-set` eval form.
+///
+/// Creates a new vector with the element at the given index replaced.
+///
+/// `(vector-set vector index v)`
+///
+fn vector_set(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("vector-set", &args, 3)?;
+
+    let vector = get_vector_argument("vector-set", &args, 0)?;
+    let index = get_index_argument("vector-set", &args, 1, vector.len())?;
+
+    let mut result = vector.clone();
+    result[index] = args[2].clone();
+
+    Ok(Object::vector(result))
+}
+
+/// `vector-push` eval form.
+///
+/// Creates a new vector with the given values appended.
+///
+/// `(vector-push vector v0 v1 ...)`
+///
+fn vector_push(args: Vec<Object>) -> Result<Object, Error> {
+    check_min_arg_count("vector-push", &args, 1)?;
+
+    let mut result = get_vector_argument("vector-push", &args, 0)?.clone();
+    result.extend(args[1..].iter().cloned());
+
+    Ok(Object::vector(result))
+}
+
+/// `vector->list` eval form.
+///
+/// `(vector->list vector)`
+///
+fn vector_to_list(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("vector->list", &args, 1)?;
+
+    let vector = get_vector_argument("vector->list", &args, 0)?;
+
+    Ok(Object::list(vector.clone()))
+}
+
+/// `list->vector` eval form.
+///
+/// `(list->vector list)`
+///
+fn list_to_vector(args: Vec<Object>) -> Result<Object, Error> {
+    check_arg_count("list->vector", &args, 1)?;
+
+    match &args[0] {
+        Object::List(_, _) | Object::Nil(_) => Ok(Object::vector(args[0].to_list())),
+        v => Error::err_kind(
+            ErrorKind::wrong_type("List", v),
+            &format!(
+                "'list->vector' requires a List as argument 1, got a {} : {}",
+                v.type_string(),
+                v
+            ),
+        ),
+    }
+}
@@ -0,0 +1,166 @@
+use crate::nl::core::object::*;
+use crate::nl::interpreter::{Binding, Generation};
+
+use std::cell::RefCell;
+use std::fmt;
+
+/// An instruction of the virtual machine.
+///
+/// The instructions work on a stack of values. `usize` operands name a
+/// constant of the chunk, a local slot of the frame or an instruction to
+/// jump to, as documented on each variant.
+///
+#[derive(Clone, Copy, Debug)]
+pub enum Instruction {
+    /// Does nothing, left in place of a block entry or exit without scope
+    /// level.
+    Nop,
+    /// Pushes nil.
+    Nil,
+    /// Pushes a constant.
+    Constant(usize),
+    /// Pops the value on top of the stack.
+    Pop,
+
+    /// Pushes the value of a slot.
+    LoadSlot(usize),
+    /// Pops a value into a slot.
+    StoreSlot(usize),
+    /// Copies the value on top of the stack into a slot.
+    SetSlot(usize),
+    /// Clears the slots `start..end`, whose block is left.
+    ClearSlots(usize, usize),
+    /// Marks the `break` slot of a loop, then pushes nil.
+    MarkSlot(usize),
+    /// Jumps if the `break` slot of a loop is marked.
+    JumpIfMarked { slot: usize, target: usize },
+
+    /// Pushes the value of the variable named by a symbol constant.
+    LoadName(usize),
+    /// Pops a value into a new variable of the innermost scope level.
+    Bind(usize),
+    /// Checks that the variable named by a symbol constant can be mutated
+    /// by `set!`.
+    CheckSet(usize),
+    /// Copies the value on top of the stack into the nearest variable named
+    /// by a symbol constant.
+    SetName(usize),
+    /// Checks that the global named by a symbol constant isn't a constant.
+    CheckDefine(usize),
+    /// Pops a value into the global named by a symbol constant, then pushes
+    /// nil.
+    Define { symbol: usize, constant: bool },
+
+    /// Enters a scope level.
+    Enter,
+    /// Enters the scope level of a loop iteration.
+    EnterLoop,
+    /// Leaves the innermost scope level.
+    Leave,
+    /// Breaks the innermost loop of the scope, then pushes nil.
+    Break,
+    /// Jumps if the innermost loop of the scope is broken.
+    JumpIfBroken(usize),
+
+    /// Jumps to an instruction.
+    Jump(usize),
+    /// Pops a Bool and jumps if it equals `when`. The condition constant is
+    /// reported if the value isn't a Bool.
+    Branch {
+        form: &'static str,
+        condition: usize,
+        when: bool,
+        target: usize,
+    },
+    /// Pops a Bool and pushes its negation.
+    Not(usize),
+
+    /// Pushes a stack frame, added to the errors raised until `PopFrame`.
+    /// The frame is located at the location of a constant.
+    PushFrame { name: &'static str, location: usize },
+    /// Pops the last stack frame.
+    PopFrame,
+
+    /// Resolves the operator of a call constant, before its arguments are
+    /// evaluated. A form which can't be called with evaluated arguments is
+    /// evaluated by the tree-walking interpreter, then the execution jumps
+    /// to `skip`.
+    Resolve { call: usize, skip: usize },
+    /// Resolves the function held by a slot, operator of a call constant.
+    ResolveSlot { slot: usize, call: usize },
+    /// Pops arguments, then calls the last resolved operator.
+    Call(usize),
+    /// Checks that the value on top of the stack is a function, operator of
+    /// a call constant.
+    CheckFunction(usize),
+    /// Pops arguments and a function, then calls it.
+    CallValue(usize),
+    /// Evaluates a constant with the tree-walking interpreter.
+    Fallback(usize),
+    /// Like `Call`, in tail position of a custom form body : a call to a
+    /// custom form ends the execution, to replace the current call.
+    TailCall(usize),
+    /// Like `CallValue`, in tail position of a custom form body.
+    TailCallValue(usize),
+    /// Like `Fallback`, in tail position of a custom form body.
+    TailFallback(usize),
+
+    /// Pops keys and values, then pushes a map.
+    MakeMap(usize),
+    /// Pops elements, then pushes a set.
+    MakeSet(usize),
+}
+
+/// A compiled expression or body.
+///
+#[derive(Clone, Default)]
+pub struct Chunk {
+    pub code: Vec<Instruction>,
+    pub constants: Vec<Object>,
+    /// Names of the local variables held in slots, empty for the slots of
+    /// the loops.
+    pub slots: Vec<String>,
+    /// Number of arguments held in the first slots, or `None` if they must
+    /// be bound in a scope level.
+    pub arguments: Option<usize>,
+    /// Names looked up in the scope, or `None` if the chunk evaluates
+    /// expressions with the tree-walking interpreter.
+    pub names: Option<Vec<String>>,
+    /// Operators resolved by `Resolve`, by call constant, with the
+    /// generation of the scope they were resolved at.
+    ///
+    /// Only the operators are cached : the names loaded by `LoadName` are
+    /// mostly variables, rebound on every call or loop iteration.
+    pub resolved: RefCell<Vec<Option<(Generation, Binding)>>>,
+}
+
+impl fmt::Display for Chunk {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, instruction) in self.code.iter().enumerate() {
+            write!(f, "{:4} {:?}", i, instruction)?;
+
+            match instruction {
+                Instruction::Constant(c)
+                | Instruction::LoadName(c)
+                | Instruction::Bind(c)
+                | Instruction::CheckSet(c)
+                | Instruction::SetName(c)
+                | Instruction::CheckDefine(c)
+                | Instruction::Define { symbol: c, .. }
+                | Instruction::Resolve { call: c, .. }
+                | Instruction::ResolveSlot { call: c, .. }
+                | Instruction::CheckFunction(c)
+                | Instruction::Fallback(c)
+                | Instruction::TailFallback(c) => write!(f, " ; {}", self.constants[*c])?,
+                Instruction::LoadSlot(s) | Instruction::StoreSlot(s) | Instruction::SetSlot(s) => {
+                    write!(f, " ; {}", self.slots[*s])?
+                }
+                _ => (),
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,742 @@
+use crate::nl::core::object::*;
+use crate::nl::interpreter::*;
+
+use super::chunk::*;
+
+use std::cell::RefCell;
+
+/// Special forms compiled into instructions. Their bindings are resolved
+/// when compiling : redefining them doesn't affect the compiled chunks.
+///
+const INTRINSICS: [&str; 15] = [
+    "quote", "if", "do", "let", "when", "unless", "cond", "and", "or", "not", "while", "break",
+    "def", "defconst", "set!",
+];
+
+/// Compiles an expression.
+///
+/// The bindings of the scope decide how the calls are compiled, they are
+/// resolved again when the chunk is executed.
+///
+pub fn compile(scope: &Scope, object: &Object) -> Chunk {
+    let mut compiler = Compiler::new(scope);
+    let root = compiler.open_block(BlockKind::Root);
+
+    compiler.expression(object);
+    compiler.finish(root)
+}
+
+/// Compiles the body of a custom form. Its calls in tail position end the
+/// execution, to be made by the caller.
+///
+/// The arguments are held in slots if they are all required, and if the
+/// body never evaluates code which could look them up in the scope.
+///
+pub fn compile_body(scope: &Scope, form: &CustomForm) -> Chunk {
+    let mut compiler = Compiler::new(scope);
+    let root = compiler.open_block(BlockKind::Root);
+    let list = &form.arguments;
+
+    if list.optional.is_empty() && list.rest.is_none() && list.keys.is_empty() {
+        for name in &list.required {
+            compiler.declare(name);
+        }
+    } else {
+        compiler.blocks[root].bound = true;
+    }
+
+    compiler.body(&form.body, true);
+    compiler.finish(root)
+}
+
+/// An instruction whose operands depend on where the local variables are
+/// held, which is known once their block is compiled.
+///
+enum Op {
+    Emit(Instruction),
+    EnterBlock(usize),
+    LeaveBlock(usize),
+    LoadLocal {
+        local: usize,
+        symbol: usize,
+    },
+    BindLocal {
+        local: usize,
+        symbol: usize,
+    },
+    SetLocal {
+        local: usize,
+        symbol: usize,
+    },
+    ResolveLocal {
+        local: usize,
+        call: usize,
+        skip: usize,
+    },
+    Break(usize),
+    JumpIfBroken {
+        block: usize,
+        target: usize,
+    },
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum BlockKind {
+    /// The arguments of a custom form.
+    Root,
+    /// A body, like the branches of `if`.
+    Level,
+    /// The variables of `let`.
+    Let,
+    /// An iteration of `while`.
+    Loop,
+}
+
+/// A construct which enters a scope level when interpreted.
+///
+/// Its variables are held in slots, and it enters no level, unless its
+/// extent evaluates code which could look them up in the scope : it is
+/// then "bound", and behaves like the interpreted construct.
+///
+struct Block {
+    kind: BlockKind,
+    bound: bool,
+}
+
+struct Local {
+    name: String,
+    block: usize,
+}
+
+struct Compiler<'s> {
+    scope: &'s Scope,
+    code: Vec<Op>,
+    constants: Vec<Object>,
+    blocks: Vec<Block>,
+    /// Blocks being compiled, the innermost last.
+    open: Vec<usize>,
+    locals: Vec<Local>,
+    /// Locals in the lexical scope, the innermost last.
+    visible: Vec<usize>,
+    /// Loops being compiled, the innermost last.
+    loops: Vec<usize>,
+    /// Names looked up in the scope.
+    names: Option<Vec<String>>,
+}
+
+impl<'s> Compiler<'s> {
+    fn new(scope: &'s Scope) -> Compiler<'s> {
+        Compiler {
+            scope,
+            code: Vec::new(),
+            constants: Vec::new(),
+            blocks: Vec::new(),
+            open: Vec::new(),
+            locals: Vec::new(),
+            visible: Vec::new(),
+            loops: Vec::new(),
+            names: Some(Vec::new()),
+        }
+    }
+
+    fn emit(&mut self, instruction: Instruction) -> usize {
+        self.code.push(Op::Emit(instruction));
+        self.code.len() - 1
+    }
+
+    fn constant(&mut self, object: &Object) -> usize {
+        self.constants.push(object.clone());
+        self.constants.len() - 1
+    }
+
+    /// Sets the target of a jump to the next instruction.
+    ///
+    fn patch(&mut self, at: usize) {
+        let next = self.code.len();
+
+        match &mut self.code[at] {
+            Op::Emit(Instruction::Jump(target))
+            | Op::Emit(Instruction::Branch { target, .. })
+            | Op::Emit(Instruction::Resolve { skip: target, .. })
+            | Op::ResolveLocal { skip: target, .. }
+            | Op::JumpIfBroken { target, .. } => *target = next,
+            _ => unreachable!(),
+        }
+    }
+
+    fn open_block(&mut self, kind: BlockKind) -> usize {
+        self.blocks.push(Block { kind, bound: false });
+
+        let block = self.blocks.len() - 1;
+        self.open.push(block);
+
+        if kind != BlockKind::Root {
+            self.code.push(Op::EnterBlock(block));
+        }
+
+        block
+    }
+
+    fn close_block(&mut self, block: usize, visible: usize) {
+        self.code.push(Op::LeaveBlock(block));
+        self.open.pop();
+        self.visible.truncate(visible);
+    }
+
+    /// Declares a local variable in the innermost block.
+    ///
+    fn declare(&mut self, name: &str) -> usize {
+        self.locals.push(Local {
+            name: name.to_string(),
+            block: *self.open.last().unwrap(),
+        });
+
+        let local = self.locals.len() - 1;
+        self.visible.push(local);
+
+        local
+    }
+
+    fn local(&self, name: &str) -> Option<usize> {
+        self.visible
+            .iter()
+            .rev()
+            .find(|x| self.locals[**x].name == name)
+            .copied()
+    }
+
+    /// Records a name looked up in the scope.
+    ///
+    fn look_up(&mut self, name: &str) {
+        if let Some(names) = &mut self.names {
+            if !names.iter().any(|x| x == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+
+    /// Evaluates an object with the interpreter. The open blocks become
+    /// bound, as the object can look up any variable.
+    ///
+    fn fallback(&mut self, object: &Object, tail: bool) {
+        for block in &self.open {
+            self.blocks[*block].bound = true;
+        }
+
+        self.names = None;
+
+        let c = self.constant(object);
+
+        if tail {
+            self.emit(Instruction::TailFallback(c));
+        } else {
+            self.emit(Instruction::Fallback(c));
+        }
+    }
+
+    fn expression(&mut self, object: &Object) {
+        self.expression_at(object, false)
+    }
+
+    /// Compiles an expression, in tail position of a custom form body if
+    /// `tail` is true.
+    ///
+    fn expression_at(&mut self, object: &Object, tail: bool) {
+        match object {
+            Object::Symbol(_, name) => {
+                let symbol = self.constant(object);
+
+                match self.local(name) {
+                    Some(local) => self.code.push(Op::LoadLocal { local, symbol }),
+                    None => {
+                        self.look_up(name);
+                        self.emit(Instruction::LoadName(symbol));
+                    }
+                }
+            }
+            Object::List(_, v) if v.is_empty() => {
+                self.emit(Instruction::Nil);
+            }
+            Object::List(_, v) => self.call(object, v, tail),
+            Object::Map(_, v) => {
+                for (key, value) in v {
+                    self.expression(key);
+                    self.expression(value);
+                }
+
+                self.emit(Instruction::MakeMap(v.len()));
+            }
+            Object::Set(_, v) => {
+                for x in v {
+                    self.expression(x);
+                }
+
+                self.emit(Instruction::MakeSet(v.len()));
+            }
+            _ => {
+                let c = self.constant(object);
+                self.emit(Instruction::Constant(c));
+            }
+        }
+    }
+
+    /// Compiles a body, leaving the value of its last expression (or nil).
+    /// The last expression is in tail position if `tail` is true.
+    ///
+    fn body(&mut self, body: &[Object], tail: bool) {
+        if body.is_empty() {
+            self.emit(Instruction::Nil);
+        }
+
+        for (i, x) in body.iter().enumerate() {
+            if i > 0 {
+                self.emit(Instruction::Pop);
+            }
+
+            self.expression_at(x, tail && i + 1 == body.len());
+        }
+    }
+
+    /// Compiles a body in a block, like `evaluate_body`.
+    ///
+    fn block_body(&mut self, body: &[Object], tail: bool) {
+        let visible = self.visible.len();
+        let block = self.open_block(BlockKind::Level);
+
+        self.body(body, tail);
+        self.close_block(block, visible);
+    }
+
+    fn call(&mut self, object: &Object, list: &[Object], tail: bool) {
+        let operator = &list[0];
+        let args = &list[1..];
+
+        let name = match operator {
+            Object::Symbol(_, v) => v,
+            Object::List(_, _) => {
+                let c = self.constant(object);
+
+                self.expression(operator);
+                self.emit(Instruction::CheckFunction(c));
+                self.arguments(args);
+
+                if tail {
+                    self.emit(Instruction::TailCallValue(args.len()));
+                } else {
+                    self.emit(Instruction::CallValue(args.len()));
+                }
+
+                return;
+            }
+            _ => return self.fallback(object, tail),
+        };
+
+        if let Some(local) = self.local(name) {
+            let call = self.constant(object);
+            let at = self.code.len();
+
+            self.code.push(Op::ResolveLocal {
+                local,
+                call,
+                skip: 0,
+            });
+            self.arguments(args);
+            self.call_instruction(args.len(), tail);
+            self.patch(at);
+            return;
+        }
+
+        match self.scope.get_binding(name) {
+            Some(Binding::SpecialForm(_)) => {
+                let intrinsic = INTRINSICS.iter().find(|x| *x == name);
+
+                match intrinsic {
+                    Some(name) if self.is_valid(name, args) => {
+                        self.intrinsic(name, operator, args, tail)
+                    }
+                    _ => self.fallback(object, tail),
+                }
+            }
+            Some(Binding::EvalForm(_))
+            | Some(Binding::DynamicForm(_))
+            | Some(Binding::DynamicVariable(Object::Function(_, _)))
+            | Some(Binding::ConstantVariable(Object::Function(_, _), _)) => {
+                self.look_up(name);
+
+                let call = self.constant(object);
+                let at = self.emit(Instruction::Resolve { call, skip: 0 });
+
+                self.arguments(args);
+                self.call_instruction(args.len(), tail);
+                self.patch(at);
+            }
+            _ => self.fallback(object, tail),
+        }
+    }
+
+    fn call_instruction(&mut self, argc: usize, tail: bool) {
+        if tail {
+            self.emit(Instruction::TailCall(argc));
+        } else {
+            self.emit(Instruction::Call(argc));
+        }
+    }
+
+    fn arguments(&mut self, args: &[Object]) {
+        for x in args {
+            self.expression(x);
+        }
+    }
+
+    /// Checks the syntax of an intrinsic form. The invalid forms are
+    /// interpreted, to raise their errors.
+    ///
+    fn is_valid(&self, name: &str, args: &[Object]) -> bool {
+        let is_clause = |x: &Object| matches!(x.as_list(), Some(v) if !v.is_empty());
+        let is_else = |x: &Object| matches!(&x.get_list()[0], Object::Symbol(_, v) if v == "else");
+
+        match name {
+            "quote" | "not" => args.len() == 1,
+            "if" => args.len() == 2 || args.len() == 3,
+            "do" | "and" | "or" => true,
+            "let" => {
+                args.len() >= 2
+                    && matches!(args[0].as_list(), Some(v)
+                        if v.len().is_multiple_of(2) && v.iter().step_by(2).all(|x| x.is_symbol()))
+            }
+            "when" | "unless" => !args.is_empty(),
+            "cond" => args
+                .iter()
+                .enumerate()
+                .all(|(i, x)| is_clause(x) && (i + 1 == args.len() || !is_else(x))),
+            "while" => args.len() >= 2,
+            "break" => args.is_empty() && !self.loops.is_empty(),
+            "def" | "defconst" | "set!" => args.len() == 2 && args[0].is_symbol(),
+            _ => false,
+        }
+    }
+
+    /// Compiles an intrinsic form. `if`, `do`, `let`, `when`, `unless` and
+    /// `cond` pass the tail position on to their last expressions.
+    ///
+    fn intrinsic(&mut self, name: &'static str, operator: &Object, args: &[Object], tail: bool) {
+        if name == "quote" {
+            let c = self.constant(&args[0]);
+            self.emit(Instruction::Constant(c));
+            return;
+        }
+
+        let location = self.constant(operator);
+        self.emit(Instruction::PushFrame { name, location });
+
+        match name {
+            "if" => self.compile_if(args, tail),
+            "do" => self.block_body(args, tail),
+            "let" => self.compile_let(args, tail),
+            "when" | "unless" => self.compile_when(name, args, tail),
+            "cond" => self.compile_cond(args, tail),
+            "and" | "or" => self.compile_and(name, args),
+            "not" => {
+                let c = self.constant(&args[0]);
+
+                self.expression(&args[0]);
+                self.emit(Instruction::Not(c));
+            }
+            "while" => self.compile_while(args),
+            "break" => {
+                let block = *self.loops.last().unwrap();
+                self.code.push(Op::Break(block));
+            }
+            "def" | "defconst" => self.compile_def(name, args),
+            "set!" => self.compile_set(args),
+            _ => unreachable!(),
+        }
+
+        self.emit(Instruction::PopFrame);
+    }
+
+    /// Emits a branch on the value of a condition.
+    ///
+    fn branch(&mut self, form: &'static str, condition: &Object, when: bool) -> usize {
+        let condition = self.constant(condition);
+
+        self.emit(Instruction::Branch {
+            form,
+            condition,
+            when,
+            target: 0,
+        })
+    }
+
+    fn compile_if(&mut self, args: &[Object], tail: bool) {
+        self.expression(&args[0]);
+
+        let otherwise = self.branch("if", &args[0], false);
+        self.block_body(&args[1..2], tail && args.len() == 3);
+
+        if args.len() == 2 {
+            self.emit(Instruction::Pop);
+            self.emit(Instruction::Nil);
+        }
+
+        let end = self.emit(Instruction::Jump(0));
+        self.patch(otherwise);
+
+        if args.len() == 3 {
+            self.block_body(&args[2..], tail);
+        } else {
+            self.emit(Instruction::Nil);
+        }
+
+        self.patch(end);
+    }
+
+    fn compile_let(&mut self, args: &[Object], tail: bool) {
+        let visible = self.visible.len();
+        let block = self.open_block(BlockKind::Let);
+
+        for pair in args[0].get_list().chunks(2) {
+            self.expression(&pair[1]);
+
+            let local = self.declare(pair[0].get_symbol());
+            let symbol = self.constant(&pair[0]);
+            self.code.push(Op::BindLocal { local, symbol });
+        }
+
+        self.body(&args[1..], tail);
+        self.close_block(block, visible);
+    }
+
+    fn compile_when(&mut self, name: &'static str, args: &[Object], tail: bool) {
+        self.expression(&args[0]);
+
+        let otherwise = self.branch(name, &args[0], name == "unless");
+        self.block_body(&args[1..], tail);
+
+        let end = self.emit(Instruction::Jump(0));
+        self.patch(otherwise);
+        self.emit(Instruction::Nil);
+        self.patch(end);
+    }
+
+    fn compile_cond(&mut self, args: &[Object], tail: bool) {
+        let mut ends = Vec::new();
+        let mut has_else = false;
+
+        for clause in args {
+            let clause = clause.get_list();
+
+            if matches!(&clause[0], Object::Symbol(_, v) if v == "else") {
+                self.block_body(&clause[1..], tail);
+                has_else = true;
+                break;
+            }
+
+            self.expression(&clause[0]);
+            let next = self.branch("cond", &clause[0], false);
+
+            if clause.len() == 1 {
+                let c = self.constant(&Object::bool(true));
+                self.emit(Instruction::Constant(c));
+            } else {
+                self.block_body(&clause[1..], tail);
+            }
+
+            ends.push(self.emit(Instruction::Jump(0)));
+            self.patch(next);
+        }
+
+        if !has_else {
+            self.emit(Instruction::Nil);
+        }
+
+        for at in ends {
+            self.patch(at);
+        }
+    }
+
+    /// Compiles `and` or `or`, which stop at the first argument equal to
+    /// `false` or `true`.
+    ///
+    fn compile_and(&mut self, name: &'static str, args: &[Object]) {
+        let stop = name == "or";
+        let mut stops = Vec::new();
+
+        for x in args {
+            self.expression(x);
+            stops.push(self.branch(name, x, stop));
+        }
+
+        let c = self.constant(&Object::bool(!stop));
+        self.emit(Instruction::Constant(c));
+        let end = self.emit(Instruction::Jump(0));
+
+        for at in stops {
+            self.patch(at);
+        }
+
+        let c = self.constant(&Object::bool(stop));
+        self.emit(Instruction::Constant(c));
+        self.patch(end);
+    }
+
+    fn compile_while(&mut self, args: &[Object]) {
+        let start = self.code.len();
+
+        self.expression(&args[0]);
+        let exit = self.branch("while", &args[0], false);
+
+        let visible = self.visible.len();
+        let block = self.open_block(BlockKind::Loop);
+        self.loops.push(block);
+
+        for x in &args[1..] {
+            self.expression(x);
+            self.emit(Instruction::Pop);
+        }
+
+        let broken = self.code.len();
+        self.code.push(Op::JumpIfBroken { block, target: 0 });
+        self.code.push(Op::LeaveBlock(block));
+        self.emit(Instruction::Jump(start));
+        self.patch(broken);
+
+        self.loops.pop();
+        self.close_block(block, visible);
+
+        self.patch(exit);
+        self.emit(Instruction::Nil);
+    }
+
+    fn compile_def(&mut self, name: &'static str, args: &[Object]) {
+        let symbol = self.constant(&args[0]);
+
+        self.emit(Instruction::CheckDefine(symbol));
+        self.emit(Instruction::PushFrame {
+            name,
+            location: symbol,
+        });
+        self.expression(&args[1]);
+        self.emit(Instruction::PopFrame);
+        self.emit(Instruction::Define {
+            symbol,
+            constant: name == "defconst",
+        });
+    }
+
+    fn compile_set(&mut self, args: &[Object]) {
+        let name = args[0].get_symbol();
+        let symbol = self.constant(&args[0]);
+        let local = self.local(name);
+
+        if local.is_none() {
+            self.look_up(name);
+            self.emit(Instruction::CheckSet(symbol));
+        }
+
+        self.emit(Instruction::PushFrame {
+            name: "set!",
+            location: symbol,
+        });
+        self.expression(&args[1]);
+        self.emit(Instruction::PopFrame);
+
+        match local {
+            Some(local) => self.code.push(Op::SetLocal { local, symbol }),
+            None => {
+                self.emit(Instruction::SetName(symbol));
+            }
+        }
+    }
+
+    /// Assigns the slots of the locals whose block isn't bound, then
+    /// resolves the instructions.
+    ///
+    fn finish(self, root: usize) -> Chunk {
+        let mut slots = Vec::new();
+        let mut local_slots = Vec::with_capacity(self.locals.len());
+        let mut ranges: Vec<Option<(usize, usize)>> = vec![None; self.blocks.len()];
+
+        for local in &self.locals {
+            if self.blocks[local.block].bound {
+                local_slots.push(None);
+                continue;
+            }
+
+            let slot = slots.len();
+            slots.push(local.name.clone());
+            local_slots.push(Some(slot));
+
+            let range = ranges[local.block].get_or_insert((slot, slot));
+            range.1 = slot + 1;
+        }
+
+        let mut flags = vec![0; self.blocks.len()];
+
+        for (i, block) in self.blocks.iter().enumerate() {
+            if block.kind == BlockKind::Loop && !block.bound {
+                flags[i] = slots.len();
+                slots.push(String::new());
+            }
+        }
+
+        let code = self
+            .code
+            .iter()
+            .map(|op| match *op {
+                Op::Emit(v) => v,
+                Op::EnterBlock(b) => match (self.blocks[b].bound, self.blocks[b].kind) {
+                    (true, BlockKind::Loop) => Instruction::EnterLoop,
+                    (true, _) => Instruction::Enter,
+                    (false, BlockKind::Loop) => Instruction::ClearSlots(flags[b], flags[b] + 1),
+                    (false, _) => Instruction::Nop,
+                },
+                Op::LeaveBlock(b) => match (self.blocks[b].bound, ranges[b]) {
+                    (true, _) => Instruction::Leave,
+                    (false, Some((start, end))) => Instruction::ClearSlots(start, end),
+                    (false, None) => Instruction::Nop,
+                },
+                Op::LoadLocal { local, symbol } => match local_slots[local] {
+                    Some(slot) => Instruction::LoadSlot(slot),
+                    None => Instruction::LoadName(symbol),
+                },
+                Op::BindLocal { local, symbol } => match local_slots[local] {
+                    Some(slot) => Instruction::StoreSlot(slot),
+                    None => Instruction::Bind(symbol),
+                },
+                Op::SetLocal { local, symbol } => match local_slots[local] {
+                    Some(slot) => Instruction::SetSlot(slot),
+                    None => Instruction::SetName(symbol),
+                },
+                Op::ResolveLocal { local, call, skip } => match local_slots[local] {
+                    Some(slot) => Instruction::ResolveSlot { slot, call },
+                    None => Instruction::Resolve { call, skip },
+                },
+                Op::Break(b) if self.blocks[b].bound => Instruction::Break,
+                Op::Break(b) => Instruction::MarkSlot(flags[b]),
+                Op::JumpIfBroken { block, target } if self.blocks[block].bound => {
+                    Instruction::JumpIfBroken(target)
+                }
+                Op::JumpIfBroken { block, target } => Instruction::JumpIfMarked {
+                    slot: flags[block],
+                    target,
+                },
+            })
+            .collect();
+
+        let arguments = if self.blocks[root].bound {
+            None
+        } else {
+            Some(self.locals.iter().filter(|x| x.block == root).count())
+        };
+
+        Chunk {
+            code,
+            resolved: RefCell::new(vec![None; self.constants.len()]),
+            constants: self.constants,
+            slots,
+            arguments,
+            names: self.names,
+        }
+    }
+}
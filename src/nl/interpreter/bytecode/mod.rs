@@ -0,0 +1,41 @@
+mod chunk;
+mod compiler;
+mod vm;
+
+pub use chunk::*;
+pub use compiler::*;
+pub use vm::*;
+
+use crate::nl::core::object::*;
+
+use super::eval::*;
+use super::scope::*;
+use super::source::*;
+
+/// Compiles then executes an expression.
+///
+pub fn evaluate_compiled(scope: &mut Scope, object: &Object) -> Result<Object, Error> {
+    let chunk = compile(scope, object);
+    execute(scope, &chunk)
+}
+
+/// Reads then compiles and executes every expression of a source file, in
+/// order. Each expression is compiled once the previous ones are executed,
+/// so that it sees their definitions.
+///
+/// Returns the value of the last expression, or `nil` if the source is
+/// empty.
+///
+pub fn evaluate_source_compiled(
+    scope: &mut Scope,
+    file: &str,
+    source: &str,
+) -> Result<Object, Error> {
+    let mut result = Object::nil();
+
+    for object in read_source(file, source)? {
+        result = evaluate_compiled(scope, &object)?;
+    }
+
+    Ok(result)
+}
@@ -0,0 +1,653 @@
+use crate::nl::core::object::*;
+use crate::nl::interpreter::builtin::{check_define, check_set, condition_error, name_function};
+use crate::nl::interpreter::*;
+
+use super::chunk::*;
+use super::compiler::*;
+
+use indexmap::{IndexMap, IndexSet};
+
+use std::rc::Rc;
+
+/// Maximum number of dynamic forms searched when checking if a call can
+/// look up the variables held in the slots of the caller. Beyond it, they
+/// are assumed to be looked up.
+///
+const MAX_SEARCHED_FORMS: usize = 16;
+
+/// Executes a chunk compiled by `compile`.
+///
+pub fn execute(scope: &mut Scope, chunk: &Chunk) -> Result<Object, Error> {
+    match Execution::new(chunk, Vec::new()).run(scope)? {
+        Tail::Value(v) => Ok(v),
+        Tail::Call(call) => execute_call(scope, call),
+    }
+}
+
+/// Resolved operator of a call.
+///
+enum Callee {
+    Eval(EvalForm),
+    Dynamic(Rc<CustomForm>),
+    Function(Rc<Function>),
+}
+
+/// Construct whose stack frames are added to the errors raised within it.
+///
+enum Context {
+    Frame { name: &'static str, location: usize },
+    Call { callee: Callee, call: usize },
+}
+
+/// State of the execution of a chunk.
+///
+struct Execution<'c> {
+    chunk: &'c Chunk,
+    slots: Vec<Option<Object>>,
+    stack: Vec<Object>,
+    contexts: Vec<Context>,
+    /// Call in tail position ending the execution.
+    tail_call: Option<TailCall>,
+}
+
+impl<'c> Execution<'c> {
+    /// Creates the execution of a chunk, with the arguments held in its
+    /// first slots.
+    ///
+    fn new(chunk: &'c Chunk, args: Vec<Object>) -> Execution<'c> {
+        let mut slots: Vec<Option<Object>> = args.into_iter().map(Some).collect();
+        slots.resize(chunk.slots.len(), None);
+
+        Execution {
+            chunk,
+            slots,
+            stack: Vec::new(),
+            contexts: Vec::new(),
+            tail_call: None,
+        }
+    }
+
+    fn run(&mut self, scope: &mut Scope) -> Result<Tail, Error> {
+        let mut pc = 0;
+
+        while let Some(instruction) = self.chunk.code.get(pc) {
+            pc += 1;
+
+            if let Err(err) = self.step(scope, *instruction, &mut pc) {
+                return Err(self.unwind(err));
+            }
+
+            if let Some(call) = self.tail_call.take() {
+                return Ok(Tail::Call(call));
+            }
+        }
+
+        Ok(Tail::Value(self.stack.pop().unwrap_or_else(Object::nil)))
+    }
+
+    fn pop(&mut self) -> Object {
+        self.stack.pop().unwrap()
+    }
+
+    fn constant(&self, c: usize) -> &'c Object {
+        &self.chunk.constants[c]
+    }
+
+    fn step(
+        &mut self,
+        scope: &mut Scope,
+        instruction: Instruction,
+        pc: &mut usize,
+    ) -> Result<(), Error> {
+        match instruction {
+            Instruction::Nop => (),
+            Instruction::Nil => self.stack.push(Object::nil()),
+            Instruction::Constant(c) => self.stack.push(self.constant(c).clone()),
+            Instruction::Pop => {
+                self.pop();
+            }
+
+            Instruction::LoadSlot(s) => match &self.slots[s] {
+                Some(v) => self.stack.push(v.clone()),
+                None => {
+                    return Error::err(&format!(
+                        "the variable '{}' is used before being assigned.",
+                        self.chunk.slots[s]
+                    ))
+                }
+            },
+            Instruction::StoreSlot(s) => self.slots[s] = Some(self.pop()),
+            Instruction::SetSlot(s) => self.slots[s] = self.stack.last().cloned(),
+            Instruction::ClearSlots(start, end) => {
+                for slot in &mut self.slots[start..end] {
+                    *slot = None;
+                }
+            }
+            Instruction::MarkSlot(s) => {
+                self.slots[s] = Some(Object::nil());
+                self.stack.push(Object::nil());
+            }
+            Instruction::JumpIfMarked { slot, target } => {
+                if self.slots[slot].is_some() {
+                    *pc = target;
+                }
+            }
+
+            Instruction::LoadName(c) => {
+                let value = evaluate_symbol(scope, self.constant(c))?;
+                self.stack.push(value);
+            }
+            Instruction::Bind(c) => {
+                let value = self.pop();
+                scope.insert(
+                    self.constant(c).get_symbol().clone(),
+                    Binding::DynamicVariable(value),
+                );
+            }
+            Instruction::CheckSet(c) => {
+                let symbol = self.constant(c);
+                check_set(scope, symbol.get_symbol(), location(symbol))?;
+            }
+            Instruction::SetName(c) => {
+                let value = self.stack.last().unwrap().clone();
+                scope.set(
+                    self.constant(c).get_symbol().clone(),
+                    Binding::DynamicVariable(value),
+                );
+            }
+            Instruction::CheckDefine(c) => {
+                let symbol = self.constant(c);
+                check_define(scope, symbol.get_symbol(), location(symbol))?;
+            }
+            Instruction::Define { symbol, constant } => {
+                let symbol = self.constant(symbol);
+                let name = symbol.get_symbol();
+                let value = name_function(self.pop(), name);
+
+                let binding = if constant {
+                    Binding::ConstantVariable(value, location(symbol))
+                } else {
+                    Binding::DynamicVariable(value)
+                };
+
+                scope.insert_global(name.clone(), binding);
+                self.stack.push(Object::nil());
+            }
+
+            Instruction::Enter => {
+                scope.enter(Mode::Evaluation);
+            }
+            Instruction::EnterLoop => {
+                scope.enter_loop(Mode::Evaluation);
+            }
+            Instruction::Leave => {
+                scope.leave();
+            }
+            Instruction::Break => {
+                scope.break_loop();
+                self.stack.push(Object::nil());
+            }
+            Instruction::JumpIfBroken(target) => {
+                if scope.is_loop_broken() {
+                    *pc = target;
+                }
+            }
+
+            Instruction::Jump(target) => *pc = target,
+            Instruction::Branch {
+                form,
+                condition,
+                when,
+                target,
+            } => {
+                let value = self.pop();
+
+                match value.as_bool() {
+                    Some(v) if v == when => *pc = target,
+                    Some(_) => (),
+                    None => return Err(condition_error(form, &value, self.constant(condition))),
+                }
+            }
+            Instruction::Not(c) => {
+                let value = self.pop();
+
+                match value.as_bool() {
+                    Some(v) => self.stack.push(Object::bool(!v)),
+                    None => return Err(condition_error("not", &value, self.constant(c))),
+                }
+            }
+
+            Instruction::PushFrame { name, location } => {
+                self.contexts.push(Context::Frame { name, location });
+            }
+            Instruction::PopFrame => {
+                self.contexts.pop();
+            }
+
+            Instruction::Resolve { call, skip } => {
+                let operator = &self.constant(call).get_list()[0];
+                let name = operator.get_symbol();
+
+                let callee = match self.resolve(scope, call, name) {
+                    None => return unbound_symbol(name, location(operator)),
+                    Some(Binding::EvalForm(f)) => Callee::Eval(f),
+                    Some(Binding::DynamicForm(form)) => Callee::Dynamic(form),
+                    Some(Binding::DynamicVariable(v)) | Some(Binding::ConstantVariable(v, _)) => {
+                        match v {
+                            Object::Function(_, f) => Callee::Function(f),
+                            _ => return Err(not_an_operator(operator)),
+                        }
+                    }
+                    // The binding changed since the call was compiled.
+                    Some(Binding::SpecialForm(_)) | Some(Binding::MacroForm(_)) => {
+                        let call = self.constant(call);
+                        let value = self.with_slots_bound(scope, |scope| evaluate(scope, call))?;
+
+                        self.stack.push(value);
+                        *pc = skip;
+                        return Ok(());
+                    }
+                };
+
+                self.contexts.push(Context::Call { callee, call });
+            }
+            Instruction::ResolveSlot { slot, call } => match &self.slots[slot] {
+                Some(Object::Function(_, f)) => {
+                    let callee = Callee::Function(f.clone());
+                    self.contexts.push(Context::Call { callee, call });
+                }
+                _ => return Err(not_an_operator(&self.constant(call).get_list()[0])),
+            },
+            Instruction::Call(argc) => {
+                let args = self.stack.split_off(self.stack.len() - argc);
+
+                let (callee, call) = match self.contexts.pop() {
+                    Some(Context::Call { callee, call }) => (callee, call),
+                    _ => unreachable!(),
+                };
+
+                let operator = &self.constant(call).get_list()[0];
+                let name = operator.get_symbol();
+
+                let result = match callee {
+                    Callee::Eval(f) => f(args),
+                    Callee::Dynamic(form) => self.call_dynamic_form(scope, operator, form, args),
+                    Callee::Function(function) => {
+                        execute_call(scope, TailCall::Function { function, args })
+                    }
+                };
+
+                // The location is only cloned on errors, calls being frequent.
+                let value = result.map_err(|err| err.push(name, location(operator)))?;
+                self.stack.push(value);
+            }
+            Instruction::CheckFunction(c) => {
+                let value = self.stack.last().unwrap();
+
+                if !matches!(value, Object::Function(_, _)) {
+                    return Error::new_kind(
+                        ErrorKind::wrong_type("Function", value),
+                        &format!(
+                            "expected a Function as operator, got a {} : {}",
+                            value.type_string(),
+                            value
+                        ),
+                    )
+                    .push_err("neolisp", location(&self.constant(c).get_list()[0]));
+                }
+            }
+            Instruction::CallValue(argc) => {
+                let args = self.stack.split_off(self.stack.len() - argc);
+
+                let value = match self.pop() {
+                    Object::Function(_, function) => {
+                        execute_call(scope, TailCall::Function { function, args })?
+                    }
+                    _ => unreachable!(),
+                };
+
+                self.stack.push(value);
+            }
+            Instruction::Fallback(c) => {
+                let value = evaluate(scope, self.constant(c))?;
+                self.stack.push(value);
+            }
+            Instruction::TailCall(argc) => {
+                let args = self.stack.split_off(self.stack.len() - argc);
+
+                let (callee, call) = match self.contexts.pop() {
+                    Some(Context::Call { callee, call }) => (callee, call),
+                    _ => unreachable!(),
+                };
+
+                let operator = &self.constant(call).get_list()[0];
+                let name = operator.get_symbol();
+
+                match callee {
+                    Callee::Eval(f) => {
+                        let value = f(args).map_err(|err| err.push(name, location(operator)))?;
+                        self.stack.push(value);
+                    }
+                    Callee::Dynamic(form) => {
+                        if self.is_looked_up(scope, &form) {
+                            scope.enter(Mode::Evaluation);
+                            self.bind_slots(scope, &self.variable_slots());
+                        }
+
+                        self.tail_call = Some(TailCall::Dynamic {
+                            name: name.clone(),
+                            location: location(operator),
+                            form,
+                            args,
+                        });
+                    }
+                    Callee::Function(function) => {
+                        self.tail_call = Some(TailCall::Function { function, args });
+                    }
+                }
+            }
+            Instruction::TailCallValue(argc) => {
+                let args = self.stack.split_off(self.stack.len() - argc);
+
+                self.tail_call = match self.pop() {
+                    Object::Function(_, function) => Some(TailCall::Function { function, args }),
+                    _ => unreachable!(),
+                };
+            }
+            Instruction::TailFallback(c) => match evaluate_tail(scope, self.constant(c))? {
+                Tail::Value(v) => self.stack.push(v),
+                Tail::Call(call) => self.tail_call = Some(call),
+            },
+
+            Instruction::MakeMap(n) => {
+                let items = self.stack.split_off(self.stack.len() - 2 * n);
+                let mut map = IndexMap::with_capacity(n);
+                let mut items = items.into_iter();
+
+                while let (Some(key), Some(value)) = (items.next(), items.next()) {
+                    map.insert(key, value);
+                }
+
+                self.stack.push(Object::map(map));
+            }
+            Instruction::MakeSet(n) => {
+                let items = self.stack.split_off(self.stack.len() - n);
+                let set: IndexSet<Object> = items.into_iter().collect();
+
+                self.stack.push(Object::set(set));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Gets the binding of the operator of a call constant, cached until
+    /// the generation of the watched bindings changes.
+    ///
+    fn resolve(&self, scope: &mut Scope, call: usize, name: &str) -> Option<Binding> {
+        let generation = scope.generation();
+
+        if let Some((g, binding)) = &self.chunk.resolved.borrow()[call] {
+            if *g == generation {
+                return Some(binding.clone());
+            }
+        }
+
+        let binding = scope.get_binding(name)?;
+
+        // The other bindings aren't called, they are reported or evaluated
+        // by the interpreter.
+        let callable = match &binding {
+            Binding::EvalForm(_) | Binding::DynamicForm(_) => true,
+            Binding::DynamicVariable(v) | Binding::ConstantVariable(v, _) => v.is_function(),
+            Binding::SpecialForm(_) | Binding::MacroForm(_) => false,
+        };
+
+        if callable {
+            scope.watch(name);
+            self.chunk.resolved.borrow_mut()[call] = Some((generation, binding.clone()));
+        }
+
+        Some(binding)
+    }
+
+    /// Adds the stack frames of the open constructs to an error.
+    ///
+    fn unwind(&mut self, mut err: Error) -> Error {
+        while let Some(context) = self.contexts.pop() {
+            err = match context {
+                Context::Frame { name, location: c } => err.push(name, location(self.constant(c))),
+                Context::Call { callee, call } => {
+                    let operator = &self.constant(call).get_list()[0];
+                    let name = operator.get_symbol();
+
+                    match callee {
+                        Callee::Dynamic(form) => err
+                            .push(name, form.location.clone())
+                            .push(name, location(operator)),
+                        _ => err.push(name, location(operator)),
+                    }
+                }
+            };
+        }
+
+        err
+    }
+
+    /// Calls a dynamic form, like `evaluate_dynamic_form` once its arguments
+    /// are evaluated.
+    ///
+    fn call_dynamic_form(
+        &mut self,
+        scope: &mut Scope,
+        operator: &Object,
+        form: Rc<CustomForm>,
+        args: Vec<Object>,
+    ) -> Result<Object, Error> {
+        let looked_up = self.is_looked_up(scope, &form);
+        let call = TailCall::Dynamic {
+            name: operator.get_symbol().clone(),
+            location: location(operator),
+            form,
+            args,
+        };
+
+        if looked_up {
+            return self.with_slots_bound(scope, |scope| execute_call(scope, call));
+        }
+
+        execute_call(scope, call)
+    }
+
+    /// Gets the slots holding a variable, the innermost variable being the
+    /// last one of a name.
+    ///
+    fn variable_slots(&self) -> Vec<usize> {
+        (0..self.slots.len())
+            .filter(|x| self.slots[*x].is_some() && !self.chunk.slots[*x].is_empty())
+            .collect()
+    }
+
+    /// Checks if a call to a dynamic form can look up the variables held in
+    /// the slots : the dynamic forms see the variables of their callers.
+    ///
+    fn is_looked_up(&self, scope: &Scope, form: &Rc<CustomForm>) -> bool {
+        let variables = self.variable_slots();
+
+        if variables.is_empty() {
+            return false;
+        }
+
+        let names: Vec<&str> = variables
+            .iter()
+            .map(|x| self.chunk.slots[*x].as_str())
+            .collect();
+        let mut searched = vec![Rc::as_ptr(form)];
+
+        looks_up(scope, form, &names, &mut searched)
+    }
+
+    /// Calls a closure with the variables held in the slots bound in a new
+    /// scope level, then copies back their values.
+    ///
+    fn with_slots_bound<F>(&mut self, scope: &mut Scope, f: F) -> Result<Object, Error>
+    where
+        F: FnOnce(&mut Scope) -> Result<Object, Error>,
+    {
+        let variables = self.variable_slots();
+
+        if variables.is_empty() {
+            return f(scope);
+        }
+
+        let depth = scope.depth();
+        scope.enter(Mode::Evaluation);
+        self.bind_slots(scope, &variables);
+
+        let result = f(scope);
+
+        if result.is_err() {
+            scope.unwind(depth);
+            return result;
+        }
+
+        for (i, slot) in variables.iter().enumerate() {
+            let name = &self.chunk.slots[*slot];
+            let shadowed = variables[i + 1..]
+                .iter()
+                .any(|x| &self.chunk.slots[*x] == name);
+
+            if !shadowed {
+                if let Some(Binding::DynamicVariable(v)) = scope.get_binding(name) {
+                    self.slots[*slot] = Some(v);
+                }
+            }
+        }
+
+        scope.leave();
+        result
+    }
+
+    /// Binds the variables held in the given slots in the innermost level.
+    ///
+    fn bind_slots(&self, scope: &mut Scope, variables: &[usize]) {
+        for slot in variables {
+            let value = self.slots[*slot].clone().unwrap();
+            scope.insert(
+                self.chunk.slots[*slot].clone(),
+                Binding::DynamicVariable(value),
+            );
+        }
+    }
+}
+
+/// Makes a call to a custom form, then the calls in tail position of its
+/// body, like the interpreter.
+///
+fn execute_call(scope: &mut Scope, call: TailCall) -> Result<Object, Error> {
+    trampoline(scope, call, &mut execute_call_body)
+}
+
+/// Enters the levels of a custom form call and binds its arguments, unless
+/// they are held in slots, then executes its body.
+///
+fn execute_call_body(scope: &mut Scope, call: TailCall) -> Result<Tail, Error> {
+    match call {
+        TailCall::Dynamic {
+            name, form, args, ..
+        } => {
+            let chunk = form.code.get_or_init(|| compile_body(scope, &form));
+
+            let result = if chunk.arguments.is_some() {
+                check_arity(&name, &form, args.len())?;
+                Execution::new(chunk, args).run(scope)
+            } else {
+                scope.enter_loop_boundary(Mode::Evaluation);
+                bind_arguments(scope, &name, &form, args)?;
+                Execution::new(chunk, Vec::new()).run(scope)
+            };
+
+            match result {
+                Ok(v) => Ok(v),
+                Err(v) => v.push_err(&name, form.location.clone()),
+            }
+        }
+        TailCall::Function { function, args } => {
+            let name = function.display_name();
+            let form = &function.form;
+
+            scope.enter_closure(&function.environment);
+
+            let chunk = form.code.get_or_init(|| compile_body(scope, form));
+
+            let result = if chunk.arguments.is_some() {
+                check_arity(name, form, args.len())
+                    .and_then(|_| Execution::new(chunk, args).run(scope))
+            } else {
+                bind_arguments(scope, name, form, args)
+                    .and_then(|_| Execution::new(chunk, Vec::new()).run(scope))
+            };
+
+            Error::rethrow(result, name, form.location.clone())
+        }
+    }
+}
+
+/// Checks if the body of a dynamic form, or of the dynamic forms it calls,
+/// can look up one of the given names.
+///
+fn looks_up(
+    scope: &Scope,
+    form: &CustomForm,
+    names: &[&str],
+    searched: &mut Vec<*const CustomForm>,
+) -> bool {
+    let chunk = form.code.get_or_init(|| compile_body(scope, form));
+
+    let looked_up = match &chunk.names {
+        Some(v) => v,
+        None => return true,
+    };
+
+    if looked_up.iter().any(|x| names.contains(&x.as_str())) {
+        return true;
+    }
+
+    for name in looked_up {
+        if let Some(Binding::DynamicForm(callee)) = scope.get_binding(name) {
+            if searched.contains(&Rc::as_ptr(&callee)) {
+                continue;
+            }
+
+            if searched.len() == MAX_SEARCHED_FORMS {
+                return true;
+            }
+
+            searched.push(Rc::as_ptr(&callee));
+
+            if looks_up(scope, &callee, names, searched) {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+/// Creates the error of a call whose operator is a variable which doesn't
+/// hold a function.
+///
+fn not_an_operator(operator: &Object) -> Error {
+    let name = operator.get_symbol();
+
+    Error::newf(
+        &format!("'{}' : expected an operator, got a variable.", name),
+        name,
+        location(operator),
+    )
+    .push(name, location(operator))
+}
+
+fn location(object: &Object) -> Location {
+    object.get_info().location.clone()
+}
@@ -1,35 +1,81 @@
-use crate::nl::core::object::Object;
+use crate::nl::core::object::*;
 
 use super::binding::*;
+use super::builtin::evaluate_tail_form;
 use super::scope::*;
 
 pub use crate::nl::core::error::*;
 
+use indexmap::{IndexMap, IndexSet};
+
+use std::rc::Rc;
+
+/// Evaluates an object.
+///
+/// The elements of map and set literals are evaluated, while a vector
+/// literal is data, like a quoted list : `[a (f b)]` evaluates to itself.
+/// The `vector` form builds a vector from evaluated elements.
+///
 pub fn evaluate(scope: &mut Scope, object: &Object) -> Result<Object, Error> {
-    let r = match object {
+    match object {
         Object::Symbol(_, _) => evaluate_symbol(scope, object),
-        Object::List(_, expr) => evaluate_expression(scope, expr),
+        Object::List(_, expr) => evaluate_expression(scope, object, expr),
+        Object::Map(_, v) => evaluate_map(scope, v),
+        Object::Set(_, v) => evaluate_set(scope, v),
+        // Data, not evaluated.
+        Object::Vector(_, _) => Ok(object.clone()),
         _ => Ok(object.clone()),
-    };
+    }
+}
+
+/// Evaluates the keys and values of a map literal.
+///
+fn evaluate_map(scope: &mut Scope, map: &IndexMap<Object, Object>) -> Result<Object, Error> {
+    let mut result = IndexMap::with_capacity(map.len());
+
+    for (key, value) in map {
+        result.insert(evaluate(scope, key)?, evaluate(scope, value)?);
+    }
+
+    Ok(Object::map(result))
+}
+
+/// Evaluates the elements of a set literal.
+///
+fn evaluate_set(scope: &mut Scope, set: &IndexSet<Object>) -> Result<Object, Error> {
+    let mut result = IndexSet::with_capacity(set.len());
+
+    for x in set {
+        result.insert(evaluate(scope, x)?);
+    }
 
-    r
+    Ok(Object::set(result))
 }
 
-fn evaluate_symbol(scope: &mut Scope, object: &Object) -> Result<Object, Error> {
+/// Creates the error of an unbound symbol.
+///
+pub(crate) fn unbound_symbol<T>(name: &str, location: Location) -> Result<T, Error> {
+    Error::new_kind(
+        ErrorKind::UnboundSymbol {
+            name: name.to_string(),
+        },
+        &format!("unbound symbol '{}'", name),
+    )
+    .push_err(name, location)
+}
+
+pub(crate) fn evaluate_symbol(scope: &mut Scope, object: &Object) -> Result<Object, Error> {
     let symbol = object.get_symbol();
 
     if !scope.has_binding(symbol) {
-        return Error::errf(
-            &format!("unbound symbol '{}'", symbol),
-            symbol,
-            object.get_info().location.clone(),
-        );
+        return unbound_symbol(symbol, object.get_info().location.clone());
     }
 
     let binding = scope.get_binding(symbol).unwrap();
 
     match binding {
         Binding::DynamicVariable(object) => Ok(object.clone()),
+        Binding::ConstantVariable(object, _) => Ok(object.clone()),
         _ => Error::errf(
             &format!("'{}' is not a variable.", symbol),
             symbol,
@@ -38,8 +84,12 @@ fn evaluate_symbol(scope: &mut Scope, object: &Object) -> Result<Object, Error>
     }
 }
 
-fn evaluate_expression(scope: &mut Scope, expr: &Vec<Object>) -> Result<Object, Error> {
-    if expr.len() == 0 {
+fn evaluate_expression(
+    scope: &mut Scope,
+    object: &Object,
+    expr: &[Object],
+) -> Result<Object, Error> {
+    if expr.is_empty() {
         return Ok(Object::nil());
     }
 
@@ -49,38 +99,44 @@ fn evaluate_expression(scope: &mut Scope, expr: &Vec<Object>) -> Result<Object,
     match operator {
         Object::Symbol(info, name) => {
             if !scope.has_binding(name) {
-                return Error::errf(
-                    &format!("unbound symbol '{}'", name),
-                    name,
-                    info.location.clone(),
-                );
+                return unbound_symbol(name, info.location.clone());
             }
 
             let binding = scope.get_binding(name).unwrap();
 
             let r = match binding {
-                Binding::DynamicVariable(_) => Error::errf(
-                    &format!("'{}' : expected an operator, got a variable.", name),
-                    name,
-                    info.location.clone(),
-                ),
+                Binding::DynamicVariable(v) | Binding::ConstantVariable(v, _) => match v {
+                    Object::Function(_, f) => match evaluate_list(scope, rest) {
+                        Ok(args) => call_function(scope, &f, args),
+                        Err(v) => Err(v),
+                    },
+                    _ => Error::errf(
+                        &format!("'{}' : expected an operator, got a variable.", name),
+                        name,
+                        info.location.clone(),
+                    ),
+                },
                 Binding::SpecialForm(func) => func(scope, rest),
                 Binding::EvalForm(func) => func(match evaluate_list(scope, rest) {
                     Ok(v) => v,
                     Err(v) => return v.push_err(name, info.location.clone()),
                 }),
                 Binding::DynamicForm(form) => {
-                    let f = form.clone();
-                    evaluate_dynamic_form(scope, name, rest, f)
-                }
-                Binding::MacroForm(form) => {
-                    let f = form.clone();
-                    evaluate_macro_form(scope, name, rest, f)
+                    evaluate_dynamic_form(scope, name, &info.location, rest, &form)
                 }
+                Binding::MacroForm(form) => evaluate_macro_form(scope, object, name, rest, &form),
             };
 
             Error::rethrow(r, name, info.location.clone())
         }
+        Object::List(_, _) => {
+            let f = evaluate_operator(scope, operator)?;
+
+            match evaluate_list(scope, rest) {
+                Ok(args) => call_function(scope, &f, args),
+                Err(v) => Err(v),
+            }
+        }
         _ => Error::errf(
             &format!("invalid s-expression : {}", operator),
             "neolisp",
@@ -89,87 +145,479 @@ fn evaluate_expression(scope: &mut Scope, expr: &Vec<Object>) -> Result<Object,
     }
 }
 
-fn evaluate_list(scope: &mut Scope, list: &[Object]) -> Result<Vec<Object>, Error> {
-    let mut result = Vec::with_capacity(list.len());
+/// Evaluates the operator of a call which isn't a symbol, which must give
+/// a function.
+///
+fn evaluate_operator(scope: &mut Scope, operator: &Object) -> Result<Rc<Function>, Error> {
+    match evaluate(scope, operator)? {
+        Object::Function(_, f) => Ok(f),
+        v => Error::new_kind(
+            ErrorKind::wrong_type("Function", &v),
+            &format!(
+                "expected a Function as operator, got a {} : {}",
+                v.type_string(),
+                v
+            ),
+        )
+        .push_err("neolisp", operator.get_info().location.clone()),
+    }
+}
 
-    for x in list {
-        match evaluate(scope, x) {
-            Ok(v) => result.push(v),
-            Err(v) => return Err(v),
+/// Result of an expression evaluated in tail position of a custom form
+/// body.
+///
+pub(crate) enum Tail {
+    Value(Object),
+    /// Call ending the evaluation of the expression, which isn't made yet.
+    /// The scope levels entered by the expression are left open, as a
+    /// dynamic form can look up their variables.
+    Call(TailCall),
+}
+
+impl Tail {
+    /// Gets the value of an expression which wasn't evaluated in tail
+    /// position.
+    ///
+    pub(crate) fn into_value(self) -> Object {
+        match self {
+            Tail::Value(v) => v,
+            Tail::Call(_) => unreachable!(),
         }
     }
+}
 
-    Ok(result)
+/// Call of a custom form, whose arguments are evaluated.
+///
+pub(crate) enum TailCall {
+    Dynamic {
+        name: String,
+        location: Location,
+        form: Rc<CustomForm>,
+        args: Vec<Object>,
+    },
+    Function {
+        function: Rc<Function>,
+        args: Vec<Object>,
+    },
 }
 
-fn evaluate_dynamic_form(
+/// Evaluates an expression in tail position of a custom form body : a
+/// call to a custom form ending its evaluation is returned instead of
+/// being made, so that it can replace the call owning the body.
+///
+/// The flow control forms and the macros pass the tail position on to
+/// their last expression.
+///
+pub(crate) fn evaluate_tail(scope: &mut Scope, object: &Object) -> Result<Tail, Error> {
+    let expr = match object {
+        Object::List(_, v) if !v.is_empty() => v,
+        _ => return evaluate(scope, object).map(Tail::Value),
+    };
+
+    let operator = &expr[0];
+    let rest = &expr[1..];
+
+    let (info, name) = match operator {
+        Object::Symbol(info, name) => (info, name),
+        Object::List(_, _) => {
+            let function = evaluate_operator(scope, operator)?;
+            let args = evaluate_list(scope, rest)?;
+
+            return Ok(Tail::Call(TailCall::Function { function, args }));
+        }
+        _ => return evaluate(scope, object).map(Tail::Value),
+    };
+
+    let r = match scope.get_binding(name) {
+        Some(Binding::SpecialForm(_)) => match evaluate_tail_form(scope, name, rest) {
+            Some(v) => v,
+            None => return evaluate(scope, object).map(Tail::Value),
+        },
+        Some(Binding::DynamicForm(form)) => match evaluate_list(scope, rest) {
+            Ok(args) => Ok(Tail::Call(TailCall::Dynamic {
+                name: name.clone(),
+                location: info.location.clone(),
+                form,
+                args,
+            })),
+            Err(v) => v.push_err(name, form.location.clone()),
+        },
+        Some(Binding::DynamicVariable(Object::Function(_, function)))
+        | Some(Binding::ConstantVariable(Object::Function(_, function), _)) => {
+            evaluate_list(scope, rest).map(|args| Tail::Call(TailCall::Function { function, args }))
+        }
+        Some(Binding::MacroForm(form)) => expand_macro_form(scope, object, name, rest, &form)
+            .and_then(|x| Error::rethrow(evaluate_tail(scope, &x), name, form.location.clone())),
+        _ => return evaluate(scope, object).map(Tail::Value),
+    };
+
+    Error::rethrow(r, name, info.location.clone())
+}
+
+/// Evaluates the expressions of a body, the last one in tail position if
+/// `tail` is true.
+///
+pub(crate) fn evaluate_last(scope: &mut Scope, body: &[Object], tail: bool) -> Result<Tail, Error> {
+    let (last, init) = match body.split_last() {
+        Some(v) => v,
+        None => return Ok(Tail::Value(Object::nil())),
+    };
+
+    for expr in init {
+        evaluate(scope, expr)?;
+    }
+
+    if tail {
+        evaluate_tail(scope, last)
+    } else {
+        evaluate(scope, last).map(Tail::Value)
+    }
+}
+
+/// Makes a call, then the calls in tail position of its body, each one
+/// replacing the previous one : recursive loops run in constant stack
+/// space. `evaluate_body` enters the levels of a call, binds its arguments
+/// and evaluates its body.
+///
+/// The levels of a call are left before the next one is made, except for
+/// a dynamic form, which sees the variables of its caller : they are
+/// merged into a single level instead. If a closure captured one of them,
+/// the call is nested.
+///
+pub(crate) fn trampoline<F>(
     scope: &mut Scope,
-    name: &String,
-    args: &[Object],
-    form: CustomForm,
+    mut call: TailCall,
+    evaluate_body: &mut F,
+) -> Result<Object, Error>
+where
+    F: FnMut(&mut Scope, TailCall) -> Result<Tail, Error>,
+{
+    let depth = scope.depth();
+
+    loop {
+        let (name, location) = match &call {
+            TailCall::Dynamic { name, form, .. } => (name.clone(), form.location.clone()),
+            TailCall::Function { function, .. } => (
+                function.display_name().to_string(),
+                function.form.location.clone(),
+            ),
+        };
+
+        let next = match evaluate_body(scope, call) {
+            Ok(Tail::Call(v)) => v,
+            Ok(Tail::Value(v)) => {
+                scope.unwind(depth);
+                return Ok(v);
+            }
+            Err(v) => {
+                scope.unwind(depth);
+                return Err(v);
+            }
+        };
+
+        match &next {
+            TailCall::Function { .. } => {
+                scope.unwind(depth);
+            }
+            TailCall::Dynamic {
+                name: callee,
+                location: at,
+                ..
+            } => {
+                if !scope.collapse(depth) {
+                    let (callee, at) = (callee.clone(), at.clone());
+                    let r = trampoline(scope, next, evaluate_body);
+
+                    scope.unwind(depth);
+                    return Error::rethrow(r, &callee, at).or_else(|v| v.push_err(&name, location));
+                }
+            }
+        }
+
+        call = next;
+    }
+}
+
+/// Calls a function object with already evaluated arguments.
+///
+/// The body is evaluated in the environment captured by the function, not
+/// in the environment of the caller.
+///
+pub fn call_function(
+    scope: &mut Scope,
+    function: &Rc<Function>,
+    args: Vec<Object>,
 ) -> Result<Object, Error> {
-    if args.len() != form.arguments.len() {
+    let call = TailCall::Function {
+        function: function.clone(),
+        args,
+    };
+
+    trampoline(scope, call, &mut evaluate_call_body)
+}
+
+/// Enters the levels of a custom form call and binds its arguments, then
+/// evaluates its body, the last expression in tail position.
+///
+fn evaluate_call_body(scope: &mut Scope, call: TailCall) -> Result<Tail, Error> {
+    match call {
+        TailCall::Dynamic {
+            name, form, args, ..
+        } => {
+            scope.enter_loop_boundary(Mode::Evaluation);
+            bind_arguments(scope, &name, &form, args)?;
+
+            match evaluate_last(scope, &form.body, true) {
+                Ok(v) => Ok(v),
+                Err(v) => v.push_err(&name, form.location.clone()),
+            }
+        }
+        TailCall::Function { function, args } => {
+            let name = function.display_name();
+            let form = &function.form;
+
+            scope.enter_closure(&function.environment);
+
+            let result = bind_arguments(scope, name, form, args)
+                .and_then(|_| evaluate_last(scope, &form.body, true));
+
+            Error::rethrow(result, name, form.location.clone())
+        }
+    }
+}
+
+/// Binds the arguments of a custom form call in the innermost scope level.
+///
+/// Default expressions of optional and keyword arguments are evaluated in
+/// that level, once the previous arguments are bound.
+///
+pub(crate) fn bind_arguments(
+    scope: &mut Scope,
+    name: &str,
+    form: &CustomForm,
+    args: Vec<Object>,
+) -> Result<(), Error> {
+    check_arity(name, form, args.len())?;
+
+    let list = &form.arguments;
+    let mut args = args.into_iter();
+
+    for arg_name in &list.required {
+        let value = args.next().unwrap();
+        scope.insert(arg_name.clone(), Binding::DynamicVariable(value));
+    }
+
+    for arg in &list.optional {
+        let value = match args.next() {
+            Some(v) => v,
+            None => evaluate_default_argument(scope, arg)?,
+        };
+
+        scope.insert(arg.name.clone(), Binding::DynamicVariable(value));
+    }
+
+    let remaining: Vec<Object> = args.collect();
+
+    if let Some(arg_name) = &list.rest {
+        let value = Object::list(remaining.clone());
+        scope.insert(arg_name.clone(), Binding::DynamicVariable(value));
+    }
+
+    if list.keys.is_empty() {
+        return Ok(());
+    }
+
+    if !remaining.len().is_multiple_of(2) {
         return Error::errf(
             &format!(
-                "'{}' requires {} arguments, got {} instead.",
-                name,
-                form.arguments.len(),
-                args.len()
+                "'{}' : keyword arguments must be given as ':name value' pairs, got an odd number of arguments.\nExpected arguments : {}",
+                name, list
             ),
             name,
-            form.location,
+            form.location.clone(),
         );
     }
 
-    scope.enter_loop_boundary(Mode::Evaluation);
+    for pair in remaining.chunks(2) {
+        let known = match &pair[0] {
+            Object::Keyword(_, k) => list.keys.iter().any(|x| &x.name == k),
+            _ => false,
+        };
+
+        if !known {
+            return Error::errf(
+                &format!(
+                    "'{}' : unknown keyword argument {}.\nExpected arguments : {}",
+                    name, pair[0], list
+                ),
+                name,
+                form.location.clone(),
+            );
+        }
+    }
+
+    for arg in &list.keys {
+        let given = remaining
+            .chunks(2)
+            .find(|x| x[0].as_keyword() == Some(&arg.name));
 
-    for i in 0..args.len() {
-        let arg_name = &form.arguments[i];
-        let expr = match evaluate(scope, &args[i]) {
-            Ok(v) => v,
-            Err(v) => return v.push_err(name, form.location),
+        let value = match given {
+            Some(v) => v[1].clone(),
+            None => evaluate_default_argument(scope, arg)?,
         };
 
-        scope.insert(arg_name.clone(), Binding::DynamicVariable(expr));
+        scope.insert(arg.name.clone(), Binding::DynamicVariable(value));
     }
 
-    let mut result = Object::nil();
+    Ok(())
+}
 
-    for expr in &form.body {
-        match evaluate(scope, expr) {
-            Ok(v) => result = v,
-            Err(v) => return v.push_err(name, form.location),
+/// Checks the number of arguments given to a custom form call.
+///
+pub(crate) fn check_arity(name: &str, form: &CustomForm, count: usize) -> Result<(), Error> {
+    let list = &form.arguments;
+    let too_many = match list.max_count() {
+        Some(max) => count > max,
+        None => false,
+    };
+
+    if count < list.min_count() || too_many {
+        return Error::new_kind(
+            ErrorKind::arity(name, list.min_count(), list.max_count(), count),
+            &format!(
+                "'{}' requires {} arguments, got {} instead.\nExpected arguments : {}",
+                name,
+                list.arity_string(),
+                count,
+                list
+            ),
+        )
+        .push_err(name, form.location.clone());
+    }
+
+    Ok(())
+}
+
+/// Evaluates the default value of an optional or keyword argument.
+///
+fn evaluate_default_argument(scope: &mut Scope, arg: &OptionalArgument) -> Result<Object, Error> {
+    match &arg.default {
+        Some(v) => evaluate(scope, v),
+        None => Ok(Object::nil()),
+    }
+}
+
+pub fn evaluate_list(scope: &mut Scope, list: &[Object]) -> Result<Vec<Object>, Error> {
+    let mut result = Vec::with_capacity(list.len());
+
+    for x in list {
+        match evaluate(scope, x) {
+            Ok(v) => result.push(v),
+            Err(v) => return Err(v),
         }
     }
 
-    scope.leave();
     Ok(result)
 }
 
+fn evaluate_dynamic_form(
+    scope: &mut Scope,
+    name: &str,
+    location: &Location,
+    args: &[Object],
+    form: &Rc<CustomForm>,
+) -> Result<Object, Error> {
+    let values = match evaluate_list(scope, args) {
+        Ok(v) => v,
+        Err(v) => return v.push_err(name, form.location.clone()),
+    };
+
+    call_dynamic_form(scope, name, location, form, values)
+}
+
+/// Calls a dynamic form with already evaluated arguments.
+///
+pub fn call_dynamic_form(
+    scope: &mut Scope,
+    name: &str,
+    location: &Location,
+    form: &Rc<CustomForm>,
+    args: Vec<Object>,
+) -> Result<Object, Error> {
+    let call = TailCall::Dynamic {
+        name: name.to_string(),
+        location: location.clone(),
+        form: form.clone(),
+        args,
+    };
+
+    trampoline(scope, call, &mut evaluate_call_body)
+}
+
+/// Expands a macro form then evaluates its expansion in the scope of the
+/// caller.
+///
 fn evaluate_macro_form(
     scope: &mut Scope,
-    name: &String,
+    call: &Object,
+    name: &str,
     args: &[Object],
-    form: CustomForm,
+    form: &CustomForm,
 ) -> Result<Object, Error> {
-    if args.len() != form.arguments.len() {
-        return Error::errf(
-            &format!(
-                "'{}' requires {} arguments, got {} instead.",
-                name,
-                form.arguments.len(),
-                args.len()
-            ),
-            name,
-            form.location,
-        );
+    let location = form.location.clone();
+    let expansion = expand_macro_form(scope, call, name, args, form)?;
+
+    Error::rethrow(evaluate(scope, &expansion), name, location)
+}
+
+/// Expands the given object once if it is a macro call.
+///
+/// Returns `None` if the object isn't a macro call.
+///
+pub fn macroexpand_1(scope: &mut Scope, object: &Object) -> Result<Option<Object>, Error> {
+    if let Object::List(_, expr) = object {
+        if let Some(Object::Symbol(info, name)) = expr.first() {
+            if let Some(Binding::MacroForm(form)) = scope.get_binding(name) {
+                let r = expand_macro_form(scope, object, name, &expr[1..], &form);
+
+                return Error::rethrow(r, name, info.location.clone()).map(Some);
+            }
+        }
     }
 
+    Ok(None)
+}
+
+/// Expands the given object until it isn't a macro call anymore.
+///
+pub fn macroexpand(scope: &mut Scope, object: &Object) -> Result<Object, Error> {
+    let mut result = object.clone();
+
+    while let Some(v) = macroexpand_1(scope, &result)? {
+        result = v;
+    }
+
+    Ok(result)
+}
+
+/// Expands a macro form : its body is evaluated with the unevaluated
+/// arguments bound to its parameters.
+///
+/// Every object of the expansion which doesn't come from the arguments is
+/// tagged with the expansion location of the call.
+///
+fn expand_macro_form(
+    scope: &mut Scope,
+    call: &Object,
+    name: &str,
+    args: &[Object],
+    form: &CustomForm,
+) -> Result<Object, Error> {
     scope.enter_loop_boundary(Mode::Macro);
 
-    for i in 0..args.len() {
-        let arg_name = &form.arguments[i];
-        scope.insert(arg_name.clone(), Binding::DynamicVariable(args[i].clone()));
+    if let Err(v) = bind_arguments(scope, name, form, args.to_vec()) {
+        scope.leave();
+        return Err(v);
     }
 
     let mut result = Object::nil();
@@ -177,10 +625,82 @@ fn evaluate_macro_form(
     for expr in &form.body {
         match evaluate(scope, expr) {
             Ok(v) => result = v,
-            Err(v) => return v.push_err(name, form.location),
+            Err(v) => {
+                scope.leave();
+                return v.push_err(name, form.location.clone());
+            }
         }
     }
 
     scope.leave();
+
+    if let Some(chain) = expansion_chain(call, name) {
+        let mut origins = Vec::new();
+
+        for arg in args {
+            collect_direct_locations(arg, &mut origins);
+        }
+
+        tag_expansion(&mut result, &chain, &origins);
+    }
+
     Ok(result)
 }
+
+/// Builds the expansion location chain of a macro call : the expansion of
+/// the call itself, followed by the chain the call comes from (if it was
+/// generated by another macro).
+///
+/// Returns `None` if the call has no source location to point to.
+///
+fn expansion_chain(call: &Object, name: &str) -> Option<Vec<ExpansionLocation>> {
+    let location = &call.get_info().location;
+
+    let (localisation, parent) = match location {
+        Location::Direct(v) => (v.clone(), Vec::new()),
+        Location::Expansion(v) => (v.last()?.localisation.clone(), v.clone()),
+        _ => return None,
+    };
+
+    let mut chain = vec![ExpansionLocation {
+        macro_name: name.to_string(),
+        form: Rc::new(call.clone()),
+        localisation,
+    }];
+
+    chain.extend(parent);
+    Some(chain)
+}
+
+/// Collects the direct locations of an object and its children.
+///
+fn collect_direct_locations<'a>(object: &'a Object, result: &mut Vec<&'a DirectLocation>) {
+    if let Location::Direct(v) = &object.get_info().location {
+        result.push(v);
+    }
+
+    if let Some(list) = object.as_list() {
+        for x in list {
+            collect_direct_locations(x, result);
+        }
+    }
+}
+
+/// Tags an object and its children with an expansion location, except the
+/// objects coming from the arguments of the macro call.
+///
+fn tag_expansion(object: &mut Object, chain: &[ExpansionLocation], origins: &[&DirectLocation]) {
+    if let Location::Direct(v) = &object.get_info().location {
+        if origins.iter().any(|x| x.same_span(v)) {
+            return;
+        }
+    }
+
+    object.get_info_mut().location = Location::Expansion(chain.to_vec());
+
+    if let Some(list) = object.as_list_mut() {
+        for x in list {
+            tag_expansion(x, chain, origins);
+        }
+    }
+}
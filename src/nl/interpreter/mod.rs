@@ -1,9 +1,14 @@
 mod binding;
 mod eval;
+mod module;
 mod scope;
+mod source;
 
 pub mod builtin;
+pub mod bytecode;
 
 pub use binding::*;
 pub use eval::*;
+pub use module::*;
 pub use scope::*;
+pub use source::*;
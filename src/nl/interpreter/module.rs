@@ -0,0 +1,267 @@
+use crate::nl::core::object::*;
+
+use super::binding::*;
+use super::builtin::register_all_builtin_forms;
+use super::eval::*;
+use super::scope::*;
+use super::source::*;
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+/// Name of the global variable holding the module search path, a list of
+/// directories as Strings.
+///
+pub const LOAD_PATH_VARIABLE: &str = "*load-path*";
+
+/// Extension of the source files of modules.
+///
+pub const MODULE_EXTENSION: &str = "nl";
+
+/// Bindings exported by a module, with their unqualified names.
+///
+pub type Exports = Rc<Vec<(String, Binding)>>;
+
+/// Modules loaded by a scope and the modules it required.
+///
+#[derive(Default)]
+pub struct ModuleRegistry {
+    /// Exports of the modules already evaluated, by path.
+    loaded: HashMap<PathBuf, Exports>,
+    /// Paths of the modules being evaluated, the last one being required by
+    /// the previous ones.
+    loading: Vec<PathBuf>,
+}
+
+/// Loads a module by name, evaluating it only the first time.
+///
+/// The name `a.b` refers to the file `a/b.nl` in one of the directories of
+/// the load path.
+///
+pub fn load_module(scope: &mut Scope, name: &str) -> Result<Exports, Error> {
+    let path = find_module(scope, name)?;
+    let modules = scope.modules();
+
+    if let Some(v) = modules.borrow().loaded.get(&path) {
+        return Ok(v.clone());
+    }
+
+    if modules.borrow().loading.contains(&path) {
+        let chain: Vec<String> = modules
+            .borrow()
+            .loading
+            .iter()
+            .chain(std::iter::once(&path))
+            .map(|x| format!("'{}'", x.display()))
+            .collect();
+
+        return Error::err_kind(
+            ErrorKind::Module {
+                name: name.to_string(),
+            },
+            &format!(
+                "circular import of module '{}' : {}",
+                name,
+                chain.join(" -> ")
+            ),
+        );
+    }
+
+    let source = match fs::read_to_string(&path) {
+        Ok(v) => v,
+        Err(err) => {
+            return Error::err_kind(
+                ErrorKind::Io {
+                    path: path.display().to_string(),
+                },
+                &format!(
+                    "can't read module '{}' from '{}' : {}",
+                    name,
+                    path.display(),
+                    err
+                ),
+            )
+        }
+    };
+
+    modules.borrow_mut().loading.push(path.clone());
+    let result = evaluate_module(scope, &path, &source);
+    modules.borrow_mut().loading.pop();
+
+    let exports = Rc::new(result?);
+    modules.borrow_mut().loaded.insert(path, exports.clone());
+
+    Ok(exports)
+}
+
+/// Finds the file of a module in the directories of the load path.
+///
+/// The load path defaults to the current directory if `*load-path*` isn't
+/// bound.
+///
+fn find_module(scope: &Scope, name: &str) -> Result<PathBuf, Error> {
+    if name.split('.').any(|x| x.is_empty()) {
+        return Error::err_kind(
+            ErrorKind::Module {
+                name: name.to_string(),
+            },
+            &format!("invalid module name '{}'.", name),
+        );
+    }
+
+    let mut relative: PathBuf = name.split('.').collect();
+    relative.set_extension(MODULE_EXTENSION);
+
+    let directories = get_load_path(scope)?;
+
+    for directory in &directories {
+        let candidate = PathBuf::from(directory).join(&relative);
+
+        if candidate.is_file() {
+            return Ok(candidate.canonicalize().unwrap_or(candidate));
+        }
+    }
+
+    Error::err_kind(
+        ErrorKind::Module {
+            name: name.to_string(),
+        },
+        &format!(
+            "module '{}' not found, searched '{}' in {}",
+            name,
+            relative.display(),
+            directories
+                .iter()
+                .map(|x| format!("'{}'", x))
+                .collect::<Vec<String>>()
+                .join(", ")
+        ),
+    )
+}
+
+/// Gets the directories of the load path.
+///
+fn get_load_path(scope: &Scope) -> Result<Vec<String>, Error> {
+    let value = match scope.get_global_binding(LOAD_PATH_VARIABLE) {
+        Some(Binding::DynamicVariable(v)) | Some(Binding::ConstantVariable(v, _)) => v,
+        Some(_) => {
+            return Error::err(&format!("'{}' is not a variable.", LOAD_PATH_VARIABLE));
+        }
+        None => return Ok(vec![".".to_string()]),
+    };
+
+    if !value.is_list() && !value.is_nil() {
+        return Error::err_kind(
+            ErrorKind::wrong_type("List of Strings", &value),
+            &format!(
+                "'{}' must be a List of Strings, got a {} : {}",
+                LOAD_PATH_VARIABLE,
+                value.type_string(),
+                value
+            ),
+        );
+    }
+
+    let mut result = Vec::new();
+
+    for x in value.to_list() {
+        match x {
+            Object::String(_, v) => result.push(v),
+            _ => {
+                return Error::err_kind(
+                    ErrorKind::wrong_type("List of Strings", &x),
+                    &format!(
+                        "'{}' must be a List of Strings, got a {} : {}",
+                        LOAD_PATH_VARIABLE,
+                        x.type_string(),
+                        x
+                    ),
+                )
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Evaluates a module in its own scope, then collects its exports.
+///
+/// Without an `export` list, every global definition of the module is
+/// exported.
+///
+fn evaluate_module(
+    scope: &Scope,
+    path: &Path,
+    source: &str,
+) -> Result<Vec<(String, Binding)>, Error> {
+    let mut module = scope.new_module();
+    register_all_builtin_forms(&mut module);
+
+    if let Some(v) = scope.get_global_binding(LOAD_PATH_VARIABLE) {
+        module.insert_global(LOAD_PATH_VARIABLE.to_string(), v);
+    }
+
+    let builtins: HashSet<String> = module
+        .visible_bindings()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect();
+
+    evaluate_source(&mut module, &path.to_string_lossy(), source)?;
+
+    let names: Vec<String> = match module.exports() {
+        Some(v) => v.clone(),
+        None => module
+            .visible_bindings()
+            .into_iter()
+            .map(|(name, _)| name)
+            .filter(|x| !builtins.contains(x))
+            .collect(),
+    };
+
+    let environment = module.global_environment();
+    let mut result = Vec::with_capacity(names.len());
+
+    for name in names {
+        match module.get_global_binding(&name) {
+            Some(binding) => {
+                let binding = export_binding(&name, binding, &environment);
+                result.push((name, binding));
+            }
+            None => {
+                return Error::err_kind(
+                    ErrorKind::Module {
+                        name: path.display().to_string(),
+                    },
+                    &format!(
+                        "module '{}' exports '{}', which isn't defined.",
+                        path.display(),
+                        name
+                    ),
+                )
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+/// Prepares a binding of a module to be used from another scope.
+///
+/// Dynamic forms become functions capturing the global level of the
+/// module, so that they can call the definitions which aren't exported.
+/// Macros are exported as-is : their expansions are evaluated in the scope
+/// of the caller.
+///
+fn export_binding(name: &str, binding: Binding, environment: &Environment) -> Binding {
+    match binding {
+        Binding::DynamicForm(form) => Binding::DynamicVariable(Object::function(Function {
+            name: Some(name.to_string()),
+            form,
+            environment: vec![environment.clone()],
+        })),
+        v => v,
+    }
+}
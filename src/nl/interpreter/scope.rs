@@ -1,7 +1,13 @@
 use super::binding::*;
+use super::module::ModuleRegistry;
 
-use std::collections::HashMap;
-use std::mem::replace;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Identifier of the next scope created.
+static NEXT_SCOPE: AtomicU64 = AtomicU64::new(0);
 
 pub enum Mode {
     Evaluation,
@@ -9,8 +15,40 @@ pub enum Mode {
     Inherit,
 }
 
+/// Bindings of a scope level, shared between the scope and the closures
+/// capturing it.
+///
+pub type Environment = Rc<RefCell<HashMap<String, Binding>>>;
+
+/// Generation of the watched bindings of a scope. A call resolved to a
+/// watched name resolves to the same binding while the generation is
+/// unchanged.
+///
+/// The compiled forms are shared by the scopes of the modules, so the
+/// generation identifies its scope too.
+///
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Generation {
+    scope: u64,
+    count: u64,
+}
+
 pub struct Scope {
     levels: Vec<ScopeLevel>,
+    /// Modules loaded by this scope, shared with the scopes of the modules
+    /// it requires.
+    modules: Rc<RefCell<ModuleRegistry>>,
+    /// Scope of a module : its global level is captured by closures.
+    is_module: bool,
+    /// Names given to `export`, or `None` if it was never called.
+    exports: Option<Vec<String>>,
+    /// Identifier of the scope, unique to the process.
+    id: u64,
+    /// Number of changes of the visible bindings of the watched names.
+    generation: u64,
+    /// Names whose resolution is cached by a call site. Dropped with the
+    /// scope, they are bounded by the operators of its sources.
+    watched: HashSet<String>,
 }
 
 struct LoopInfo {
@@ -19,57 +57,300 @@ struct LoopInfo {
 }
 
 struct ScopeLevel {
-    pub bindings: HashMap<String, Binding>,
+    pub bindings: Environment,
     pub loop_info: Option<LoopInfo>,
     pub mode: Mode,
+    /// First level of a closure call. The levels below it (except the
+    /// global one) are not visible from the closure.
+    pub closure_base: bool,
 }
 
 impl Scope {
     pub fn new() -> Scope {
         Scope {
             levels: vec![ScopeLevel::new(Mode::Evaluation)],
+            modules: Rc::new(RefCell::new(ModuleRegistry::default())),
+            is_module: false,
+            exports: None,
+            id: NEXT_SCOPE.fetch_add(1, Ordering::Relaxed),
+            generation: 0,
+            watched: HashSet::new(),
+        }
+    }
+
+    /// Creates the empty scope of a module required from this scope.
+    ///
+    pub fn new_module(&self) -> Scope {
+        Scope {
+            levels: vec![ScopeLevel::new(Mode::Evaluation)],
+            modules: self.modules.clone(),
+            is_module: true,
+            exports: None,
+            id: NEXT_SCOPE.fetch_add(1, Ordering::Relaxed),
+            generation: 0,
+            watched: HashSet::new(),
         }
     }
 
+    /// Gets the registry of the loaded modules.
+    ///
+    pub fn modules(&self) -> Rc<RefCell<ModuleRegistry>> {
+        self.modules.clone()
+    }
+
+    /// Gets the names exported by the module evaluated in this scope.
+    ///
+    pub fn exports(&self) -> Option<&Vec<String>> {
+        self.exports.as_ref()
+    }
+
+    /// Adds names to the export list of the module evaluated in this scope.
+    ///
+    pub fn export(&mut self, names: Vec<String>) -> &mut Self {
+        self.exports.get_or_insert_with(Vec::new).extend(names);
+        self
+    }
+
+    /// Gets the bindings of the outermost (global) level.
+    ///
+    pub fn global_environment(&self) -> Environment {
+        self.levels[0].bindings.clone()
+    }
+
     pub fn has_level(&self) -> bool {
         !self.levels.is_empty()
     }
 
-    pub fn has_binding(&self, name: &String) -> bool {
-        for level in self.levels.iter().rev() {
-            if level.bindings.contains_key(name) {
-                return true;
+    /// Gets the number of levels in the scope.
+    ///
+    pub fn depth(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Gets the generation of the watched bindings.
+    ///
+    /// The generation only changes when a watched name is bound, or when
+    /// one of its bindings is removed, hidden or revealed : the variables
+    /// are rebound on every call and every loop iteration.
+    ///
+    pub fn generation(&self) -> Generation {
+        Generation {
+            scope: self.id,
+            count: self.generation,
+        }
+    }
+
+    /// Watches a name, cached as the operator of a call.
+    ///
+    pub fn watch(&mut self, name: &str) {
+        if !self.watched.contains(name) {
+            self.watched.insert(name.to_string());
+        }
+    }
+
+    /// Checks if one of the given levels binds a watched name.
+    ///
+    fn binds_watched(&self, levels: &[ScopeLevel]) -> bool {
+        levels.iter().any(|x| self.holds_watched(&x.bindings))
+    }
+
+    /// Checks if an environment binds a watched name.
+    ///
+    fn holds_watched(&self, environment: &Environment) -> bool {
+        let bindings = environment.borrow();
+
+        if bindings.len() < self.watched.len() {
+            bindings.keys().any(|x| self.watched.contains(x))
+        } else {
+            self.watched.iter().any(|x| bindings.contains_key(x))
+        }
+    }
+
+    /// Gets the index of the outermost level visible from the innermost
+    /// one, excluding the global level.
+    ///
+    fn frame_start(&self) -> usize {
+        (1..self.levels.len())
+            .rev()
+            .find(|&i| self.levels[i].closure_base)
+            .unwrap_or(1)
+    }
+
+    /// Removes the levels above the given depth. Used to restore the scope
+    /// after an error interrupted the evaluation.
+    ///
+    pub fn unwind(&mut self, depth: usize) -> &mut Self {
+        let depth = depth.max(1);
+
+        if self.levels.len() <= depth {
+            return self;
+        }
+
+        let removed: Vec<_> = self.levels.drain(depth..).collect();
+        let mut changed = self.binds_watched(&removed);
+
+        // The levels hidden by a removed closure call are visible again.
+        if removed.iter().any(|x| x.closure_base) {
+            changed |= self.binds_watched(&self.levels[self.frame_start()..]);
+        }
+
+        if changed {
+            self.generation += 1;
+        }
+
+        self
+    }
+
+    /// Merges the levels above the given depth into a single loop boundary
+    /// level, the innermost bindings replacing the outermost ones. Used when
+    /// a call in tail position replaces the call owning these levels.
+    ///
+    /// Returns `false`, leaving the scope unchanged, if one of these levels
+    /// is captured by a closure : its bindings can't be moved.
+    ///
+    pub fn collapse(&mut self, depth: usize) -> bool {
+        let depth = depth.max(1);
+
+        if self.levels.len() <= depth {
+            return true;
+        }
+
+        if self.levels[depth..]
+            .iter()
+            .any(|x| Rc::strong_count(&x.bindings) > 1)
+        {
+            return false;
+        }
+
+        // A closure base in the merged levels no longer hides the merged
+        // levels below it.
+        let base = (depth + 1..self.levels.len())
+            .rev()
+            .find(|&i| self.levels[i].closure_base);
+
+        if let Some(base) = base {
+            if self.binds_watched(&self.levels[depth..base]) {
+                self.generation += 1;
             }
         }
 
-        return false;
+        let mut result = ScopeLevel::new_loop_boundary(Mode::Evaluation);
+
+        for level in self.levels.drain(depth..) {
+            result.closure_base |= level.closure_base;
+            result.bindings.borrow_mut().extend(level.bindings.take());
+        }
+
+        self.levels.push(result);
+        true
     }
 
-    pub fn get_binding(&self, name: &String) -> Option<&Binding> {
-        for level in self.levels.iter().rev() {
-            if level.bindings.contains_key(name) {
-                return level.bindings.get(name);
+    /// Gets the index of the innermost visible level defining the given
+    /// name.
+    ///
+    fn find_level(&self, name: &str) -> Option<usize> {
+        let mut i = self.levels.len();
+
+        while i > 0 {
+            i -= 1;
+
+            let level = &self.levels[i];
+
+            if level.bindings.borrow().contains_key(name) {
+                return Some(i);
+            }
+
+            if level.closure_base && i > 0 {
+                i = 1;
             }
         }
 
         None
     }
 
-    pub fn get_binding_mut(&mut self, name: &String) -> Option<&mut Binding> {
-        for level in self.levels.iter_mut().rev() {
-            if level.bindings.contains_key(name) {
-                return level.bindings.get_mut(name);
+    pub fn has_binding(&self, name: &str) -> bool {
+        self.find_level(name).is_some()
+    }
+
+    pub fn get_binding(&self, name: &str) -> Option<Binding> {
+        self.find_level(name)
+            .and_then(|i| self.levels[i].bindings.borrow().get(name).cloned())
+    }
+
+    /// Gets the bindings visible from the innermost level, sorted by name.
+    /// A shadowed binding is replaced by the innermost one.
+    ///
+    pub fn visible_bindings(&self) -> Vec<(String, Binding)> {
+        let mut result: HashMap<String, Binding> = HashMap::new();
+        let mut i = self.levels.len();
+
+        while i > 0 {
+            i -= 1;
+
+            let level = &self.levels[i];
+
+            for (name, binding) in level.bindings.borrow().iter() {
+                result
+                    .entry(name.clone())
+                    .or_insert_with(|| binding.clone());
+            }
+
+            if level.closure_base && i > 0 {
+                i = 1;
             }
         }
 
-        None
+        let mut result: Vec<_> = result.into_iter().collect();
+        result.sort_by(|a, b| a.0.cmp(&b.0));
+        result
+    }
+
+    /// Gets the environments visible from the innermost level, from the
+    /// outermost to the innermost, excluding the global level.
+    ///
+    /// In a module, the global level is captured too outside of a closure
+    /// call, so that the module definitions stay visible once the closure
+    /// is exported.
+    ///
+    pub fn capture(&self) -> Vec<Environment> {
+        let mut result = Vec::new();
+        let mut in_closure = false;
+
+        for level in self.levels.iter().skip(1).rev() {
+            result.push(level.bindings.clone());
+
+            if level.closure_base {
+                in_closure = true;
+                break;
+            }
+        }
+
+        if self.is_module && !in_closure {
+            result.push(self.global_environment());
+        }
+
+        result.reverse();
+        result
+    }
+
+    /// Gets the evaluation mode of the innermost level, resolving
+    /// `Mode::Inherit` to the mode of the enclosing levels.
+    ///
+    pub fn mode(&self) -> &Mode {
+        for level in self.levels.iter().rev() {
+            match level.mode {
+                Mode::Inherit => {}
+                _ => return &level.mode,
+            }
+        }
+
+        &Mode::Evaluation
     }
 
     pub fn is_in_loop(&self) -> bool {
         for level in self.levels.iter().rev() {
-            match &level.loop_info {
-                Some(v) => return !v.boundary,
-                None => {}
+            if let Some(v) = &level.loop_info {
+                return !v.boundary;
             }
         }
 
@@ -78,9 +359,8 @@ impl Scope {
 
     pub fn is_loop_broken(&self) -> bool {
         for level in self.levels.iter().rev() {
-            match &level.loop_info {
-                Some(v) => return !v.boundary && v.break_called,
-                None => {}
+            if let Some(v) = &level.loop_info {
+                return !v.boundary && v.break_called;
             }
         }
 
@@ -89,14 +369,11 @@ impl Scope {
 
     pub fn break_loop(&mut self) {
         for level in self.levels.iter_mut().rev() {
-            match &mut level.loop_info {
-                Some(v) => {
-                    if !v.boundary {
-                        v.break_called = true;
-                    }
-                    return;
+            if let Some(v) = &mut level.loop_info {
+                if !v.boundary {
+                    v.break_called = true;
                 }
-                _ => {}
+                return;
             }
         }
     }
@@ -116,27 +393,111 @@ impl Scope {
         self
     }
 
+    /// Enters a closure call : the captured environments are restored on
+    /// top of the stack, followed by a new level for the arguments. Only
+    /// these levels and the global one are visible until `leave_closure`.
+    ///
+    pub fn enter_closure(&mut self, environment: &[Environment]) -> &mut Self {
+        // The levels of the caller are hidden, the captured ones visible.
+        if self.binds_watched(&self.levels[self.frame_start()..])
+            || environment.iter().any(|x| self.holds_watched(x))
+        {
+            self.generation += 1;
+        }
+
+        for env in environment {
+            self.levels.push(ScopeLevel::new_captured(env.clone()));
+        }
+
+        self.levels
+            .push(ScopeLevel::new_loop_boundary(Mode::Evaluation));
+
+        let base = self.levels.len() - environment.len() - 1;
+        self.levels[base].closure_base = true;
+
+        self
+    }
+
     pub fn leave(&mut self) -> &mut Self {
-        self.levels.pop();
+        if let Some(level) = self.levels.pop() {
+            if self.holds_watched(&level.bindings) {
+                self.generation += 1;
+            }
+        }
+
         self
     }
 
-    pub fn insert(&mut self, name: String, binding: Binding) -> &mut Self {
-        match self.levels.last_mut() {
-            Some(level) => {
-                level.bindings.insert(name, binding);
+    /// Leaves a closure call, removing every level above and including
+    /// the closure base level.
+    ///
+    pub fn leave_closure(&mut self) -> &mut Self {
+        let mut changed = false;
+
+        while let Some(level) = self.levels.pop() {
+            changed |= self.holds_watched(&level.bindings);
+
+            if level.closure_base {
+                break;
             }
-            None => panic!("Empty scope stack."),
+        }
+
+        // The levels of the caller are visible again.
+        if changed || self.binds_watched(&self.levels[self.frame_start()..]) {
+            self.generation += 1;
         }
 
         self
     }
 
+    /// Inserts a binding in a level, changing the generation if its name is
+    /// watched.
+    ///
+    fn insert_at(&mut self, index: usize, name: String, binding: Binding) {
+        if self.watched.contains(&name) {
+            self.generation += 1;
+        }
+
+        self.levels[index]
+            .bindings
+            .borrow_mut()
+            .insert(name, binding);
+    }
+
+    pub fn insert(&mut self, name: String, binding: Binding) -> &mut Self {
+        match self.levels.len() {
+            0 => panic!("Empty scope stack."),
+            len => self.insert_at(len - 1, name, binding),
+        }
+
+        self
+    }
+
+    /// Inserts a binding in the outermost (global) level.
+    ///
+    pub fn insert_global(&mut self, name: String, binding: Binding) -> &mut Self {
+        match self.levels.len() {
+            0 => panic!("Empty scope stack."),
+            _ => self.insert_at(0, name, binding),
+        }
+
+        self
+    }
+
+    /// Gets a binding from the outermost (global) level only.
+    ///
+    pub fn get_global_binding(&self, name: &str) -> Option<Binding> {
+        self.levels
+            .first()
+            .and_then(|x| x.bindings.borrow().get(name).cloned())
+    }
+
     pub fn set(&mut self, name: String, binding: Binding) -> &mut Self {
-        if self.has_binding(&name) {
-            replace(self.get_binding_mut(&name).unwrap(), binding);
-        } else {
-            self.insert(name, binding);
+        match self.find_level(&name) {
+            Some(i) => self.insert_at(i, name, binding),
+            None => {
+                self.insert(name, binding);
+            }
         }
 
         self
@@ -151,34 +512,52 @@ impl Scope {
     }
 }
 
+impl Default for Scope {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ScopeLevel {
     fn new(mode: Mode) -> ScopeLevel {
         ScopeLevel {
-            bindings: HashMap::new(),
+            bindings: Environment::default(),
             loop_info: None,
             mode,
+            closure_base: false,
         }
     }
 
     fn new_loop(mode: Mode) -> ScopeLevel {
         ScopeLevel {
-            bindings: HashMap::new(),
+            bindings: Environment::default(),
             loop_info: Some(LoopInfo {
                 break_called: false,
                 boundary: false,
             }),
             mode,
+            closure_base: false,
         }
     }
 
     fn new_loop_boundary(mode: Mode) -> ScopeLevel {
         ScopeLevel {
-            bindings: HashMap::new(),
+            bindings: Environment::default(),
             loop_info: Some(LoopInfo {
                 break_called: false,
                 boundary: true,
             }),
             mode,
+            closure_base: false,
+        }
+    }
+
+    fn new_captured(bindings: Environment) -> ScopeLevel {
+        ScopeLevel {
+            bindings,
+            loop_info: None,
+            mode: Mode::Evaluation,
+            closure_base: false,
         }
     }
 }
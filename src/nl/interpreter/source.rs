@@ -0,0 +1,62 @@
+use crate::nl::core::object::*;
+use crate::nl::reader::*;
+
+use super::eval::*;
+use super::scope::*;
+
+/// Reads every expression of a source file, completing their locations
+/// with the given file name.
+///
+/// A syntax error is converted into an evaluation error pointing to the
+/// character the reader failed on, with the set of expected tokens.
+///
+pub fn read_source(file: &str, source: &str) -> Result<Vec<Object>, Error> {
+    match nl_parser::exprs(source) {
+        Ok(mut objects) => {
+            for object in &mut objects {
+                object.complete_location(file, source);
+            }
+
+            Ok(objects)
+        }
+        Err(err) => {
+            let offset = err.location.offset;
+            let next = source[offset..].chars().next();
+            let found = match next {
+                Some(c) => format!("{:?}", c),
+                None => "the end of the input".to_string(),
+            };
+
+            let end = offset + next.map_or(0, |x| x.len_utf8());
+            let mut location = DirectLocation::new_light(offset, end);
+            location.complete(file, source);
+
+            let mut expected: Vec<String> = err.expected.tokens().map(String::from).collect();
+            expected.sort();
+
+            Error::new_kind(
+                ErrorKind::Syntax {
+                    expected,
+                    found: next,
+                },
+                &format!("syntax error, expected {}, found {}", err.expected, found),
+            )
+            .push_err("reader", Location::Direct(location))
+        }
+    }
+}
+
+/// Reads then evaluates every expression of a source file, in order.
+///
+/// Returns the value of the last expression, or `nil` if the source is
+/// empty.
+///
+pub fn evaluate_source(scope: &mut Scope, file: &str, source: &str) -> Result<Object, Error> {
+    let mut result = Object::nil();
+
+    for object in read_source(file, source)? {
+        result = evaluate(scope, &object)?;
+    }
+
+    Ok(result)
+}
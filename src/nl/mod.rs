@@ -1,4 +1,5 @@
 #[macro_use]
 pub mod core;
+pub mod formatter;
 pub mod interpreter;
 pub mod reader;
@@ -1,16 +1,25 @@
 use crate::nl::core::object::*;
 
+use indexmap::IndexMap;
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_traits::Zero;
+
 peg::parser! { pub grammar nl_parser() for str {
 
     pub rule exprs() -> Vec<Object>
-        = expr()+
+        = _ l:(expr()*) _ { l }
 
     pub rule expr() -> Object
         = quote_macro()
         / equote_macro()
         / escape_macro()
         / list()
+        / vector()
+        / map()
+        / set()
         / float()
+        / rational()
         / integer()
         / character()
         / string()
@@ -33,18 +42,32 @@ peg::parser! { pub grammar nl_parser() for str {
     rule list() -> Object
         = _ from:position!() "(" _ l:(expr()*) _ ")" to:position!() _
             { make_list(from, to, l) }
+    rule vector() -> Object
+        = _ from:position!() "[" _ l:(expr()*) _ "]" to:position!() _
+            { make_vector(from, to, l) }
+    rule map() -> Object
+        = _ from:position!() "{" _ l:(expr()*) _ "}" to:position!() _
+            {? make_map(from, to, l) }
+    rule set() -> Object
+        = _ from:position!() "#{" _ l:(expr()*) _ "}" to:position!() _
+            { make_set(from, to, l) }
     rule integer() -> Object
-        = _ from:position!() "0x" v:$(hexadecimal_digit()+) to:position!() _
-            { make_hexa(from, to, v) }
-        / _ from:position!() "0o" v:$(octal_digit()+) to:position!() _
-            { make_octal(from, to, v) }
-        / _ from:position!() "0b" v:$(binary_digit()+) to:position!() _
-            { make_bin(from, to, v) }
-        / _ from:position!() v:$(decimal_digit()+) to:position!() _
-            { make_dec(from, to, v) }
+        = _ from:position!() s:$(sign()?) quiet!{"0x"} v:$(hexadecimal_digit()+) !name_char() to:position!() _
+            {? make_integer(from, to, s, v, 16) }
+        / _ from:position!() s:$(sign()?) quiet!{"0o"} v:$(octal_digit()+) !name_char() to:position!() _
+            {? make_integer(from, to, s, v, 8) }
+        / _ from:position!() s:$(sign()?) quiet!{"0b"} v:$(binary_digit()+) !name_char() to:position!() _
+            {? make_integer(from, to, s, v, 2) }
+        / _ from:position!() s:$(sign()?) v:$(decimal_digits()) !name_char() to:position!() _
+            {? make_integer(from, to, s, v, 10) }
+    rule rational() -> Object
+        = _ from:position!() s:$(sign()?) n:$(decimal_digits()) "/" d:$(decimal_digits()) !name_char() to:position!() _
+            {? make_rational(from, to, s, n, d) }
     rule float() -> Object
-        = _ from:position!() v:$(decimal_digit()+ "." decimal_digit()+) to:position!() _
-            { make_float(from, to, v) }
+        = _ from:position!() v:$(sign()? float_digits()) !name_char() to:position!() _
+            {? make_float(from, to, v) }
+        / _ from:position!() v:$(sign()? quiet!{"inf" / "nan"}) !name_char() to:position!() _
+            { make_special_float(from, to, v) }
     rule character() -> Object
         = _ from:position!() "#" c:char_name() to:position!() _
             { make_char(from, to, c) }
@@ -57,18 +80,18 @@ peg::parser! { pub grammar nl_parser() for str {
         = _ from:position!() "r\"" v:(raw_string_char()*) "\"" to:position!() _
             { make_string(from, to, v) }
     rule nil() -> Object
-        = _ from:position!() "nil" to:position!() _
+        = _ from:position!() quiet!{"nil"} to:position!() _
             { make_nil(from, to) }
     rule boolean() -> Object
-        = _ from:position!() "true" to:position!() _
+        = _ from:position!() quiet!{"true"} to:position!() _
             { make_bool(from, to, true) }
-        / _ from:position!() "false" to:position!() _
+        / _ from:position!() quiet!{"false"} to:position!() _
             { make_bool(from, to, false) }
     rule keyword() -> Object
         = _ from:position!() ":" v:$(name()) to:position!() _
             { make_keyword(from, to, v) }
     rule symbol() -> Object
-        = _ from:position!() v:$(name()) to:position!() _
+        = _ from:position!() !(number_token() !name_char()) v:$(name()) to:position!() _
             { make_symbol(from, to, v) }
 
 
@@ -80,6 +103,22 @@ peg::parser! { pub grammar nl_parser() for str {
         = "0" / "1" / "_"
     rule decimal_digit()
         = ['0'..='9'] / "_"
+    rule decimal_digits()
+        = quiet!{ ['0'..='9'] decimal_digit()* } / expected!("a number")
+    rule sign()
+        = quiet!{ "+" / "-" }
+    rule float_digits()
+        = quiet!{
+            decimal_digits() "." decimal_digit()+ exponent()?
+            / "." ['0'..='9'] decimal_digit()* exponent()?
+            / decimal_digits() exponent()
+        }
+    rule number_token()
+        = sign()? ("0x" / "0o" / "0b") name_char()*
+        / sign()? float_digits()
+        / sign()? decimal_digits() ("/" decimal_digits())?
+    rule exponent()
+        = quiet!{ ("e" / "E") sign()? decimal_digits() }
 
     rule escaped_char() -> char
         = "\\n"                                         { '\n' }
@@ -93,30 +132,23 @@ peg::parser! { pub grammar nl_parser() for str {
         / "tab"                                         { '\t' }
         / "carriage-return"                             { '\r' }
     rule string_char() -> char
-        = !"\"" c:escaped_char()                        { c }
+        = quiet!{ !"\"" c:escaped_char() { c } }
     rule raw_string_char() -> char
-        = "\"\""                                        { '"' }
-        / !"\"" c:$([_])                                { c.chars().next().unwrap() }
+        = quiet!{ "\"\"" { '"' } / !"\"" c:$([_]) { c.chars().next().unwrap() } }
 
     rule name()
-        = name_char()+
+        = quiet!{ name_char()+ } / expected!("a symbol")
     rule name_char()
-        = ['a'..='z']
-        / ['A'..='Z']
-        / ['0'..='9']
-        / "+" / "-" / "*" / "/" / "%" / "^" / "~"
-        / ">" / "<" / "="
-        / "?" / "." / ":" / "!"
-        / "_"
+        = [c if is_name_char(c)]
 
 
     rule _()
-        = (spacing() / comment())*
+        = quiet!{ (spacing() / comment())* }
 
     rule spacing()
         = " " / "\t" / "\r" / "\n"
     rule comment()
-        = ";" (!eol() [_])* eol()
+        = ";" (!eol() [_])* (eol() / eof())
 
     rule eol()
         = "\r\n"
@@ -128,6 +160,12 @@ peg::parser! { pub grammar nl_parser() for str {
 
 }}
 
+/// Checks if a character can be part of a symbol, a keyword or a number.
+///
+pub fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "+-*/%^~><=?.:!&_".contains(c)
+}
+
 fn make_quote_macro(f: usize, t: usize, e: Object) -> Object {
     Object::List(
         ObjectInfo::new(Location::new_direct(f, t)),
@@ -174,39 +212,108 @@ fn make_list(s: usize, e: usize, l: Vec<Object>) -> Object {
     )
 }
 
-fn make_hexa(f: usize, t: usize, v: &str) -> Object {
-    Object::Integer(
-        ObjectInfo::new(Location::new_direct(f, t)),
-        i32::from_str_radix(&v.replace("_", ""), 16).unwrap(),
+fn make_vector(s: usize, e: usize, l: Vec<Object>) -> Object {
+    Object::Vector(
+        ObjectInfo::new(Location::Direct(DirectLocation::new_light(s, e))),
+        l,
     )
 }
 
-fn make_octal(f: usize, t: usize, v: &str) -> Object {
-    Object::Integer(
-        ObjectInfo::new(Location::new_direct(f, t)),
-        i32::from_str_radix(&v.replace("_", ""), 8).unwrap(),
-    )
+fn make_map(s: usize, e: usize, l: Vec<Object>) -> Result<Object, &'static str> {
+    if !l.len().is_multiple_of(2) {
+        return Err("an even number of forms in a map");
+    }
+
+    let mut map = IndexMap::with_capacity(l.len() / 2);
+    let mut iter = l.into_iter();
+
+    while let (Some(key), Some(value)) = (iter.next(), iter.next()) {
+        map.insert(key, value);
+    }
+
+    Ok(Object::Map(
+        ObjectInfo::new(Location::Direct(DirectLocation::new_light(s, e))),
+        map,
+    ))
 }
 
-fn make_bin(f: usize, t: usize, v: &str) -> Object {
-    Object::Integer(
-        ObjectInfo::new(Location::new_direct(f, t)),
-        i32::from_str_radix(&v.replace("_", ""), 2).unwrap(),
+fn make_set(s: usize, e: usize, l: Vec<Object>) -> Object {
+    Object::Set(
+        ObjectInfo::new(Location::Direct(DirectLocation::new_light(s, e))),
+        l.into_iter().collect(),
     )
 }
 
-fn make_dec(f: usize, t: usize, v: &str) -> Object {
-    Object::Integer(
-        ObjectInfo::new(Location::new_direct(f, t)),
-        i32::from_str_radix(&v.replace("_", ""), 10).unwrap(),
-    )
+/// Makes an Integer, or a Bignum if the literal doesn't fit in 64 bits.
+///
+fn make_integer(
+    f: usize,
+    t: usize,
+    sign: &str,
+    v: &str,
+    radix: u32,
+) -> Result<Object, &'static str> {
+    let value = match parse_digits(sign, v, radix) {
+        Some(v) => v,
+        None => return Err("at least one digit in an integer literal"),
+    };
+
+    let mut object = Object::bignum(value);
+    *object.get_info_mut() = ObjectInfo::new(Location::new_direct(f, t));
+
+    Ok(object)
+}
+
+/// Makes a Rational, or an Integer if the denominator divides the
+/// numerator.
+///
+fn make_rational(f: usize, t: usize, sign: &str, n: &str, d: &str) -> Result<Object, &'static str> {
+    let numerator = parse_digits(sign, n, 10).unwrap();
+    let denominator = parse_digits("", d, 10).unwrap();
+
+    if denominator.is_zero() {
+        return Err("a non-zero denominator in a rational literal");
+    }
+
+    let mut object = Object::rational(BigRational::new(numerator, denominator));
+    *object.get_info_mut() = ObjectInfo::new(Location::new_direct(f, t));
+
+    Ok(object)
 }
 
-fn make_float(f: usize, t: usize, v: &str) -> Object {
-    Object::Float(
+/// Parses the digits of an integer literal, ignoring the `_` separators.
+///
+fn parse_digits(sign: &str, v: &str, radix: u32) -> Option<BigInt> {
+    let digits = v.replace("_", "");
+    let value = BigInt::parse_bytes(digits.as_bytes(), radix)?;
+
+    if sign == "-" {
+        Some(-value)
+    } else {
+        Some(value)
+    }
+}
+
+fn make_float(f: usize, t: usize, v: &str) -> Result<Object, &'static str> {
+    let value = match v.replace("_", "").parse::<f64>() {
+        Ok(v) if v.is_finite() => v,
+        _ => return Err("a float literal within the range of a Float"),
+    };
+
+    Ok(Object::Float(
         ObjectInfo::new(Location::new_direct(f, t)),
-        v.replace("_", "").parse::<f32>().unwrap(),
-    )
+        value,
+    ))
+}
+
+fn make_special_float(f: usize, t: usize, v: &str) -> Object {
+    let value = match v {
+        "inf" | "+inf" => f64::INFINITY,
+        "-inf" => f64::NEG_INFINITY,
+        _ => f64::NAN,
+    };
+
+    Object::Float(ObjectInfo::new(Location::new_direct(f, t)), value)
 }
 
 fn make_char(f: usize, t: usize, v: char) -> Object {
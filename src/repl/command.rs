@@ -0,0 +1,192 @@
+use neolisp::nl::interpreter::*;
+
+use super::Session;
+
+use std::fs;
+use std::time::Instant;
+
+const HELP: &str = "Commands :
+  :env             Lists the bindings of the scope.
+  :describe <sym>  Describes the binding of a symbol.
+  :load <file>     Evaluates a source file in the current scope.
+  :reset           Restores the initial scope.
+  :time <expr>     Evaluates expressions and prints the elapsed time.
+  :ast <expr>      Dumps the objects read from expressions.
+  :help            Prints this message.
+  :quit            Exits the REPL.";
+
+/// Outcome of a meta-command.
+///
+pub enum Command {
+    /// Continues reading inputs.
+    Continue,
+    /// Exits the REPL.
+    Quit,
+}
+
+/// Checks if a line of the REPL is a meta-command (`:name args...`).
+///
+/// A keyword alone (`:foo`) is evaluated as an expression, only the known
+/// command names are meta-commands.
+///
+pub fn is_command(line: &str) -> bool {
+    let name = line.split_whitespace().next().unwrap_or("");
+
+    matches!(
+        name,
+        ":env" | ":describe" | ":load" | ":reset" | ":time" | ":ast" | ":help" | ":quit"
+    )
+}
+
+/// Executes a meta-command. `args` are the command line arguments of the
+/// REPL, bound again to `*args*` on `:reset`.
+///
+pub fn execute_command(session: &mut Session, args: &[String], line: &str) -> Command {
+    let line = line.trim();
+    let (name, rest) = match line.find(char::is_whitespace) {
+        Some(i) => (&line[..i], line[i..].trim()),
+        None => (line, ""),
+    };
+
+    match name {
+        ":env" => env(&session.scope),
+        ":describe" => describe(&session.scope, rest),
+        ":load" => load(session, rest),
+        ":reset" => {
+            session.scope = super::super::create_scope(args);
+            println!("Scope reset.");
+        }
+        ":time" => time(session, rest),
+        ":ast" => ast(session, rest),
+        ":help" => println!("{}", HELP),
+        ":quit" => return Command::Quit,
+        _ => unreachable!("'{}' isn't a REPL command.", name),
+    }
+
+    Command::Continue
+}
+
+/// `:env`
+///
+fn env(scope: &Scope) {
+    let bindings = scope.visible_bindings();
+    let width = bindings
+        .iter()
+        .map(|(name, _)| name.len())
+        .max()
+        .unwrap_or(0);
+
+    for (name, binding) in bindings {
+        println!("{:width$}  {}", name, binding.kind_string(), width = width);
+    }
+}
+
+/// `:describe <sym>`
+///
+fn describe(scope: &Scope, name: &str) {
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        println!("Usage : :describe <sym>");
+        return;
+    }
+
+    let binding = match scope.get_binding(name) {
+        Some(v) => v,
+        None => {
+            println!("'{}' isn't bound.", name);
+            return;
+        }
+    };
+
+    println!("'{}' : {}", name, binding.kind_string());
+
+    match binding {
+        Binding::DynamicVariable(v) => match v.as_function() {
+            Some(function) => describe_form(&function.form),
+            None => println!("Value : {}", v),
+        },
+        Binding::ConstantVariable(v, location) => {
+            println!("Value : {}", v);
+            println!("Defined {}", location);
+        }
+        Binding::SpecialForm(_) | Binding::EvalForm(_) => println!("Builtin form."),
+        Binding::DynamicForm(form) | Binding::MacroForm(form) => describe_form(&form),
+    }
+}
+
+/// Prints the arguments, the body and the location of a custom form.
+///
+fn describe_form(form: &CustomForm) {
+    println!("Arguments : {}", form.arguments);
+    println!("Body :");
+
+    for x in &form.body {
+        println!("  {}", x);
+    }
+
+    println!("Defined {}", form.location);
+}
+
+/// `:load <file>`
+///
+fn load(session: &mut Session, file: &str) {
+    if file.is_empty() {
+        println!("Usage : :load <file>");
+        return;
+    }
+
+    let source = match fs::read_to_string(file) {
+        Ok(v) => v,
+        Err(err) => {
+            println!("Can't read '{}' : {}", file, err);
+            return;
+        }
+    };
+
+    session.sources.insert(file, &source);
+
+    let depth = session.scope.depth();
+
+    match evaluate_source(&mut session.scope, file, &source) {
+        Ok(result) => println!("=> {}", result),
+        Err(err) => {
+            session.scope.unwind(depth);
+            session.report(&err);
+        }
+    }
+}
+
+/// `:time <expr>`
+///
+fn time(session: &mut Session, source: &str) {
+    let file = session.add_input(source);
+
+    let depth = session.scope.depth();
+    let start = Instant::now();
+    let result = evaluate_source(&mut session.scope, &file, source);
+    let elapsed = start.elapsed();
+
+    match result {
+        Ok(v) => println!("=> {}", v),
+        Err(err) => {
+            session.scope.unwind(depth);
+            session.report(&err);
+        }
+    }
+
+    println!("Elapsed : {:?}", elapsed);
+}
+
+/// `:ast <expr>`
+///
+fn ast(session: &mut Session, source: &str) {
+    let file = session.add_input(source);
+
+    match read_source(&file, source) {
+        Ok(objects) => {
+            for object in objects {
+                object.dump();
+            }
+        }
+        Err(err) => session.report(&err),
+    }
+}
@@ -0,0 +1,156 @@
+use neolisp::nl::reader::is_name_char;
+
+use rustyline::completion::Completer;
+use rustyline::highlight::{Highlighter, MatchingBracketHighlighter};
+use rustyline::hint::Hinter;
+use rustyline::validate::{ValidationContext, ValidationResult, Validator};
+use rustyline::{Context, Helper};
+
+use std::borrow::Cow;
+
+/// Line editor helper of the REPL.
+///
+/// Continues the input on the next line while brackets are unbalanced,
+/// completes the names bound in the scope and highlights the bracket
+/// matching the one under the cursor.
+///
+pub struct ReplHelper {
+    names: Vec<String>,
+    brackets: MatchingBracketHighlighter,
+}
+
+impl ReplHelper {
+    pub fn new() -> ReplHelper {
+        ReplHelper {
+            names: Vec::new(),
+            brackets: MatchingBracketHighlighter::new(),
+        }
+    }
+
+    /// Sets the names proposed by the completion. Called after each
+    /// evaluation, as it may have changed the bindings of the scope.
+    ///
+    pub fn set_names(&mut self, names: Vec<String>) {
+        self.names = names;
+    }
+}
+
+impl Helper for ReplHelper {}
+
+impl Completer for ReplHelper {
+    type Candidate = String;
+
+    fn complete(
+        &self,
+        line: &str,
+        pos: usize,
+        _ctx: &Context<'_>,
+    ) -> rustyline::Result<(usize, Vec<String>)> {
+        let start = line[..pos]
+            .char_indices()
+            .rev()
+            .find(|(_, c)| !is_name_char(*c))
+            .map(|(i, c)| i + c.len_utf8())
+            .unwrap_or(0);
+        let prefix = &line[start..pos];
+
+        if prefix.is_empty() {
+            return Ok((pos, Vec::new()));
+        }
+
+        let candidates = self
+            .names
+            .iter()
+            .filter(|x| x.starts_with(prefix))
+            .cloned()
+            .collect();
+
+        Ok((start, candidates))
+    }
+}
+
+impl Hinter for ReplHelper {}
+
+impl Highlighter for ReplHelper {
+    fn highlight<'l>(&self, line: &'l str, pos: usize) -> Cow<'l, str> {
+        self.brackets.highlight(line, pos)
+    }
+
+    fn highlight_char(&self, line: &str, pos: usize) -> bool {
+        self.brackets.highlight_char(line, pos)
+    }
+}
+
+impl Validator for ReplHelper {
+    fn validate(&self, ctx: &mut ValidationContext) -> rustyline::Result<ValidationResult> {
+        if is_incomplete(ctx.input()) {
+            Ok(ValidationResult::Incomplete)
+        } else {
+            Ok(ValidationResult::Valid(None))
+        }
+    }
+}
+
+/// Checks if an input has unclosed brackets or an unterminated string.
+///
+/// Extra closing brackets don't make the input incomplete, the reader
+/// reports them.
+///
+fn is_incomplete(input: &str) -> bool {
+    let mut depth = 0;
+    let mut previous = ' ';
+    let mut chars = input.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth -= 1,
+            ';' => {
+                while chars.peek().is_some_and(|x| *x != '\n' && *x != '\r') {
+                    chars.next();
+                }
+            }
+            '#' => match chars.peek() {
+                // `#{` opens a set, any other character is a Char literal.
+                Some('{') | None => {}
+                Some('\\') => {
+                    chars.next();
+                    chars.next();
+                }
+                Some(_) => {
+                    chars.next();
+                }
+            },
+            'r' if !is_name_char(previous) && chars.peek() == Some(&'"') => {
+                chars.next();
+
+                // Raw strings escape quotes by doubling them.
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                        }
+                        Some('"') => break,
+                        Some(_) => {}
+                        None => return true,
+                    }
+                }
+            }
+            '"' => loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => {
+                        chars.next();
+                    }
+                    Some(_) => {}
+                    None => return true,
+                }
+            },
+            _ => {}
+        }
+
+        previous = c;
+    }
+
+    depth > 0
+}
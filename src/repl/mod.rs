@@ -0,0 +1,149 @@
+mod command;
+mod helper;
+
+use command::{execute_command, is_command, Command};
+use helper::ReplHelper;
+
+use neolisp::nl::core::diagnostic::*;
+use neolisp::nl::interpreter::*;
+
+use rustyline::error::ReadlineError;
+use rustyline::Editor;
+
+use std::io;
+use std::path::PathBuf;
+
+/// Name of the history file, in the home directory of the user.
+const HISTORY_FILE: &str = ".neolisp_history";
+
+/// State of the REPL.
+///
+pub struct Session {
+    pub scope: Scope,
+    /// Sources of the inputs, used to render the errors.
+    pub sources: SourceMap,
+    /// Number of inputs read so far.
+    inputs: usize,
+}
+
+impl Session {
+    fn new(args: &[String]) -> Session {
+        Session {
+            scope: super::create_scope(args),
+            sources: SourceMap::new(),
+            inputs: 0,
+        }
+    }
+
+    /// Registers the source of an input. Returns the file name used by the
+    /// locations of its objects (`<repl:N>`).
+    ///
+    pub fn add_input(&mut self, source: &str) -> String {
+        self.inputs += 1;
+
+        let file = format!("<repl:{}>", self.inputs);
+        self.sources.insert(&file, source);
+
+        file
+    }
+
+    /// Prints an error with the source lines it refers to.
+    ///
+    pub fn report(&self, error: &Error) {
+        let renderer = Renderer::new(&self.sources).with_color(super::use_color(&io::stdout()));
+        print!("{}", renderer.render(error));
+    }
+}
+
+/// `neolisp repl [args...]`
+///
+pub fn repl(args: &[String]) -> i32 {
+    let mut rl = Editor::<ReplHelper>::new();
+    let mut session = Session::new(args);
+    let history = history_path();
+
+    rl.set_helper(Some(ReplHelper::new()));
+    update_completion(&mut rl, &session.scope);
+
+    if let Some(path) = &history {
+        // A missing history file is expected on the first run.
+        let _ = rl.load_history(path);
+    }
+
+    let code = loop {
+        match rl.readline("> ") {
+            Ok(line) => {
+                if !line.trim().is_empty() {
+                    rl.add_history_entry(line.trim_end());
+                }
+
+                if is_command(&line) {
+                    if let Command::Quit = execute_command(&mut session, args, &line) {
+                        break 0;
+                    }
+                } else {
+                    evaluate_line(&mut session, &line);
+                }
+
+                update_completion(&mut rl, &session.scope);
+            }
+            Err(ReadlineError::Interrupted) => break 0,
+            Err(ReadlineError::Eof) => break 0,
+            Err(err) => {
+                println!("Error: {:?}", err);
+                break super::EXIT_ERROR;
+            }
+        }
+    };
+
+    if let Some(path) = &history {
+        if let Err(err) = rl.save_history(path) {
+            eprintln!("Can't save the history to '{}' : {}", path.display(), err);
+        }
+    }
+
+    code
+}
+
+fn evaluate_line(session: &mut Session, line: &str) {
+    let file = session.add_input(line);
+
+    match read_source(&file, line) {
+        Ok(objects) => {
+            for object in objects {
+                let depth = session.scope.depth();
+
+                match evaluate(&mut session.scope, &object) {
+                    Ok(result) => println!("=> {}", result),
+                    Err(err) => {
+                        // Closes the levels the error left open.
+                        session.scope.unwind(depth);
+                        session.report(&err);
+                        break;
+                    }
+                }
+            }
+        }
+        Err(err) => session.report(&err),
+    }
+}
+
+/// Gives the names bound in the scope to the completion.
+///
+fn update_completion(rl: &mut Editor<ReplHelper>, scope: &Scope) {
+    if let Some(helper) = rl.helper_mut() {
+        helper.set_names(
+            scope
+                .visible_bindings()
+                .into_iter()
+                .map(|(name, _)| name)
+                .collect(),
+        );
+    }
+}
+
+/// Gets the path of the history file, if the home directory is known.
+///
+fn history_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|x| x.join(HISTORY_FILE))
+}
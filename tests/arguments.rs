@@ -0,0 +1,60 @@
+//! Binds the optional, rest and keyword parameters of user-defined forms.
+
+mod common;
+
+use common::{evaluate_error, evaluate_program};
+
+#[test]
+fn parameters() {
+    let cases = [
+        // Defaults may refer to the previous parameters.
+        (
+            "(defndynamic opt (a &optional (b (+ a 1)) c) (list a b c)) (opt 1)",
+            "(1 2 nil )",
+        ),
+        (
+            "(defndynamic opt (a &optional (b (+ a 1)) c) (list a b c)) (opt 1 5 6)",
+            "(1 5 6 )",
+        ),
+        ("(defndynamic rst (a &rest r) r) (rst 1)", "()"),
+        ("(defndynamic rst (a &rest r) r) (rst 1 2 3)", "(2 3 )"),
+        (
+            "(defndynamic kw (&key (k 3) j) (list k j)) (kw)",
+            "(3 nil )",
+        ),
+        (
+            "(defndynamic kw (&key (k 3) j) (list k j)) (kw :j 1 :k 4)",
+            "(4 1 )",
+        ),
+        // Closures and macros share the parameter lists.
+        ("(def f (fn (a &rest r) r)) (f 1 2)", "(2 )"),
+        ("(defmacro m (a &optional (b 2)) `(+ ,a ,b)) (m 1)", "3"),
+        // Invalid calls and parameter lists.
+        ("(defndynamic kw (&key k) k) (kw :z 1)", "\"E0000\""),
+        ("(defndynamic kw (&key k) k) (kw :k)", "\"E0000\""),
+        ("(defndynamic bad (&rest) 1)", "\"E0002\""),
+        ("(defndynamic bad (&optional a &rest) 1)", "\"E0002\""),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn arity_errors() {
+    let err = evaluate_error("(defndynamic two (a &optional b) a) (two 1 2 3)");
+
+    assert_eq!(
+        err["data"].to_string(),
+        r#"{"form":"two","min":1,"max":2,"actual":3}"#
+    );
+
+    // The expected signature is described.
+    let message = err["message"].as_str().unwrap();
+    assert!(
+        message.ends_with("Expected arguments : (a &optional b)"),
+        "{}",
+        message
+    );
+}
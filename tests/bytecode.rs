@@ -0,0 +1,266 @@
+//! Runs the same programs with the tree-walking interpreter and with the
+//! bytecode virtual machine, and compares their results, outputs and errors.
+
+mod common;
+
+use common::{create_scope, describe};
+
+use neolisp::nl::core::diagnostic::error_to_json;
+use neolisp::nl::interpreter::bytecode::*;
+use neolisp::nl::interpreter::*;
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Programs checked expression by expression, including the ones raising
+/// errors.
+const PROGRAMS: &[&str] = &[
+    // Literals and calls.
+    "1 2.5 \"text\" #a :key nil true () '(a b c)",
+    "(+ 1 2) (* 2 (- 10 4)) (list 1 (list 2 3))",
+    "{:a (+ 1 2) (+ 1 1) :b} #{1 (+ 1 1)}",
+    "(def sq (fn (x) (* x x))) (sq 12) sq",
+    // Variables and scopes.
+    "(def x 1) (let (x 2 y x) (list x y)) x",
+    "(let (x 1) (let (x (+ x 1)) x))",
+    "(let (x 1 x 2) x)",
+    "(let (a 1) (set! a (+ a 1)) (set! a (* a 10)) a)",
+    "(let (not 1) not) (let (f (fn (x) (* x 3))) (f 3))",
+    "(let (f 1) (f 3))",
+    "(defconst limit 10) (def limit 1)",
+    "(defconst limit 10) (let (y 1) (set! limit y))",
+    "(set! missing 1)",
+    "(let (a 1) (set! a (undefined)))",
+    "(def n (+ 1 \"a\"))",
+    // Flow control.
+    "(if true 1 2) (if false 1 2) (if false 1) (if true (+ 1 1))",
+    "(if 1 2 3)",
+    "(if true)",
+    "(when true 1 2) (when false 1) (unless false 3) (unless true 4)",
+    "(when 1 2)",
+    "(cond ((= 1 2) :a) ((= 1 1)) (else :c)) (cond ((= 1 2) :a)) (cond (else))",
+    "(cond (true 1) (else 2) (true 3))",
+    "(cond (1 2))",
+    "(and) (or) (and true false) (or false true) (not false)",
+    "(and true 2)",
+    "(or false (+ 1 \"a\"))",
+    "(not 1)",
+    "(let (v 2) (case v (1 :one) ((2 3) :two-or-three) (else :other)))",
+    "(do) (do 1 2 3)",
+    "(let (x 1) (do (let (x 2) x)))",
+    "(let ((x) 1) x)",
+    "(let (x))",
+    // Loops.
+    "(let (i 0 s 0) (while (< i 10) (set! s (+ s i)) (set! i (+ i 1))) s)",
+    "(let (i 0) (while true (set! i (+ i 1)) (when (> i 20) (break)) (set! i (+ i 10))) i)",
+    "(let (i 0 n 0)
+       (while (< i 3)
+         (let (j 0)
+           (while true
+             (when (= j 2) (break))
+             (set! n (+ n 1))
+             (set! j (+ j 1))))
+         (set! i (+ i 1)))
+       n)",
+    "(break)",
+    "(let (i 0) (while (< i 1) (break 1)))",
+    "(defndynamic stop () (break)) (let (i 0) (while (< i 1) (stop)))",
+    "(let (i 0) (while i (set! i 1)))",
+    "(def k 0) (while (< k 3) (set! k (+ k 1)) (try (break) (catch e e))) k",
+    // Dynamic forms see the variables of their callers.
+    "(defndynamic get-y () y) (defndynamic with-y (y) (get-y)) (with-y 42)",
+    "(defndynamic get-y () y) (let (y 5) (get-y))",
+    "(defndynamic set-y () (set! y (* y 2))) (defndynamic twice (y) (set-y) (set-y) y) (twice 3)",
+    "(defndynamic set-y () (set! y 0)) (let (y 1 z 2) (set-y) (list y z))",
+    "(def y :global) (defndynamic read () y) (defndynamic shadow (y) (list (read) y)) (shadow :local)",
+    "(defndynamic f3 () deep) (defndynamic f2 () (f3)) (defndynamic f1 () (f2)) (let (deep 7) (f1))",
+    "(defndynamic f0 () (list v (+ v 1))) (let (v 1) (let (v 2) (f0)))",
+    "(defndynamic inner () x) (defndynamic outer (x) (let (x (+ x 1)) (inner))) (outer 1)",
+    "(defndynamic lookup () x) (defndynamic loop-lookup (x) (let (r (list)) (while (< (length r) 2) (set! r (append r (lookup)))) r)) (loop-lookup :v)",
+    // Argument lists.
+    "(defndynamic opt (a &optional (b (+ a 1))) (list a b)) (opt 1) (opt 1 5)",
+    "(defndynamic rst (a &rest r) (list a r)) (rst 1) (rst 1 2 3)",
+    "(defndynamic kw (&key (k 3)) k) (kw) (kw :k 4) (kw :j 1)",
+    "(defndynamic two (a b) (+ a b)) (two 1)",
+    "(defndynamic two (a b) (+ a b)) (two 1 2 3)",
+    "((fn (a) a))",
+    // Closures.
+    "(def make (fn (n) (fn (x) (+ x n)))) ((make 3) 4) (funcall (make 1) 1)",
+    "(defndynamic counter () (let (c 0) (fn () (set! c (+ c 1)) c)))
+     (def next (counter)) (next) (next) (next)",
+    "(def twice (fn (f x) (f (f x)))) (twice (fn (x) (* x x)) 3)",
+    "(let (f (fn (x) (* x 2))) ((fn (g) (g 5)) f))",
+    "(def outer-x 1) (def read-x (fn () outer-x)) (let (outer-x 2) (read-x))",
+    "((list) 1)",
+    "(1 2)",
+    "(def failing (fn (x) (+ x \"a\"))) (failing 1)",
+    "(defndynamic deep-fail (n) (if (= n 0) (+ 1 \"a\") (deep-fail (- n 1)))) (deep-fail 3)",
+    "(defndynamic args-fail (n) n) (args-fail (+ 1 \"a\"))",
+    "(def fn-var (fn (n) n)) (fn-var (+ 1 \"a\"))",
+    "(+ 1 (list \"a\"))",
+    "(undefined 1 2)",
+    "(def not-a-function 1) (not-a-function 2)",
+    // Exceptions and macros, evaluated by the interpreter.
+    "(try (throw :t 1) (catch :t e (error-value e)))",
+    "(defndynamic risky (x) (try (+ x 1) (catch e (error-code e)))) (list (risky 1) (risky :a))",
+    "(defmacro twice (e) `(do ,e ,e)) (def c 0) (twice (set! c (+ c 1))) c",
+    "(defmacro swap (a b) `(let (tmp ,a) (set! ,a ,b) (set! ,b tmp)))
+     (defndynamic swapped (x y) (swap x y) (list x y)) (swapped 1 2)",
+    "(match (list 1 2) ((a b) (+ a b)))",
+    // Recursion.
+    "(defndynamic fact (n) (if (= n 0) 1 (* n (fact (- n 1))))) (fact 20)",
+    "(defndynamic even? (n) (if (= n 0) true (odd? (- n 1))))
+     (defndynamic odd? (n) (if (= n 0) false (even? (- n 1))))
+     (list (even? 10) (odd? 7))",
+    // Tail calls, replacing the frames of their callers.
+    "(defndynamic down (n) (cond ((= n 0) (let (x 1) (+ x \"a\"))) (else (down (- n 1))))) (down 3)",
+    "(defndynamic kept (n) (let (f (fn () n)) (if (= n 0) (+ n \"b\") (kept (- n 1))))) (kept 2)",
+    "(def down (fn (n) (when (> n 0) (down (- n 1))) (if (= n 0) (undefined) (down (- n 1))))) (down 2)",
+    "(defndynamic inner () (+ n 1)) (defndynamic outer (n) (let (n (* n 10)) (inner))) (outer 4)",
+    "(defndynamic inner () (set! n 0)) (defndynamic outer (n) (do (inner))) (outer 4)",
+    "(defmacro recur (f n) `(,f (- ,n 1))) (defndynamic down (n) (if (= n 0) (+ n \"c\") (recur down n))) (down 2)",
+    "(defndynamic down (n) (case n (0 ((list) 1)) (else (down (- n 1))))) (down 2)",
+    "(defndynamic arity (a) a) (defndynamic caller () (arity)) (caller)",
+    // Operators resolved again once redefined, shadowed or unbound.
+    "(defndynamic op (a b) (+ a b)) (defndynamic run () (op 1 2)) (run)
+     (defndynamic op (a b) (- a b)) (run) (def op (fn (a b) (* a b))) (run)",
+    "(def f (fn (x) (* x 10))) (defndynamic call () (f 2)) (call)
+     (let (f (fn (x) (+ x 1))) (call)) (call) (let (f 5) (call))",
+    "(def f (fn (x) (* x 10))) (defndynamic call () (f 2)) (defndynamic wrap (f) (call))
+     (call) (wrap (fn (x) x)) (wrap 5) (call) (set! f (fn (x) (- x))) (call)",
+    "(def f (fn (x) x)) (defndynamic call (n) (f n))
+     (let (i 0 r (list)) (while (< i 3) (set! r (cons (call i) r)) (set! f (fn (x) (* x 100))) (set! i (+ i 1))) r)",
+    "(def make (fn (g) (fn (x) (g x)))) (def a (make (fn (x) (+ x 1)))) (def b (make (fn (x) (* x 3))))
+     (a 1) (b 1) (a 2)",
+    "(defndynamic call () (g 1)) (call) (def g (fn (x) x)) (call) (defmacro g (x) x) (call)",
+    "(def f (fn (x) (* x 10))) (defndynamic call () (f 2)) (def hide (fn () (list (call))))
+     (let (f (fn (x) x)) (list (hide) (call)))",
+    "(def f (fn (x) (* x 10))) (defndynamic call () (f 2)) (def hide (fn () (list (call))))
+     (defndynamic outer (f) (list (hide) (call))) (outer (fn (x) x))",
+];
+
+/// Evaluates a program expression by expression, in both modes.
+fn compare(program: &str) {
+    let objects =
+        read_source("<program>", program).unwrap_or_else(|err| panic!("{}", error_to_json(&err)));
+
+    let mut interpreted = create_scope();
+    let mut compiled = create_scope();
+
+    for object in &objects {
+        let expected = describe(evaluate(&mut interpreted, object));
+        let actual = describe(evaluate_compiled(&mut compiled, object));
+
+        assert_eq!(
+            expected, actual,
+            "\nexpression : {}\nprogram : {}",
+            object, program
+        );
+    }
+}
+
+#[test]
+fn programs() {
+    for program in PROGRAMS {
+        compare(program);
+    }
+}
+
+#[test]
+fn deep_dynamic_calls() {
+    // A chain of dynamic forms longer than the searched ones.
+    let mut program = String::from("(defndynamic f0 () hidden)");
+
+    for i in 1..40 {
+        program.push_str(&format!("(defndynamic f{} () (f{}))", i, i - 1));
+    }
+
+    program.push_str("(let (hidden :found) (f39))");
+    compare(&program);
+}
+
+/// Evaluates a program in a scope with the virtual machine, then gets the
+/// value of its last expression.
+fn run_compiled(scope: &mut Scope, program: &str) -> String {
+    let objects =
+        read_source("<program>", program).unwrap_or_else(|err| panic!("{}", error_to_json(&err)));
+
+    objects
+        .iter()
+        .map(|x| describe(evaluate_compiled(scope, x)))
+        .last()
+        .unwrap()
+}
+
+#[test]
+fn shared_forms() {
+    // A form compiled in a scope resolves its operators in every scope
+    // calling it.
+    let mut first = create_scope();
+    let mut second = create_scope();
+
+    let source = "(defndynamic op () :first) (defndynamic call () (op)) (call)";
+    assert_eq!(run_compiled(&mut first, source), "first");
+
+    second.insert("call".to_string(), first.get_binding("call").unwrap());
+
+    let source = "(defndynamic op () :second) (call)";
+    assert_eq!(run_compiled(&mut second, source), "second");
+    assert_eq!(run_compiled(&mut first, "(call)"), "first");
+}
+
+fn run(file: &PathBuf, vm: bool) -> Output {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_neolisp"));
+    command.arg("run").arg("--error-format=json");
+
+    if vm {
+        command.arg("--vm");
+    }
+
+    command.arg(file).output().expect("can't run neolisp")
+}
+
+#[test]
+fn examples() {
+    let directory = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("examples");
+
+    let mut files: Vec<PathBuf> = fs::read_dir(&directory)
+        .unwrap()
+        .map(|x| x.unwrap().path())
+        .filter(|x| x.extension().is_some_and(|x| x == "nl"))
+        .collect();
+    files.sort();
+
+    assert!(!files.is_empty());
+
+    for file in &files {
+        let interpreted = run(file, false);
+        let compiled = run(file, true);
+
+        let stderr = String::from_utf8_lossy(&interpreted.stderr);
+        assert!(interpreted.status.success(), "{:?} : {}", file, stderr);
+
+        assert_eq!(
+            interpreted.status.code(),
+            compiled.status.code(),
+            "{:?}",
+            file
+        );
+        assert_eq!(
+            String::from_utf8_lossy(&interpreted.stdout),
+            String::from_utf8_lossy(&compiled.stdout),
+            "{:?}",
+            file
+        );
+        assert_eq!(
+            stderr,
+            String::from_utf8_lossy(&compiled.stderr),
+            "{:?}",
+            file
+        );
+
+        // Every expression gives the same value too.
+        compare(&fs::read_to_string(file).unwrap());
+    }
+}
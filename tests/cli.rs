@@ -0,0 +1,80 @@
+//! Runs the `run` and `eval` subcommands of `neolisp`.
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Runs `neolisp` with arguments, without colors.
+fn neolisp(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_neolisp"))
+        .args(args)
+        .env("NO_COLOR", "1")
+        .output()
+        .expect("can't start neolisp")
+}
+
+/// Writes a source in a temporary file, unique to a test.
+fn temporary_file(name: &str, source: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("neolisp-cli-{}-{}.nl", std::process::id(), name));
+    fs::write(&path, source).unwrap();
+    path
+}
+
+#[test]
+fn run() {
+    let file = temporary_file("run", "(println (join *args* \",\"))\n(def x 1)\n");
+
+    // The extra arguments are bound to `*args*`.
+    let output = neolisp(&["run", file.to_str().unwrap(), "a", "b"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "a,b\n");
+
+    let output = neolisp(&["run", "--vm", file.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "\n");
+
+    fs::remove_file(file).unwrap();
+}
+
+#[test]
+fn run_errors() {
+    let file = temporary_file("errors", "(def x 1)\n(+ x :a)\n");
+
+    // Errors are located in the file.
+    let output = neolisp(&["run", "--error-format=json", file.to_str().unwrap()]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let err: serde_json::Value = serde_json::from_str(stderr.trim()).unwrap();
+    assert_eq!(err["code"], "E0006");
+    assert_eq!(err["frames"][0]["location"]["file"], file.to_str().unwrap());
+    assert_eq!(err["frames"][0]["location"]["line"], 2);
+
+    assert_eq!(neolisp(&["run", "/nonexistent.nl"]).status.code(), Some(1));
+    assert_eq!(neolisp(&["run"]).status.code(), Some(2));
+
+    fs::remove_file(file).unwrap();
+}
+
+#[test]
+fn eval() {
+    // The value of the last expression is printed.
+    let output = neolisp(&["eval", "(def x 2) (+ x 1)"]);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "3\n");
+
+    let output = neolisp(&["eval", "(first *args*)", "a"]);
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "\"a\"\n");
+
+    let output = neolisp(&["eval", "(undefined)"]);
+    assert_eq!(output.status.code(), Some(1));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.starts_with("error : unbound symbol 'undefined'"),
+        "{}",
+        stderr
+    );
+
+    assert_eq!(neolisp(&["eval", "(a"]).status.code(), Some(1));
+    assert_eq!(neolisp(&["unknown"]).status.code(), Some(2));
+}
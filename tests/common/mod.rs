@@ -0,0 +1,42 @@
+//! Helpers shared by the integration tests. Each test crate uses only some
+//! of them.
+#![allow(dead_code)]
+
+use neolisp::nl::core::diagnostic::error_to_json;
+use neolisp::nl::core::object::*;
+use neolisp::nl::interpreter::builtin::register_all_builtin_forms;
+use neolisp::nl::interpreter::*;
+
+use serde_json::Value;
+
+/// Creates a scope with the builtin forms.
+pub fn create_scope() -> Scope {
+    let mut scope = Scope::new();
+    register_all_builtin_forms(&mut scope);
+    scope
+}
+
+/// Describes the result of an evaluation : its value, or its error in JSON.
+pub fn describe(result: Result<Object, Error>) -> String {
+    match result {
+        Ok(v) => v.to_string(),
+        Err(err) => error_to_json(&err).to_string(),
+    }
+}
+
+/// Evaluates a program, then describes the value of its last expression,
+/// or the code of its error.
+pub fn evaluate_program(source: &str) -> String {
+    match evaluate_source(&mut create_scope(), "<program>", source) {
+        Ok(v) => v.to_string(),
+        Err(err) => error_to_json(&err)["code"].to_string(),
+    }
+}
+
+/// Evaluates a program which must fail, then gets its error in JSON.
+pub fn evaluate_error(source: &str) -> Value {
+    match evaluate_source(&mut create_scope(), "<program>", source) {
+        Ok(v) => panic!("{} evaluated to {}", source, v),
+        Err(err) => error_to_json(&err),
+    }
+}
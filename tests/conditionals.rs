@@ -0,0 +1,53 @@
+//! Evaluates the conditional forms `cond`, `case`, `when`, `unless`, `and`,
+//! `or` and `not`.
+
+mod common;
+
+use common::{evaluate_error, evaluate_program};
+
+#[test]
+fn branches() {
+    let cases = [
+        ("(cond ((= 1 2) :a) ((= 1 1) :b) (else :c))", "b"),
+        ("(cond ((= 1 2) :a))", "nil"),
+        ("(case 2 (1 :one) ((2 3) :two) (else :other))", "two"),
+        ("(case #a (#a 1))", "1"),
+        ("(case \"s\" (\"s\" 1))", "1"),
+        ("(case 9 (1 2))", "nil"),
+        ("(when true 1 2)", "2"),
+        ("(when false 1)", "nil"),
+        ("(unless false 3)", "3"),
+        ("(unless true 3)", "nil"),
+        ("(not false)", "true"),
+        ("(and)", "true"),
+        ("(or)", "false"),
+        // `and` and `or` stop at the first deciding value.
+        ("(and true false (undefined))", "false"),
+        ("(or true (undefined))", "true"),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn errors() {
+    let cases = [
+        ("(and true 2)", "\"E0006\""),
+        ("(or false 3)", "\"E0006\""),
+        ("(not 1)", "\"E0006\""),
+        ("(when 1 2)", "\"E0006\""),
+        ("(case 1 (x 2))", "\"E0000\""),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+
+    // The invalid condition is located.
+    let err = evaluate_error("(cond ((= 1 2) 1)\n (1 2))");
+    assert_eq!(err["code"], "E0006");
+    assert_eq!(err["frames"][0]["location"]["line"], 2);
+    assert_eq!(err["frames"][0]["location"]["column"], 3);
+}
@@ -0,0 +1,107 @@
+//! Renders errors as diagnostics with source snippets.
+
+mod common;
+
+use common::create_scope;
+
+use neolisp::nl::core::diagnostic::{Renderer, SourceMap};
+use neolisp::nl::interpreter::*;
+
+/// Evaluates a source which must fail, then renders its error.
+fn render(source: &str, color: bool) -> String {
+    let mut sources = SourceMap::new();
+    sources.insert("<program>", source);
+
+    let err = match evaluate_source(&mut create_scope(), "<program>", source) {
+        Ok(v) => panic!("{} evaluated to {}", source, v),
+        Err(err) => err,
+    };
+
+    Renderer::new(&sources).with_color(color).render(&err)
+}
+
+#[test]
+fn snippets() {
+    let source = "(def x 1)\n(defndynamic f (y)\n  (+ y :a))\n(f x)\n";
+
+    // Every frame is labelled, under its underlined span.
+    assert_eq!(
+        render(source, false),
+        "error : '+' only receives numbers, got a Keyword as argument 2 : :a
+  --> <program>:3:4
+   |
+ 3 |   (+ y :a))
+   |    ^ in '+'
+  --> <program>:2:14
+   |
+ 2 | (defndynamic f (y)
+   |              ^ in 'f'
+  --> <program>:4:2
+   |
+ 4 | (f x)
+   |  ^ in 'f'
+"
+    );
+}
+
+#[test]
+fn syntax_errors() {
+    let source = "(a b";
+    let mut sources = SourceMap::new();
+    sources.insert("<program>", source);
+
+    let err = match read_source("<program>", source) {
+        Ok(_) => panic!("{} was read", source),
+        Err(err) => err,
+    };
+
+    let rendered = Renderer::new(&sources).render(&err);
+
+    // The expected tokens are listed, the end of the input is pointed.
+    assert!(
+        rendered.starts_with("error : syntax error, expected one of \"#\""),
+        "{}",
+        rendered
+    );
+    assert!(
+        rendered.ends_with("  --> <program>:1:5\n   |\n 1 | (a b\n   |     ^ in 'reader'\n"),
+        "{}",
+        rendered
+    );
+}
+
+#[test]
+fn colors() {
+    let source = "(undefined)";
+
+    assert!(!render(source, false).contains('\x1b'));
+    assert!(render(source, true).contains("\x1b["));
+}
+
+#[test]
+fn expansions() {
+    let source = "(defmacro bad (x) `(+ ,x :a))\n(defmacro outer (x) `(bad ,x))\n(outer 1)\n";
+
+    // The call site of the nested expansions is shown once.
+    assert_eq!(
+        render(source, false),
+        "error : '+' only receives numbers, got a Keyword as argument 2 : :a
+  --> <program>:3:1
+   |
+ 3 | (outer 1)
+   | ^^^^^^^^^ in '+', from the expansion of 'bad' within 'outer'
+  --> <program>:1:11
+   |
+ 1 | (defmacro bad (x) `(+ ,x :a))
+   |           ^^^ in 'bad'
+  --> <program>:2:11
+   |
+ 2 | (defmacro outer (x) `(bad ,x))
+   |           ^^^^^ in 'outer'
+  --> <program>:3:2
+   |
+ 3 | (outer 1)
+   |  ^^^^^ in 'outer'
+"
+    );
+}
@@ -0,0 +1,61 @@
+//! Describes the values of arity and range errors in their JSON form.
+
+mod common;
+
+use common::evaluate_error;
+
+#[test]
+fn arity() {
+    let cases = [
+        ("(first)", r#"{"form":"first","min":1,"max":1,"actual":0}"#),
+        (
+            "(slice (list 1))",
+            r#"{"form":"slice","min":2,"max":3,"actual":1}"#,
+        ),
+        (
+            "(apply +)",
+            r#"{"form":"apply","min":2,"max":null,"actual":1}"#,
+        ),
+        (
+            "(defndynamic f (a &optional b) a) (f)",
+            r#"{"form":"f","min":1,"max":2,"actual":0}"#,
+        ),
+        (
+            "(defndynamic f (a &rest b) a) (f)",
+            r#"{"form":"f","min":1,"max":null,"actual":0}"#,
+        ),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(
+            evaluate_error(source)["data"].to_string(),
+            expected,
+            "{}",
+            source
+        );
+    }
+}
+
+#[test]
+fn range() {
+    let cases = [
+        ("(nth (list 1 2) 2)", r#"{"index":2,"start":0,"end":2}"#),
+        ("(nth nil 0)", r#"{"index":0,"start":0,"end":0}"#),
+        ("(vector-ref [1] -1)", r#"{"index":-1,"start":0,"end":1}"#),
+        ("(char-at \"\" 0)", r#"{"index":0,"start":0,"end":0}"#),
+        ("(slice (list 1 2) 3)", r#"{"index":3,"start":0,"end":3}"#),
+        (
+            "(substring \"abc\" 2 1)",
+            r#"{"index":2,"start":0,"end":2}"#,
+        ),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(
+            evaluate_error(source)["data"].to_string(),
+            expected,
+            "{}",
+            source
+        );
+    }
+}
@@ -0,0 +1,74 @@
+//! Raises and handles errors with `throw`, `try`, `catch` and `finally`.
+
+mod common;
+
+use common::{create_scope, evaluate_error, evaluate_program};
+
+use neolisp::nl::interpreter::*;
+
+#[test]
+fn handlers() {
+    let cases = [
+        ("(try (throw :t 1) (catch :t e (error-value e)))", "1"),
+        // The first clause matching the tag handles the error.
+        (
+            "(try (throw :t 1) (catch :other e 1) (catch e (error-tag e)))",
+            "t",
+        ),
+        (
+            "(try (undefined) (catch e (error-message e)))",
+            "unbound symbol 'undefined'",
+        ),
+        ("(try (+ 1 :a) (catch e (error-tag e)))", "error"),
+        (
+            "(try (throw :t 1) (catch e (error-frames e)))",
+            "((throw in file '<program>', at 1:7 ) )",
+        ),
+        // The levels opened by the failed expression are closed.
+        (
+            "(let (n 1) (try (let (n 2) (throw :t 1)) (catch e n)))",
+            "1",
+        ),
+        ("(try 1 (finally 2))", "1"),
+        ("(throw 1 2)", "\"E0006\""),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn uncaught() {
+    let err = evaluate_error("(try (throw :t 1) (catch :u e 1))");
+
+    assert_eq!(err["code"], "E0011");
+    assert_eq!(err["data"].to_string(), r#"{"tag":"t","value":"1"}"#);
+}
+
+#[test]
+fn finally() {
+    // `finally` runs after an error, which is then raised again.
+    let source = "(def log (list))
+                  (try
+                    (try (+ 1 :a) (finally (set! log (cons :f log))))
+                    (catch e (list (error-code e) log)))";
+    assert_eq!(evaluate_program(source), "(E0006 (f ) )");
+
+    let mut scope = create_scope();
+    let depth = scope.depth();
+
+    let source = "(def ran false) (try (let (y 2) (+ y :a)) (finally (set! ran true)))";
+    assert!(evaluate_source(&mut scope, "<program>", source).is_err());
+
+    // The levels opened in `try` are closed.
+    assert_eq!(scope.depth(), depth);
+    assert!(!scope.has_binding("y"));
+    assert_eq!(
+        evaluate_source(&mut scope, "<program>", "ran")
+            .ok()
+            .unwrap()
+            .to_string(),
+        "true"
+    );
+}
@@ -0,0 +1,165 @@
+//! Formats sources with the formatter and with `neolisp fmt`.
+
+use neolisp::nl::core::diagnostic::error_to_json;
+use neolisp::nl::formatter::Formatter;
+
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Output, Stdio};
+
+/// Sources whose expected formatting is already known, with their width.
+const CASES: &[(&str, usize, &str)] = &[
+    // Comments and blank lines are kept, consecutive blank lines collapse.
+    (
+        "(def   x 1) ; trailing\n\n\n\n; alone\n(def y 2)\n",
+        80,
+        "(def x 1) ; trailing\n\n; alone\n(def y 2)\n",
+    ),
+    (
+        "(defndynamic f (x y)\n  (print x)\n\n  ; then\n  (print y))\n",
+        80,
+        "(defndynamic f (x y)\n  (print x)\n\n  ; then\n  (print y))\n",
+    ),
+    // A comment may end the source without an end of line.
+    ("(a b) ; end", 80, "(a b) ; end\n"),
+    // Bodies are indented by two spaces, the branches of `if` are aligned.
+    (
+        "(defndynamic f (x y)\n(let (a 1 b 2)\n(if (= x 0) (+ a x)\n(* b y))))\n",
+        24,
+        "(defndynamic f (x y)\n  (let (a 1 b 2)\n    (if (= x 0)\n        (+ a x)\n        (* b y))))\n",
+    ),
+    // Forms fitting in the width are joined on a single line.
+    (
+        "(defndynamic f (x y)\n(let (a 1 b 2)\n(if (= x 0) a\nb)))\n",
+        80,
+        "(defndynamic f (x y) (let (a 1 b 2) (if (= x 0) a b)))\n",
+    ),
+    // Calls exceeding the width are wrapped, one argument per line.
+    (
+        "(def total (+ first-value second-value third-value fourth-value))\n",
+        30,
+        "(def total\n  (+ first-value\n     second-value\n     third-value\n     fourth-value))\n",
+    ),
+];
+
+fn format(source: &str, width: usize) -> String {
+    Formatter::new()
+        .with_width(width)
+        .format("<source>", source)
+        .unwrap_or_else(|err| panic!("{}", error_to_json(&err)))
+}
+
+/// Runs `neolisp fmt` with arguments, writing a source to its standard
+/// input.
+fn run_fmt(args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_neolisp"))
+        .arg("fmt")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("can't start neolisp");
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+/// Writes a source in a temporary file, unique to a test.
+fn temporary_file(name: &str, source: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("neolisp-fmt-{}-{}.nl", std::process::id(), name));
+    fs::write(&path, source).unwrap();
+    path
+}
+
+#[test]
+fn formatting() {
+    for (source, width, expected) in CASES {
+        assert_eq!(&format(source, *width), expected, "{}", source);
+    }
+}
+
+#[test]
+fn idempotence() {
+    let mut sources: Vec<(String, usize)> = CASES
+        .iter()
+        .map(|(source, width, _)| (source.to_string(), *width))
+        .collect();
+
+    for entry in fs::read_dir("examples").unwrap() {
+        let path = entry.unwrap().path();
+
+        if path.extension().is_some_and(|x| x == "nl") {
+            for width in [20, 80] {
+                sources.push((fs::read_to_string(&path).unwrap(), width));
+            }
+        }
+    }
+
+    for (source, width) in sources {
+        let formatted = format(&source, width);
+        assert_eq!(format(&formatted, width), formatted, "{}", source);
+    }
+}
+
+#[test]
+fn command() {
+    let output = run_fmt(&["--width=30"], CASES[5].0);
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), CASES[5].2);
+
+    let output = run_fmt(&[], "(a b) ; end");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "(a b) ; end\n");
+
+    // Syntax errors and usage errors.
+    assert_eq!(run_fmt(&[], "(a").status.code(), Some(1));
+    assert_eq!(run_fmt(&["--width=0"], "").status.code(), Some(2));
+    assert_eq!(run_fmt(&["--unknown"], "").status.code(), Some(2));
+}
+
+#[test]
+fn check() {
+    // The standard input is checked without being printed.
+    let output = run_fmt(&["--check"], "(def x 1)\n");
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+
+    let output = run_fmt(&["--check"], "(def   x 1)\n");
+    assert_eq!(output.status.code(), Some(1));
+
+    // Files are listed, and left unchanged.
+    let formatted = temporary_file("formatted", "(def x 1)\n");
+    let unformatted = temporary_file("unformatted", "(def   x 1)\n");
+
+    let output = run_fmt(&["--check", formatted.to_str().unwrap()], "");
+    assert_eq!(output.status.code(), Some(0));
+
+    let output = run_fmt(
+        &[
+            "--check",
+            formatted.to_str().unwrap(),
+            unformatted.to_str().unwrap(),
+        ],
+        "",
+    );
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("unformatted"), "{}", stdout);
+    assert!(!stdout.contains("-formatted"), "{}", stdout);
+    assert_eq!(fs::read_to_string(&unformatted).unwrap(), "(def   x 1)\n");
+
+    // Without `--check`, the files are formatted in place.
+    let output = run_fmt(&[unformatted.to_str().unwrap()], "");
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(fs::read_to_string(&unformatted).unwrap(), "(def x 1)\n");
+
+    fs::remove_file(formatted).unwrap();
+    fs::remove_file(unformatted).unwrap();
+}
@@ -0,0 +1,61 @@
+//! Calls functions, eval forms and dynamic forms through `funcall` and
+//! `apply`.
+
+mod common;
+
+use common::evaluate_program;
+
+#[test]
+fn funcall() {
+    let cases = [
+        ("(funcall (fn (a b) (- a b)) 5 2)", "3"),
+        ("(funcall + 1 2)", "3"),
+        ("(funcall '< 1 2)", "true"),
+        ("(defndynamic twice (x) (* x 2)) (funcall twice 4)", "8"),
+        ("(defndynamic twice (x) (* x 2)) (funcall 'twice 4)", "8"),
+        (
+            "(defndynamic fold (f acc l)
+               (if (= (length l) 0) acc (fold f (funcall f acc (first l)) (rest l))))
+             (fold '+ 0 (list 1 2 3 4))",
+            "10",
+        ),
+        // Dynamic forms still see the variables of their caller.
+        (
+            "(defndynamic scaled (x) (* x factor)) (let (factor 3) (funcall scaled 2))",
+            "6",
+        ),
+        ("(funcall 1 2)", "\"E0006\""),
+        ("(funcall 'undefined 2)", "\"E0006\""),
+        ("(funcall + 1 \"a\")", "\"E0006\""),
+        ("(defndynamic one (x) x) (funcall one)", "\"E0005\""),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn apply() {
+    let cases = [
+        ("(apply (fn (a b) (- a b)) (list 5 2))", "3"),
+        ("(apply + (list 1 2))", "3"),
+        ("(apply + 1 2 (list 3 4))", "10"),
+        ("(apply 'max (list 1 5 2))", "5"),
+        ("(apply + nil)", "0"),
+        (
+            "(defndynamic sum3 (a b c) (+ a b c)) (apply sum3 1 (list 2 3))",
+            "6",
+        ),
+        (
+            "(defndynamic sum3 (a b c) (+ a b c)) (apply 'sum3 (list 1 2 3))",
+            "6",
+        ),
+        ("(apply + 1)", "\"E0006\""),
+        ("(apply 1 (list 2))", "\"E0006\""),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
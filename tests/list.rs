@@ -0,0 +1,40 @@
+//! Handles `nil` as the empty list in the list builtins.
+
+mod common;
+
+use common::evaluate_program;
+
+#[test]
+fn empty_results() {
+    let cases = [
+        "(rest nil)",
+        "(rest (list))",
+        "(rest (list 1))",
+        "(reverse nil)",
+        "(sort nil)",
+        "(concat nil nil)",
+        "(slice nil 0 0)",
+    ];
+
+    let empty = evaluate_program("(list)");
+
+    for source in cases {
+        assert_eq!(evaluate_program(source), empty, "{}", source);
+    }
+}
+
+#[test]
+fn nil_arguments() {
+    let cases = [
+        ("(first nil)", "nil"),
+        ("(length nil)", "0"),
+        ("(cons 1 nil)", "(1 )"),
+        ("(contains? nil 1)", "false"),
+        ("(index-of nil 1)", "nil"),
+        ("(rest 1)", "\"E0006\""),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
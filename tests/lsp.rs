@@ -0,0 +1,273 @@
+//! Drives `neolisp lsp` over its standard input and output, like an editor.
+
+use serde_json::{json, Value};
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::process::{Child, ChildStdin, ChildStdout, Command, Stdio};
+
+const MAIN_URI: &str = "file:///main.nl";
+const BROKEN_URI: &str = "file:///broken.nl";
+
+const MAIN_SOURCE: &str = "(defconst limit 10)
+
+(defndynamic square (x)
+  (* x x))
+
+(def total (square limit))
+";
+
+struct Client {
+    child: Child,
+    input: ChildStdin,
+    output: BufReader<ChildStdout>,
+    next_id: i64,
+    /// Notifications received while waiting for responses.
+    notifications: Vec<Value>,
+}
+
+impl Client {
+    fn start() -> Client {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_neolisp"))
+            .arg("lsp")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .expect("can't start the language server");
+
+        let input = child.stdin.take().unwrap();
+        let output = BufReader::new(child.stdout.take().unwrap());
+
+        Client {
+            child,
+            input,
+            output,
+            next_id: 0,
+            notifications: Vec::new(),
+        }
+    }
+
+    fn send(&mut self, message: Value) {
+        self.send_content(&message.to_string());
+    }
+
+    fn send_content(&mut self, content: &str) {
+        write!(
+            self.input,
+            "Content-Length: {}\r\n\r\n{}",
+            content.len(),
+            content
+        )
+        .unwrap();
+        self.input.flush().unwrap();
+    }
+
+    fn receive(&mut self) -> Value {
+        let mut length = 0;
+
+        loop {
+            let mut line = String::new();
+            self.output.read_line(&mut line).unwrap();
+
+            match line.trim_end() {
+                "" => break,
+                header => {
+                    if let Some(v) = header.strip_prefix("Content-Length: ") {
+                        length = v.parse().unwrap();
+                    }
+                }
+            }
+        }
+
+        let mut content = vec![0; length];
+        self.output.read_exact(&mut content).unwrap();
+        serde_json::from_slice(&content).unwrap()
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Value {
+        self.next_id += 1;
+        let id = self.next_id;
+
+        self.send(json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }));
+
+        loop {
+            let message = self.receive();
+
+            if message["id"] == json!(id) {
+                return message;
+            }
+
+            self.notifications.push(message);
+        }
+    }
+
+    fn notify(&mut self, method: &str, params: Value) {
+        self.send(json!({ "jsonrpc": "2.0", "method": method, "params": params }));
+    }
+
+    fn open(&mut self, uri: &str, text: &str) -> Value {
+        self.notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": { "uri": uri, "languageId": "neolisp", "version": 1, "text": text },
+            }),
+        );
+
+        let message = self.receive();
+        assert_eq!(message["method"], "textDocument/publishDiagnostics");
+        assert_eq!(message["params"]["uri"], uri);
+
+        message["params"]["diagnostics"].clone()
+    }
+}
+
+fn position(uri: &str, line: u64, character: u64) -> Value {
+    json!({
+        "textDocument": { "uri": uri },
+        "position": { "line": line, "character": character },
+    })
+}
+
+#[test]
+fn lsp_session() {
+    let mut client = Client::start();
+
+    let response = client.request("initialize", json!({ "capabilities": {} }));
+    let capabilities = &response["result"]["capabilities"];
+    assert_eq!(capabilities["textDocumentSync"], 1);
+    assert_eq!(capabilities["definitionProvider"], true);
+    assert_eq!(capabilities["hoverProvider"], true);
+    assert_eq!(capabilities["documentSymbolProvider"], true);
+    assert!(capabilities["completionProvider"].is_object());
+
+    client.notify("initialized", json!({}));
+
+    // Diagnostics.
+    assert_eq!(client.open(MAIN_URI, MAIN_SOURCE), json!([]));
+
+    let diagnostics = client.open(BROKEN_URI, "(def x\n  (+ 1 2)");
+    assert_eq!(diagnostics.as_array().unwrap().len(), 1);
+    assert_eq!(diagnostics[0]["severity"], 1);
+    assert_eq!(diagnostics[0]["code"], "E0001");
+    assert_eq!(diagnostics[0]["source"], "neolisp");
+
+    // Go-to-definition of `square`, used on the last line.
+    let response = client.request("textDocument/definition", position(MAIN_URI, 5, 13));
+    assert_eq!(
+        response["result"],
+        json!({
+            "uri": MAIN_URI,
+            "range": {
+                "start": { "line": 2, "character": 13 },
+                "end": { "line": 2, "character": 19 },
+            },
+        })
+    );
+
+    // The cursor right after a symbol is still on it.
+    let response = client.request("textDocument/definition", position(MAIN_URI, 5, 24));
+    assert_eq!(response["result"]["range"]["start"]["line"], 0);
+
+    // Builtins have no definition.
+    let response = client.request("textDocument/definition", position(MAIN_URI, 3, 4));
+    assert_eq!(response["result"], Value::Null);
+
+    // Hover.
+    let response = client.request("textDocument/hover", position(MAIN_URI, 5, 14));
+    let hover = response["result"]["contents"]["value"].as_str().unwrap();
+    assert!(hover.contains("(square x)"), "{}", hover);
+
+    let response = client.request("textDocument/hover", position(MAIN_URI, 3, 3));
+    let hover = response["result"]["contents"]["value"].as_str().unwrap();
+    assert!(hover.contains("Builtin eval form"), "{}", hover);
+
+    // Document symbols.
+    let response = client.request(
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": MAIN_URI } }),
+    );
+    let symbols: Vec<(&str, i64)> = response["result"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|x| (x["name"].as_str().unwrap(), x["kind"].as_i64().unwrap()))
+        .collect();
+    assert_eq!(symbols, vec![("limit", 14), ("square", 12), ("total", 13)]);
+
+    // Completion.
+    let response = client.request("textDocument/completion", position(MAIN_URI, 5, 0));
+    let labels: Vec<&str> = response["result"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|x| x["label"].as_str().unwrap())
+        .collect();
+    for name in ["cons", "defndynamic", "if", "square", "limit"] {
+        assert!(labels.contains(&name), "'{}' isn't completed", name);
+    }
+
+    // Fixing the broken document clears its diagnostics.
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": BROKEN_URI, "version": 2 },
+            "contentChanges": [{ "text": "(def x\n  (+ 1 2))" }],
+        }),
+    );
+    let message = client.receive();
+    assert_eq!(message["params"]["diagnostics"], json!([]));
+
+    let response = client.request("unknown/method", json!({}));
+    assert_eq!(response["error"]["code"], -32601);
+
+    let response = client.request("shutdown", Value::Null);
+    assert_eq!(response["result"], Value::Null);
+    client.notify("exit", Value::Null);
+
+    assert!(client.notifications.is_empty());
+    assert!(client.child.wait().unwrap().success());
+}
+
+#[test]
+fn lsp_recovery() {
+    let mut client = Client::start();
+
+    client.request("initialize", json!({ "capabilities": {} }));
+
+    // A message which isn't valid JSON is answered, then the next ones are
+    // still read.
+    client.send_content("{\"jsonrpc\": \"2.0\", \"id\": ");
+    let message = client.receive();
+    assert_eq!(message["id"], Value::Null);
+    assert_eq!(message["error"]["code"], -32700);
+
+    assert_eq!(client.open(MAIN_URI, MAIN_SOURCE), json!([]));
+
+    // While the document has a syntax error, its previous definitions are
+    // kept.
+    client.notify(
+        "textDocument/didChange",
+        json!({
+            "textDocument": { "uri": MAIN_URI, "version": 2 },
+            "contentChanges": [{ "text": format!("{}(square", MAIN_SOURCE) }],
+        }),
+    );
+    let message = client.receive();
+    let diagnostics = &message["params"]["diagnostics"];
+    assert_eq!(diagnostics[0]["code"], "E0001");
+    assert_eq!(diagnostics[0]["range"]["start"]["line"], 6);
+
+    let response = client.request("textDocument/definition", position(MAIN_URI, 5, 13));
+    assert_eq!(response["result"]["range"]["start"]["line"], 2);
+
+    let response = client.request(
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": MAIN_URI } }),
+    );
+    assert_eq!(response["result"].as_array().unwrap().len(), 3);
+
+    client.request("shutdown", Value::Null);
+    client.notify("exit", Value::Null);
+
+    assert!(client.notifications.is_empty());
+    assert!(client.child.wait().unwrap().success());
+}
@@ -0,0 +1,47 @@
+//! Defines macros with `defmacro`, and evaluates their expansions.
+
+mod common;
+
+use common::evaluate_program;
+
+#[test]
+fn expansions() {
+    let cases = [
+        // The argument is expanded twice, so evaluated twice.
+        (
+            "(defmacro twice (e) `(do ,e ,e)) (def c 0) (twice (set! c (+ c 1))) c",
+            "2",
+        ),
+        (
+            "(defmacro my-if (c a b) `(cond (,c ,a) (else ,b))) (my-if false 1 2)",
+            "2",
+        ),
+        // The expansion is evaluated in the scope of the caller.
+        ("(defmacro inc (x) `(+ ,x 1)) (let (y 2) (inc y))", "3"),
+        (
+            "(defmacro swap (a b) `(let (tmp ,a) (set! ,a ,b) (set! ,b tmp)))
+             (let (x 1 y 2) (swap x y) (list x y))",
+            "(2 1 )",
+        ),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn errors() {
+    let cases = [
+        // The argument lists are checked like the ones of `defndynamic`.
+        ("(defmacro m (1) 1)", "\"E0002\""),
+        ("(defndynamic m (1) 1)", "\"E0002\""),
+        ("(defmacro m (a) a) (m)", "\"E0005\""),
+        // Errors raised by the expansion.
+        ("(defmacro m () (+ 1 :a)) (m)", "\"E0006\""),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
@@ -0,0 +1,29 @@
+//! Uses floats as the keys of maps and the elements of sets.
+
+mod common;
+
+use common::evaluate_program;
+
+#[test]
+fn float_keys() {
+    let cases = [
+        ("(length (keys {nan 1 nan 2}))", "1"),
+        ("(get {nan 1} nan)", "1"),
+        ("(get (assoc {} nan 1) nan)", "1"),
+        ("(length (keys {0.0 1 -0.0 2}))", "2"),
+        ("(get {0.0 1 -0.0 2} -0.0)", "2"),
+        ("(get {0.0 1 -0.0 2} 0.0)", "1"),
+        ("(contains? #{0.0} -0.0)", "false"),
+        ("(contains? (list 0.0) -0.0)", "false"),
+        ("(index-of (list 0.0 -0.0) -0.0)", "1"),
+        ("(match -0.0 (0.0 :zero) (_ :other))", "other"),
+        ("(get {1.5 :a} 1.5)", "a"),
+        ("(contains? (list nan) nan)", "true"),
+        ("(= nan nan)", "false"),
+        ("(= 0.0 -0.0)", "true"),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
@@ -0,0 +1,66 @@
+//! Destructures values with `match`.
+
+mod common;
+
+use common::{evaluate_error, evaluate_program};
+
+#[test]
+fn patterns() {
+    let cases = [
+        ("(match (list 1 2) ((a b) (+ a b)))", "3"),
+        ("(match (list 1 2 3) ((a &rest r) r))", "(2 3 )"),
+        (
+            "(match (list 1 (list 2 3)) ((a (b c)) (list a b c)))",
+            "(1 2 3 )",
+        ),
+        ("(match (list 1 2) ((1 x) x))", "2"),
+        ("(match :k (:j 1) (:k 2))", "2"),
+        ("(match \"s\" (\"s\" 1))", "1"),
+        ("(match 5 (1 :one) (_ :other))", "other"),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn fall_through() {
+    let cases = [
+        // A clause whose pattern doesn't match is skipped.
+        (
+            "(match (list 1 2) ((a) :one) ((a b c) :three) ((a b) :two))",
+            "two",
+        ),
+        (
+            "(match (list 1 2) ((2 x) :first) ((1 x) :second))",
+            "second",
+        ),
+        // A clause whose guard fails is skipped too.
+        (
+            "(match 3 (x :when (> x 5) :big) (x :when (> x 1) :medium) (_ :small))",
+            "medium",
+        ),
+        ("(match 0 (x :when (> x 5) :big) (_ :small))", "small"),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn errors() {
+    // The unmatched value is reported at its location.
+    let err = evaluate_error("(match (list 1 2) ((a) a) ((a b c) a))");
+
+    assert_eq!(err["code"], "E0000");
+    assert_eq!(
+        err["message"],
+        "non-exhaustive 'match' : no pattern matches (1 2)"
+    );
+    assert_eq!(err["frames"][0]["location"]["column"], 8);
+
+    assert_eq!(evaluate_program("(match 3 (x :when :a 1))"), "\"E0006\"");
+    assert_eq!(evaluate_program("(match 3 (x :when))"), "\"E0002\"");
+}
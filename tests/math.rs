@@ -0,0 +1,108 @@
+//! Evaluates the arithmetic and comparison forms.
+
+mod common;
+
+use common::{evaluate_error, evaluate_program};
+
+#[test]
+fn arithmetic() {
+    let cases = [
+        ("(+ 1 2 3)", "6"),
+        ("(+)", "0"),
+        ("(*)", "1"),
+        ("(- 5)", "-5"),
+        ("(- 10 4 3)", "3"),
+        ("(* 2 3 4)", "24"),
+        ("(/ 7 2)", "3"),
+        ("(mod -7 3)", "2"),
+        ("(rem -7 3)", "-1"),
+        ("(abs -3)", "3"),
+        ("(min 3 1 2)", "1"),
+        ("(max 1 2)", "2"),
+        // Integers are promoted to Floats.
+        ("(+ 1 2.5)", "3.5"),
+        ("(/ 7.0 2)", "3.5"),
+        ("(min 3 1.5 2)", "1.5"),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn comparison() {
+    let cases = [
+        ("(= 1 1 1)", "true"),
+        ("(= 1 1.0)", "true"),
+        ("(/= 1 2)", "true"),
+        ("(< 1 2 3)", "true"),
+        ("(< 1 3 2)", "false"),
+        ("(<= 1 1 0)", "false"),
+        ("(> 3 2)", "true"),
+        ("(>= 2 2)", "true"),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn errors() {
+    assert_eq!(evaluate_program("(+ 1 :a)"), "\"E0006\"");
+    assert_eq!(evaluate_program("(< 1 \"a\")"), "\"E0006\"");
+    assert_eq!(evaluate_program("(abs)"), "\"E0005\"");
+
+    // Divisions by zero are located at the caller.
+    let err = evaluate_error("(+ 1\n   (mod 1 0))");
+    assert_eq!(err["code"], "E0008");
+    assert_eq!(err["frames"][0]["name"], "mod");
+    assert_eq!(err["frames"][0]["location"]["line"], 2);
+    assert_eq!(err["frames"][0]["location"]["column"], 5);
+}
+
+#[test]
+fn promotion() {
+    let cases = [
+        // Integers overflowing 64 bits are promoted to bignums.
+        ("(+ 9223372036854775807 1)", "9223372036854775808"),
+        ("(- -9223372036854775808 1)", "-9223372036854775809"),
+        (
+            "(* 9223372036854775807 9223372036854775807)",
+            "85070591730234615847396907784232501249",
+        ),
+        ("(abs -9223372036854775808)", "9223372036854775808"),
+        // Bignums fitting in 64 bits are Integers again.
+        ("(- (+ 9223372036854775807 1) 1)", "9223372036854775807"),
+        ("(< 9223372036854775807 (+ 9223372036854775807 1))", "true"),
+        ("(= (+ 9223372036854775807 1) 9223372036854775808)", "true"),
+        (
+            "(= (+ 0.0 (+ 9223372036854775807 1)) 9223372036854775808.0)",
+            "true",
+        ),
+        // Literals too large for 64 bits are read as bignums.
+        ("0xFFFFFFFF", "4294967295"),
+        ("0xFFFFFFFFFFFFFFFFFF", "4722366482869645213695"),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn division_by_zero() {
+    let cases = [
+        "(/ 1 0)",
+        "(mod 1 0)",
+        "(rem 1 0)",
+        "(/ 1.0 0)",
+        "(/ 1.0 0.0)",
+        "(/ (+ 9223372036854775807 1) 0)",
+    ];
+
+    for source in cases {
+        assert_eq!(evaluate_program(source), "\"E0008\"", "{}", source);
+    }
+}
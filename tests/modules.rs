@@ -0,0 +1,107 @@
+//! Loads modules with `require` and `import`.
+
+mod common;
+
+use common::{create_scope, evaluate_error, evaluate_program};
+
+use neolisp::nl::interpreter::*;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Writes modules in a temporary directory, unique to a test.
+fn module_directory(name: &str, modules: &[(&str, &str)]) -> PathBuf {
+    let directory =
+        std::env::temp_dir().join(format!("neolisp-modules-{}-{}", std::process::id(), name));
+
+    for (path, source) in modules {
+        let path = directory.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, source).unwrap();
+    }
+
+    directory
+}
+
+/// Prefixes a program with the definition of the load path.
+fn with_load_path(directory: &Path, program: &str) -> String {
+    format!("(def *load-path* (list {:?})) {}", directory, program)
+}
+
+#[test]
+fn exports() {
+    let directory = module_directory(
+        "exports",
+        &[(
+            "math.nl",
+            "(def hidden 1) (defndynamic sq (x) (* x x)) (export sq)",
+        )],
+    );
+
+    let cases = [
+        ("(require math) (math/sq 3)", "9"),
+        ("(require math :as m) (m/sq 4)", "16"),
+        ("(import math sq) (sq 5)", "25"),
+        ("(require math) math/hidden", "\"E0003\""),
+        ("(import math hidden)", "\"E0010\""),
+        ("(require missing)", "\"E0010\""),
+    ];
+
+    for (source, expected) in cases {
+        let program = with_load_path(&directory, source);
+        assert_eq!(evaluate_program(&program), expected, "{}", source);
+    }
+
+    fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn evaluated_once() {
+    let directory = module_directory("once", &[("util/once.nl", "(def v 3)")]);
+    let mut scope = create_scope();
+
+    let program = with_load_path(&directory, "(require util.once) util.once/v");
+    let value = evaluate_source(&mut scope, "<program>", &program)
+        .ok()
+        .unwrap();
+    assert_eq!(value.to_string(), "3");
+
+    // The module changed on disk isn't evaluated again.
+    fs::write(directory.join("util/once.nl"), "(def v 4)").unwrap();
+
+    let program = "(require util.once) util.once/v";
+    let value = evaluate_source(&mut scope, "<program>", program)
+        .ok()
+        .unwrap();
+    assert_eq!(value.to_string(), "3");
+
+    fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn circular_imports() {
+    let directory = module_directory(
+        "circular",
+        &[
+            ("a.nl", "(require b) (def a-value 1)"),
+            ("b.nl", "(require a) (def b-value 2)"),
+        ],
+    );
+
+    let err = evaluate_error(&with_load_path(&directory, "(require a)"));
+    let a = directory.join("a.nl");
+    let b = directory.join("b.nl");
+
+    assert_eq!(err["code"], "E0010");
+    assert_eq!(
+        err["message"],
+        format!(
+            "circular import of module 'a' : '{}' -> '{}' -> '{}'",
+            a.display(),
+            b.display(),
+            a.display()
+        )
+    );
+
+    fs::remove_dir_all(directory).unwrap();
+}
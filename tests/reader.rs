@@ -0,0 +1,75 @@
+//! Reads the numeric literals, and evaluates the rationals.
+
+mod common;
+
+use common::evaluate_program;
+
+use neolisp::nl::core::diagnostic::error_to_json;
+use neolisp::nl::interpreter::*;
+
+use serde_json::Value;
+
+/// Reads a source which must be invalid, then gets its error in JSON.
+fn read_error(source: &str) -> Value {
+    match read_source("<program>", source) {
+        Ok(_) => panic!("{} was read", source),
+        Err(err) => error_to_json(&err),
+    }
+}
+
+#[test]
+fn literals() {
+    let cases = [
+        ("-5", "-5"),
+        ("+3", "3"),
+        ("-0x10", "-16"),
+        ("0b101", "5"),
+        ("(= 1e10 10000000000.0)", "true"),
+        ("(= 1.5e-3 0.0015)", "true"),
+        ("(= .5 0.5)", "true"),
+        ("inf", "inf"),
+        ("(= nan nan)", "false"),
+        ("1/2", "1/2"),
+        ("-2/4", "-1/2"),
+        ("4/2", "2"),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn errors() {
+    let cases = [
+        ("3/0", "a non-zero denominator in a rational literal", 3),
+        ("1e400", "a float literal within the range of a Float", 5),
+    ];
+
+    for (source, expected, index) in cases {
+        let err = read_error(source);
+
+        assert_eq!(err["code"], "E0001", "{}", source);
+        assert_eq!(err["data"]["expected"][0], expected, "{}", source);
+        assert_eq!(err["frames"][0]["location"]["index"], index, "{}", source);
+    }
+}
+
+#[test]
+fn rationals() {
+    let cases = [
+        ("(+ 1/2 1/3)", "5/6"),
+        ("(* 2/4 2)", "1"),
+        ("(- 1/2 1)", "-1/2"),
+        ("(/ 1 3/4)", "4/3"),
+        ("(< 1/3 1/2)", "true"),
+        ("(= 1/2 0.5)", "true"),
+        ("(= (+ 1/2 0.5) 1.0)", "true"),
+        ("(/ 1/2 0)", "\"E0008\""),
+        ("(/ 1 0/1)", "\"E0008\""),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
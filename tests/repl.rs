@@ -0,0 +1,103 @@
+//! Drives `neolisp repl` through its standard input.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Runs the REPL on lines of input, then gets its output. The history is
+/// kept in a temporary home directory.
+fn run_repl(input: &str) -> String {
+    let home = std::env::temp_dir().join(format!("neolisp-repl-{}", std::process::id()));
+    std::fs::create_dir_all(&home).unwrap();
+
+    let mut child = Command::new(env!("CARGO_BIN_EXE_neolisp"))
+        .arg("repl")
+        .env("HOME", &home)
+        .env("NO_COLOR", "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("can't start neolisp");
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input.as_bytes())
+        .unwrap();
+
+    let output = child.wait_with_output().unwrap();
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+/// Gets the lines of an output starting with `=>` or `error`.
+fn results(output: &str) -> Vec<&str> {
+    output
+        .lines()
+        .filter(|x| x.starts_with("=>") || x.starts_with("error"))
+        .collect()
+}
+
+#[test]
+fn errors_close_levels() {
+    let file = std::env::temp_dir().join(format!("neolisp-repl-{}.nl", std::process::id()));
+    std::fs::write(&file, "(let (z 3) (undefined-thing))").unwrap();
+
+    let output = run_repl(&format!(
+        "(let (x 1) (undefined-thing))\nx\n\
+         :time (let (y 2) (undefined-thing))\ny\n\
+         :load {}\nz\n\
+         (def w 4) w\n",
+        file.display()
+    ));
+
+    assert_eq!(
+        results(&output),
+        vec![
+            "error : unbound symbol 'undefined-thing'",
+            "error : unbound symbol 'x'",
+            "error : unbound symbol 'undefined-thing'",
+            "error : unbound symbol 'y'",
+            "error : unbound symbol 'undefined-thing'",
+            "error : unbound symbol 'z'",
+            "=> nil",
+            "=> 4",
+        ],
+        "{}",
+        output
+    );
+
+    std::fs::remove_file(file).unwrap();
+}
+
+#[test]
+fn meta_commands() {
+    let output = run_repl(
+        "(defndynamic sq (x) (* x x))\n\
+         :describe sq\n\
+         :env\n\
+         :ast (a 1)\n\
+         :time (+ 1 2)\n\
+         (def y 1)\n\
+         :reset\n\
+         y\n\
+         :describe\n\
+         :quit\n\
+         (def z 1)\n",
+    );
+
+    for expected in [
+        "'sq' : DynamicForm\nArguments : (x)\nBody :\n  (* x x)\nDefined in file '<repl:1>', at 1:14\n",
+        "\nsq             DynamicForm\n",
+        "\n+              EvalForm\n",
+        "List : \n  Symbol : a\n  Integer : 1\n",
+        "=> 3\nElapsed : ",
+        "Scope reset.\nerror : unbound symbol 'y'\n",
+        "Usage : :describe <sym>\n",
+    ] {
+        assert!(output.contains(expected), "{}\n{}", expected, output);
+    }
+
+    // Nothing is evaluated after `:quit`.
+    assert_eq!(results(&output).last(), Some(&"error : unbound symbol 'y'"));
+}
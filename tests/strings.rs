@@ -0,0 +1,44 @@
+//! Evaluates the string forms, indexed by Unicode scalar values.
+
+mod common;
+
+use common::{evaluate_error, evaluate_program};
+
+#[test]
+fn forms() {
+    let cases = [
+        ("(string-length \"héllo\")", "5"),
+        ("(substring \"héllo\" 1 3)", "él"),
+        ("(string-append \"a\" \"b\" \"c\")", "abc"),
+        ("(length (split \"a,b,,c\" \",\"))", "4"),
+        ("(join (list \"a\" \"b\") \"-\")", "a-b"),
+        ("(trim \"  x \")", "x"),
+        ("(upcase \"abé\")", "ABÉ"),
+        ("(downcase \"ABC\")", "abc"),
+        ("(starts-with? \"hello\" \"he\")", "true"),
+        ("(ends-with? \"hello\" \"he\")", "false"),
+        ("(string-index \"héllo\" \"l\")", "2"),
+        ("(string-index \"abc\" \"z\")", "nil"),
+        ("(replace \"a-b-c\" \"-\" \"+\")", "a+b+c"),
+        ("(char-at \"héllo\" 1)", "é"),
+        ("(list->string (string->list \"ab\"))", "ab"),
+        ("(char->integer #a)", "97"),
+        ("(integer->char 955)", "λ"),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn errors() {
+    // Indices out of range are reported, rather than slicing bytes.
+    let err = evaluate_error("(substring \"héllo\" 2 6)");
+    assert_eq!(err["code"], "E0007");
+    assert_eq!(err["data"].to_string(), r#"{"index":6,"start":0,"end":6}"#);
+
+    assert_eq!(evaluate_program("(char-at \"abc\" 3)"), "\"E0007\"");
+    assert_eq!(evaluate_program("(integer->char 55296)"), "\"E0000\"");
+    assert_eq!(evaluate_program("(string-length 1)"), "\"E0006\"");
+}
@@ -0,0 +1,93 @@
+//! Runs recursive loops much deeper than the Rust stack allows without tail
+//! calls, with the interpreter and with the virtual machine.
+
+mod common;
+
+use common::{create_scope, describe};
+
+use neolisp::nl::interpreter::bytecode::*;
+use neolisp::nl::interpreter::*;
+
+/// Loops whose recursive call is in tail position through each form passing
+/// the tail position on.
+const LOOPS: &[(&str, &str)] = &[
+    (
+        "(defndynamic count (n) (if (= n 0) :done (do (count (- n 1)))))
+         (count 20000)",
+        "done",
+    ),
+    (
+        "(def count (fn (n acc) (cond ((= n 0) acc) (else (let (m (- n 1)) (count m (+ acc 2)))))))
+         (count 20000 0)",
+        "40000",
+    ),
+    (
+        "(defndynamic count (n) (when (> n 0) (count (- n 1))))
+         (defndynamic count-down (n) (unless (= n 0) (count (- n 1))))
+         (count-down 20000)",
+        "nil",
+    ),
+    (
+        "(defndynamic count (n) (case (mod n 2) (0 (if (= n 0) :even (count (- n 1)))) (else (count (- n 1)))))
+         (count 20001)",
+        "even",
+    ),
+    (
+        "(defmacro again (n) `(count (- ,n 1)))
+         (defndynamic count (n) (if (= n 0) :done (again n)))
+         (count 20000)",
+        "done",
+    ),
+    (
+        "(defndynamic even? (n) (if (= n 0) true (odd? (- n 1))))
+         (defndynamic odd? (n) (if (= n 0) false (even? (- n 1))))
+         (and (even? 20000) (odd? 20001))",
+        "true",
+    ),
+    (
+        "(defndynamic next (n) ((fn (m) (count m)) (- n 1)))
+         (defndynamic count (n) (if (= n 0) :done (next n)))
+         (count 20000)",
+        "done",
+    ),
+    // The callee still sees the variables of its caller.
+    (
+        "(defndynamic total () (+ n acc))
+         (defndynamic count (n acc) (if (= n 0) (let (acc (* acc 2)) (total)) (count (- n 1) (+ acc 1))))
+         (count 20000 0)",
+        "40000",
+    ),
+];
+
+/// Evaluates a program with the interpreter or the virtual machine, then
+/// describes the value of its last expression.
+fn evaluate_program(source: &str, compiled: bool) -> String {
+    let mut scope = create_scope();
+
+    describe(if compiled {
+        evaluate_source_compiled(&mut scope, "<program>", source)
+    } else {
+        evaluate_source(&mut scope, "<program>", source)
+    })
+}
+
+const MILLION: &str = "(defndynamic count (n acc) (if (= n 0) acc (count (- n 1) (+ acc 1))))
+     (count 1000000 0)";
+
+#[test]
+fn million_iterations() {
+    assert_eq!(evaluate_program(MILLION, false), "1000000");
+}
+
+#[test]
+fn million_iterations_compiled() {
+    assert_eq!(evaluate_program(MILLION, true), "1000000");
+}
+
+#[test]
+fn tail_positions() {
+    for (source, expected) in LOOPS {
+        assert_eq!(&evaluate_program(source, false), expected, "{}", source);
+        assert_eq!(&evaluate_program(source, true), expected, "{}", source);
+    }
+}
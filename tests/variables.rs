@@ -0,0 +1,50 @@
+//! Defines and mutates variables with `def`, `set!` and `defconst`.
+
+mod common;
+
+use common::{evaluate_error, evaluate_program};
+
+#[test]
+fn definitions() {
+    let cases = [
+        ("(def x 1) x", "1"),
+        ("(def x 1) (def x 2) x", "2"),
+        // `def` binds a global variable, even from a nested level.
+        ("(let (a 1) (def g 2)) g", "2"),
+        ("(defconst c 1) c", "1"),
+        ("(def x 1) (set! x 2) x", "2"),
+        // `set!` mutates the nearest binding.
+        ("(def x 1) (let (x 2) (set! x 3)) x", "1"),
+        ("(def x 1) (let (z 2) (set! x 3)) x", "3"),
+        ("(set! y 1)", "\"E0003\""),
+        ("(def x)", "\"E0005\""),
+        ("(def 1 2)", "\"E0002\""),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}
+
+#[test]
+fn constants() {
+    for source in ["(defconst c 1) (set! c 2)", "(defconst c 1) (def c 2)"] {
+        let err = evaluate_error(source);
+
+        assert_eq!(err["code"], "E0004", "{}", source);
+        assert_eq!(err["data"]["name"], "c", "{}", source);
+
+        // Both the definition and the mutation are named.
+        let message = err["message"].as_str().unwrap();
+        assert!(
+            message.contains("defined in file '<program>', at 1:11"),
+            "{}",
+            message
+        );
+        assert!(
+            message.contains("mutated in file '<program>'"),
+            "{}",
+            message
+        );
+    }
+}
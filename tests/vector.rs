@@ -0,0 +1,50 @@
+//! Reads and evaluates vector literals, and builds vectors with the vector
+//! forms.
+
+mod common;
+
+use common::{create_scope, describe, evaluate_program};
+
+use neolisp::nl::interpreter::bytecode::*;
+
+#[test]
+fn literals() {
+    let cases = [
+        // The elements of a vector literal aren't evaluated.
+        ("[1 (+ 1 1) 3]", "[1 (+ 1 1) 3]"),
+        ("[a b]", "[a b]"),
+        ("(vector-ref [(undefined) 2] 0)", "(undefined )"),
+        ("(vector-ref [[1 2] 3] 0)", "[1 2]"),
+        // Unlike the ones of map and set literals, and of the vector form.
+        ("(vector 1 (+ 1 1) 3)", "[1 2 3]"),
+        ("(get {:a (+ 1 1)} :a)", "2"),
+        ("(contains? #{(+ 1 1)} 2)", "true"),
+        ("(is-vector? [])", "true"),
+    ];
+
+    // The virtual machine keeps the literals as constants.
+    for (source, expected) in cases {
+        let compiled = evaluate_source_compiled(&mut create_scope(), "<program>", source);
+
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+        assert_eq!(describe(compiled), expected, "{}", source);
+    }
+}
+
+#[test]
+fn forms() {
+    let cases = [
+        ("(vector-length [1 2 3])", "3"),
+        ("(vector-ref [1 2 3] 2)", "3"),
+        ("(vector-set [1 2 3] 0 :x)", "[:x 2 3]"),
+        ("(vector-push [1] 2 3)", "[1 2 3]"),
+        ("(vector->list [1 2])", "(1 2 )"),
+        ("(list->vector (list 1 2))", "[1 2]"),
+        ("(vector-ref [1 2] 2)", "\"E0007\""),
+        ("(vector-ref (list 1) 0)", "\"E0006\""),
+    ];
+
+    for (source, expected) in cases {
+        assert_eq!(evaluate_program(source), expected, "{}", source);
+    }
+}